@@ -0,0 +1,113 @@
+extern crate criterion;
+extern crate ctrl_z;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ctrl_z::ReadToCtrlZ;
+use std::io::BufRead;
+use std::io::Read;
+
+/// The number of bytes in each benchmarked input.
+const LEN: usize = 1024 * 1024;
+
+/// Creates an input of `LEN` bytes with a `0x1A` byte at `position`, if any.
+fn input(position: Option<usize>) -> Vec<u8> {
+    let mut input = vec![b'a'; LEN];
+    if let Some(position) = position {
+        input[position] = b'\x1a';
+    }
+    input
+}
+
+/// Reads `reader` to the end through `Read`, copying into a caller-provided buffer.
+fn read<R>(mut reader: R) -> usize
+where
+    R: Read,
+{
+    let mut buf = [0; 8 * 1024];
+    let mut total = 0;
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            return total;
+        }
+        total += n;
+    }
+}
+
+/// Reads `reader` to the end through `BufRead`, without copying.
+fn fill_buf<R>(mut reader: R) -> usize
+where
+    R: BufRead,
+{
+    let mut total = 0;
+    loop {
+        let n = reader.fill_buf().unwrap().len();
+        if n == 0 {
+            return total;
+        }
+        reader.consume(n);
+        total += n;
+    }
+}
+
+fn bench_read(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("read");
+    group.throughput(Throughput::Bytes(LEN as u64));
+    let positions = [
+        ("start", Some(0)),
+        ("middle", Some(LEN / 2)),
+        ("end", Some(LEN - 1)),
+        ("absent", None),
+    ];
+    for &(name, position) in positions.iter() {
+        let input = input(position);
+        group.bench_with_input(BenchmarkId::new("Read", name), &input, |bencher, input| {
+            bencher.iter(|| read(ReadToCtrlZ::new(&input[..])))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("BufRead", name),
+            &input,
+            |bencher, input| bencher.iter(|| fill_buf(ReadToCtrlZ::new(&input[..]))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_bytes(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("bytes");
+    group.throughput(Throughput::Bytes(LEN as u64));
+    let input = input(None);
+    group.bench_with_input("Read::bytes", &input, |bencher, input| {
+        bencher.iter(|| ReadToCtrlZ::new(&input[..]).bytes().count())
+    });
+    group.bench_with_input("logical_bytes", &input, |bencher, input| {
+        bencher.iter(|| ReadToCtrlZ::new(&input[..]).logical_bytes().count())
+    });
+    group.finish();
+}
+
+fn bench_read_to_end(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("read_to_end");
+    group.throughput(Throughput::Bytes(LEN as u64));
+    let input = input(Some(LEN - 1));
+    group.bench_with_input("Read::read_to_end", &input, |bencher, input| {
+        bencher.iter(|| {
+            let mut output = Vec::new();
+            ReadToCtrlZ::new(&input[..])
+                .read_to_end(&mut output)
+                .unwrap()
+        })
+    });
+    group.bench_with_input("read_to_end_buffered", &input, |bencher, input| {
+        bencher.iter(|| {
+            let mut output = Vec::new();
+            ReadToCtrlZ::new(&input[..])
+                .read_to_end_buffered(&mut output)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_read, bench_bytes, bench_read_to_end);
+criterion_main!(benches);
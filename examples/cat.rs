@@ -0,0 +1,43 @@
+//! Prints the contents of a file, or of standard input, up to the first `0x1A` byte.
+//!
+//! ```sh
+//! cargo run --example cat -- legacy.txt
+//! cargo run --example cat < legacy.txt
+//! ```
+
+extern crate clap;
+extern crate ctrl_z;
+
+use clap::Arg;
+use clap::Command;
+use ctrl_z::ReadToCtrlZ;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::process;
+
+/// Copies everything before the `0x1A` byte from `inner` to standard output.
+fn cat<R>(inner: R) -> io::Result<u64>
+where
+    R: Read,
+{
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    io::copy(&mut ReadToCtrlZ::new(inner), &mut stdout)
+}
+
+fn main() {
+    let matches = Command::new("cat")
+        .about("Prints a file up to its 0x1A end-of-file marker")
+        .arg(Arg::new("file").help("The file to print. Reads standard input if omitted."))
+        .get_matches();
+
+    let result = match matches.get_one::<String>("file") {
+        Some(path) => File::open(path).and_then(cat),
+        None => cat(io::stdin()),
+    };
+    if let Err(error) = result {
+        eprintln!("cat: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,253 @@
+use std::cmp;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+use std::iter;
+use ReadToCtrlZ;
+
+/// The default capacity of a `BufReadToCtrlZ`'s buffer.
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// A buffered reader to read until a `0x1A` byte.
+///
+/// This is a [`ReadToCtrlZ`] combined with an internal buffer, providing [`BufRead`] for inner
+/// readers that only implement [`Read`]. The buffer is only ever filled with bytes preceding the
+/// `0x1A` byte, so it never holds bytes following it. Unlike a [`BufReader`](std::io::BufReader),
+/// the buffer can be grown after construction using
+/// [`grow_buffer()`](BufReadToCtrlZ::grow_buffer()).
+///
+/// # Example
+/// ```
+/// use ctrl_z::BufReadToCtrlZ;
+/// use std::io::BufRead;
+/// #
+/// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+/// # trait AsSlice {
+/// #     fn as_slice(&self) -> &[u8];
+/// # }
+/// #
+/// # impl AsSlice for [u8] {
+/// #     fn as_slice(&self) -> &[u8] {
+/// #         self
+/// #     }
+/// # }
+///
+/// let mut reader = BufReadToCtrlZ::new(b"foo\nbar\x1abaz".as_slice());
+///
+/// let lines = reader.lines().map(|line| line.unwrap()).collect::<Vec<_>>();
+/// assert_eq!(lines, ["foo", "bar"]);
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct BufReadToCtrlZ<R> {
+    /// The internal reader being read.
+    inner: ReadToCtrlZ<R>,
+    /// The internal buffer. Its entire length is available for filling.
+    buf: Vec<u8>,
+    /// The position of the first unconsumed byte in `buf`.
+    pos: usize,
+    /// The number of bytes in `buf` that have been filled.
+    filled: usize,
+    /// Whether the space added by `grow_buffer()` should be filled by the next `fill_buf()`,
+    /// despite bytes still being buffered.
+    top_up: bool,
+}
+
+impl<R> BufReadToCtrlZ<R> {
+    /// Creates a new `BufReadToCtrlZ` with a default buffer capacity of 8 KiB.
+    pub fn new(inner: R) -> Self {
+        BufReadToCtrlZ::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Creates a new `BufReadToCtrlZ` with the specified buffer capacity.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        BufReadToCtrlZ {
+            inner: ReadToCtrlZ::new(inner),
+            buf: iter::repeat(0).take(capacity).collect(),
+            pos: 0,
+            filled: 0,
+            top_up: false,
+        }
+    }
+
+    /// Returns the capacity of the internal buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Grows the internal buffer by `additional` bytes.
+    ///
+    /// Any bytes currently buffered are kept, and moved to the start of the buffer. If any are
+    /// buffered, the next call to [`fill_buf()`](BufRead::fill_buf()) reads into the space
+    /// following them before returning, so a record that did not fit in the buffer can be
+    /// completed. This is useful when parsing records larger than the buffer's initial capacity.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::BufReadToCtrlZ;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = BufReadToCtrlZ::with_capacity(128, b"foo\x1a".as_slice());
+    /// reader.grow_buffer(128);
+    ///
+    /// assert_eq!(reader.capacity(), 256);
+    /// ```
+    pub fn grow_buffer(&mut self, additional: usize) {
+        let len = self.filled - self.pos;
+        for i in 0..len {
+            self.buf[i] = self.buf[self.pos + i];
+        }
+        self.pos = 0;
+        self.filled = len;
+        self.top_up = len > 0;
+        self.buf.extend(iter::repeat(0).take(additional));
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader may cause bytes to be missed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner.inner
+    }
+
+    /// Unwraps this `BufReadToCtrlZ`, returning the inner reader.
+    ///
+    /// Any buffered bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.inner.inner
+    }
+}
+
+impl<R> Read for BufReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // Large reads bypass the internal buffer when it is empty.
+        if self.pos == self.filled && buf.len() >= self.buf.len() {
+            return self.inner.read(buf);
+        }
+        let n = try!(try!(self.fill_buf()).read(buf));
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R> BufRead for BufReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.pos == self.filled {
+            self.filled = try!(self.inner.read(&mut self.buf));
+            self.pos = 0;
+            self.top_up = false;
+        } else if self.top_up {
+            self.filled += try!(self.inner.read(&mut self.buf[self.filled..]));
+            self.top_up = false;
+        }
+        Ok(&self.buf[self.pos..self.filled])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = cmp::min(self.pos + amount, self.filled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufReadToCtrlZ;
+    use std::io::BufRead;
+    use std::io::Read;
+
+    #[test]
+    fn fill_buf() {
+        let mut reader = BufReadToCtrlZ::with_capacity(4, b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foob");
+        reader.consume(4);
+        assert_ok_eq!(reader.fill_buf(), b"ar");
+        reader.consume(2);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn read() {
+        let mut output = String::new();
+        let mut reader = BufReadToCtrlZ::with_capacity(2, b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn read_after_fill_buf() {
+        let mut output = [0; 2];
+        let mut reader = BufReadToCtrlZ::with_capacity(4, b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foob");
+        reader.consume(1);
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_eq!(&output, b"oo");
+    }
+
+    #[test]
+    fn grow_buffer() {
+        let mut reader = BufReadToCtrlZ::with_capacity(4, b"foobarbaz\x1a" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foob");
+        reader.grow_buffer(4);
+        assert_eq!(reader.capacity(), 8);
+
+        // Buffered bytes are kept, and the new space is filled following them.
+        assert_ok_eq!(reader.fill_buf(), b"foobarba");
+        reader.consume(8);
+        assert_ok_eq!(reader.fill_buf(), b"z");
+    }
+
+    #[test]
+    fn grow_buffer_after_consume() {
+        let mut reader = BufReadToCtrlZ::with_capacity(4, b"foobarbaz\x1a" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foob");
+        reader.consume(2);
+        reader.grow_buffer(4);
+
+        // The unconsumed bytes are moved to the start, leaving room for six more.
+        assert_ok_eq!(reader.fill_buf(), b"obarbaz");
+        reader.consume(7);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn grow_buffer_empty() {
+        let mut reader = BufReadToCtrlZ::with_capacity(4, b"foobarbaz\x1a" as &[u8]);
+
+        reader.grow_buffer(4);
+
+        assert_ok_eq!(reader.fill_buf(), b"foobarba");
+    }
+
+    #[test]
+    fn lines() {
+        let reader = BufReadToCtrlZ::new(b"foo\nbar\x1abaz\n" as &[u8]);
+
+        let lines = reader.lines().map(|line| line.unwrap()).collect::<Vec<_>>();
+        assert_eq!(lines, ["foo", "bar"]);
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::iter;
+#[cfg(feature = "fused")]
+use std::iter::FusedIterator;
+use ReadToCtrlZ;
+
+/// The size of the internal buffer of a [`LogicalBytes`].
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// An iterator over the bytes preceding a `0x1A` byte.
+///
+/// Unlike [`Read::bytes()`], which reads a single byte at a time, this reads into an internal
+/// buffer, so the cost of each read is amortized across many bytes. Iteration ends at the `0x1A`
+/// byte or the end of the inner reader. After an error has been yielded, or once iteration has
+/// ended, `None` is always returned.
+///
+/// This type is created using [`ReadToCtrlZ::logical_bytes()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let bytes = ReadToCtrlZ::new(&b"foo\x1abar"[..])
+///     .logical_bytes()
+///     .map(|byte| byte.unwrap())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(bytes, b"foo");
+/// ```
+///
+/// [`ReadToCtrlZ::logical_bytes()`]: crate::ReadToCtrlZ::logical_bytes()
+pub struct LogicalBytes<R> {
+    /// The internal reader being read.
+    inner: ReadToCtrlZ<R>,
+    /// Bytes read from the internal reader.
+    buffer: Vec<u8>,
+    /// The position of the next byte to yield within `buffer`.
+    pos: usize,
+    /// The number of bytes within `buffer` that were read.
+    len: usize,
+    /// Whether or not iteration has finished.
+    finished: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `LogicalBytes`, iterating over the bytes preceding the `0x1A` byte.
+    pub fn logical_bytes(self) -> LogicalBytes<R> {
+        LogicalBytes {
+            inner: self,
+            buffer: iter::repeat(0).take(BUFFER_SIZE).collect(),
+            pos: 0,
+            len: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<R> LogicalBytes<R> {
+    /// Unwraps this `LogicalBytes`, returning the underlying `ReadToCtrlZ`.
+    ///
+    /// Any bytes that have been buffered but not yet yielded are lost.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> Iterator for LogicalBytes<R>
+where
+    R: Read,
+{
+    type Item = Result<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos == self.len {
+            if self.finished {
+                return None;
+            }
+            match self.inner.read(&mut self.buffer) {
+                Ok(0) => {
+                    self.finished = true;
+                    return None;
+                }
+                Ok(n) => {
+                    self.pos = 0;
+                    self.len = n;
+                }
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        let byte = self.buffer[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+#[cfg(feature = "fused")]
+#[allow(clippy::incompatible_msrv)]
+impl<R> FusedIterator for LogicalBytes<R> where R: Read {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    #[cfg(feature = "fused")]
+    assert_impl_all!(super::LogicalBytes<&'static [u8]>: std::iter::FusedIterator);
+
+    #[test]
+    fn stops_at_ctrl_z() {
+        let mut bytes = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).logical_bytes();
+
+        assert_some_eq!(bytes.next().map(Result::unwrap), b'f');
+        assert_some_eq!(bytes.next().map(Result::unwrap), b'o');
+        assert_some_eq!(bytes.next().map(Result::unwrap), b'o');
+        assert_none!(bytes.next());
+        assert_none!(bytes.next());
+    }
+
+    #[test]
+    fn no_ctrl_z() {
+        let bytes = ReadToCtrlZ::new(b"foobar" as &[u8])
+            .logical_bytes()
+            .map(|byte| byte.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bytes, b"foobar");
+    }
+
+    #[test]
+    fn longer_than_buffer() {
+        let mut input = vec![b'a'; 3 * super::BUFFER_SIZE + 1];
+        input.push(b'\x1a');
+        input.push(b'b');
+
+        let bytes = assert_ok!(ReadToCtrlZ::new(&input[..])
+            .logical_bytes()
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(bytes.len(), 3 * super::BUFFER_SIZE + 1);
+    }
+
+    #[test]
+    fn error_once() {
+        let mut count = 0;
+        let mut bytes = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            count += 1;
+            match count {
+                1 => {
+                    buf[0] = b'a';
+                    Ok(1)
+                }
+                2 => Err(Error::new(ErrorKind::Interrupted, "foo")),
+                _ => Err(Error::new(ErrorKind::Other, "bar")),
+            }
+        }))
+        .logical_bytes();
+
+        assert_some_eq!(bytes.next().map(Result::unwrap), b'a');
+        assert_eq!(
+            assert_err!(assert_some!(bytes.next())).kind(),
+            ErrorKind::Other
+        );
+        assert_none!(bytes.next());
+    }
+}
@@ -0,0 +1,143 @@
+use bytes_crate::BytesMut;
+use std::io::Read;
+use std::io::Result;
+use BufReadToCtrlZ;
+use ReadToCtrlZ;
+
+/// Reads up to `max` bytes from `reader` directly into the end of `dst`.
+///
+/// `dst` is extended in place, and truncated afterward to the bytes actually read, so nothing is
+/// copied through an intermediate buffer and no bytes beyond those read are left in `dst`.
+fn read_into<R>(reader: &mut R, dst: &mut BytesMut, max: usize) -> Result<usize>
+where
+    R: Read,
+{
+    let start = dst.len();
+    dst.resize(start + max, 0);
+    let result = reader.read(&mut dst[start..]);
+    let n = *result.as_ref().unwrap_or(&0);
+    dst.truncate(start + n);
+    result
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Appends up to `max` bytes preceding the `0x1A` byte to `dst`, returning the number of
+    /// bytes appended.
+    ///
+    /// Bytes are read directly into `dst`'s storage. This behaves exactly like a single call to
+    /// [`read()`](Read::read()) with a buffer of length `max`, so the `0x1A` byte and anything
+    /// following it are never appended.
+    ///
+    /// This method is only available with the `bytes` feature enabled.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate bytes;
+    /// # extern crate ctrl_z;
+    /// use bytes::BytesMut;
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// # fn main() {
+    /// let mut reader = ReadToCtrlZ::new(&b"foo\x1abar"[..]);
+    /// let mut dst = BytesMut::new();
+    ///
+    /// assert_eq!(reader.read_into_bytes(&mut dst, 16).unwrap(), 3);
+    /// assert_eq!(&dst[..], b"foo");
+    /// # }
+    /// ```
+    pub fn read_into_bytes(&mut self, dst: &mut BytesMut, max: usize) -> Result<usize> {
+        read_into(self, dst, max)
+    }
+}
+
+impl<R> BufReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Appends up to `max` bytes preceding the `0x1A` byte to `dst`, returning the number of
+    /// bytes appended.
+    ///
+    /// This behaves exactly like a single call to [`read()`](Read::read()) with a buffer of length
+    /// `max`.
+    ///
+    /// This method is only available with the `bytes` feature enabled.
+    pub fn read_into_bytes(&mut self, dst: &mut BytesMut, max: usize) -> Result<usize> {
+        read_into(self, dst, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes_crate::BytesMut;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use BufReadToCtrlZ;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn stops_at_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+        let mut dst = BytesMut::from(&b"abc"[..]);
+
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 16), 3);
+        assert_eq!(&dst[..], b"abcfoo");
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 16), 0);
+        assert_eq!(&dst[..], b"abcfoo");
+        assert_eq!(reader.bytes_delivered(), 3);
+    }
+
+    #[test]
+    fn max() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+        let mut dst = BytesMut::new();
+
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 4), 4);
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 4), 2);
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 4), 0);
+        assert_eq!(&dst[..], b"foobar");
+    }
+
+    #[test]
+    fn never_past_ctrl_z() {
+        let mut count = 0;
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            // Reads the marker and the data following it in a single chunk, then more data.
+            count += 1;
+            let input: &[u8] = if count == 1 { b"ab\x1acd" } else { b"ef" };
+            buf[..input.len()].clone_from_slice(input);
+            Ok(input.len())
+        }));
+        let mut dst = BytesMut::new();
+
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 16), 2);
+        assert_ok_eq!(reader.read_into_bytes(&mut dst, 16), 0);
+        assert_eq!(&dst[..], b"ab");
+    }
+
+    #[test]
+    fn error_leaves_dst_unchanged() {
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+        let mut dst = BytesMut::from(&b"abc"[..]);
+
+        assert_eq!(
+            assert_err!(reader.read_into_bytes(&mut dst, 16)).kind(),
+            ErrorKind::Other
+        );
+        assert_eq!(&dst[..], b"abc");
+    }
+
+    #[test]
+    fn buffered() {
+        let mut reader = BufReadToCtrlZ::with_capacity(2, b"foo\x1abar" as &[u8]);
+        let mut dst = BytesMut::new();
+
+        while assert_ok!(reader.read_into_bytes(&mut dst, 16)) > 0 {}
+        assert_eq!(&dst[..], b"foo");
+    }
+}
@@ -0,0 +1,147 @@
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// A handle for cancelling a long-running scan from another thread.
+///
+/// Clones of a `CancelToken` share the same flag, so one clone can be passed to a scan, such as
+/// [`classify_reader_cancellable()`], while another is kept to [`cancel()`](CancelToken::cancel())
+/// it. Cancellable scans check the flag before each buffer they read, and stop with a
+/// [`Cancelled`] error once it is set.
+///
+/// # Example
+/// ```
+/// use ctrl_z::CancelToken;
+///
+/// let token = CancelToken::new();
+/// let handle = token.clone();
+///
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+///
+/// [`classify_reader_cancellable()`]: crate::classify_reader_cancellable()
+/// [`Cancelled`]: crate::Cancelled
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    /// Whether cancellation has been requested.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a new `CancelToken` that has not been cancelled.
+    pub fn new() -> Self {
+        CancelToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Requests cancellation of every scan using this token or one of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Returns an error wrapping [`Cancelled`] if cancellation of `token` has been requested.
+///
+/// [`Cancelled`]: crate::Cancelled
+pub fn check(token: &CancelToken, bytes_scanned: u64) -> Result<()> {
+    if token.is_cancelled() {
+        Err(Error::new(
+            ErrorKind::Other,
+            Cancelled {
+                bytes_scanned: bytes_scanned,
+            },
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// The error wrapped by an [`ErrorKind::Other`] error when a scan is cancelled through a
+/// [`CancelToken`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::CancelToken;
+/// use ctrl_z::Cancelled;
+///
+/// let token = CancelToken::new();
+/// token.cancel();
+///
+/// let error = ctrl_z::classify_reader_cancellable(&b"foo\x1a"[..], &token).unwrap_err();
+/// let cancelled = error.get_ref().unwrap().downcast_ref::<Cancelled>().unwrap();
+/// assert_eq!(cancelled.bytes_scanned(), 0);
+/// ```
+///
+/// [`ErrorKind::Other`]: std::io::ErrorKind::Other
+/// [`CancelToken`]: crate::CancelToken
+#[derive(Debug)]
+pub struct Cancelled {
+    /// The number of bytes scanned before the scan was cancelled.
+    bytes_scanned: u64,
+}
+
+impl Cancelled {
+    /// Returns the number of bytes scanned before the scan was cancelled.
+    pub fn bytes_scanned(&self) -> u64 {
+        self.bytes_scanned
+    }
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "scan cancelled after {} bytes",
+            self.bytes_scanned
+        )
+    }
+}
+
+impl error::Error for Cancelled {
+    fn description(&self) -> &str {
+        "scan cancelled"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+    use super::CancelToken;
+    use super::Cancelled;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn not_cancelled() {
+        let token = CancelToken::new();
+
+        assert!(!token.is_cancelled());
+        assert_ok!(check(&token, 0));
+    }
+
+    #[test]
+    fn cancelled_through_clone() {
+        let token = CancelToken::new();
+        token.clone().cancel();
+
+        assert!(token.is_cancelled());
+        let error = assert_err!(check(&token, 42));
+        assert_eq!(error.kind(), ErrorKind::Other);
+        let cancelled = assert_some!(assert_some!(error.get_ref()).downcast_ref::<Cancelled>());
+        assert_eq!(cancelled.bytes_scanned(), 42);
+        assert_eq!(format!("{}", cancelled), "scan cancelled after 42 bytes");
+    }
+}
@@ -0,0 +1,130 @@
+use crossbeam_channel::Receiver;
+use std::io::Read;
+use std::io::Result;
+
+/// A reader over the chunks of bytes received from a [`crossbeam_channel::Receiver`].
+///
+/// Chunks are read in the order they are received. Reading blocks until a chunk is available,
+/// and the end of the reader is reached once the channel is disconnected and empty. Wrapping a
+/// `ChannelReader` in a [`ReadToCtrlZ`] allows data from a producer thread to be read until a
+/// `0x1A` byte.
+///
+/// This type is only available with the `crossbeam` feature enabled.
+///
+/// # Example
+/// ```
+/// extern crate crossbeam_channel;
+/// # extern crate ctrl_z;
+///
+/// use ctrl_z::ChannelReader;
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+/// use std::thread;
+///
+/// # fn main() {
+/// let (sender, receiver) = crossbeam_channel::unbounded();
+/// let producer = thread::spawn(move || {
+///     sender.send(b"foo".to_vec()).unwrap();
+///     sender.send(b"bar\x1abaz".to_vec()).unwrap();
+/// });
+///
+/// let mut reader = ReadToCtrlZ::new(ChannelReader::new(receiver));
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foobar");
+/// producer.join().unwrap();
+/// # }
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct ChannelReader<T> {
+    /// The channel the chunks are received from.
+    receiver: Receiver<T>,
+    /// The chunk currently being read, if any.
+    chunk: Option<T>,
+    /// The position of the first unread byte within `chunk`.
+    pos: usize,
+}
+
+impl<T> ChannelReader<T> {
+    /// Creates a new `ChannelReader`, reading the chunks received from `receiver`.
+    pub fn new(receiver: Receiver<T>) -> Self {
+        ChannelReader {
+            receiver: receiver,
+            chunk: None,
+            pos: 0,
+        }
+    }
+}
+
+impl<T> Read for ChannelReader<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            if let Some(ref chunk) = self.chunk {
+                let remaining = &chunk.as_ref()[self.pos..];
+                if !remaining.is_empty() {
+                    let n = try!((&*remaining).read(buf));
+                    self.pos += n;
+                    return Ok(n);
+                }
+            }
+            // The current chunk is exhausted, so the next one is received.
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = Some(chunk);
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelReader;
+    use crossbeam_channel;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read_chunks() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        assert_ok!(sender.send(b"foo" as &[u8]));
+        assert_ok!(sender.send(b"" as &[u8]));
+        assert_ok!(sender.send(b"bar" as &[u8]));
+        drop(sender);
+        let mut output = String::new();
+
+        assert_ok_eq!(ChannelReader::new(receiver).read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn read_to_ctrl_z() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        assert_ok!(sender.send(b"foo".to_vec()));
+        assert_ok!(sender.send(b"bar\x1abaz".to_vec()));
+        let mut output = String::new();
+
+        // The sender is still connected, but reading stops at the `0x1A` byte.
+        assert_ok_eq!(
+            ReadToCtrlZ::new(ChannelReader::new(receiver)).read_to_string(&mut output),
+            6
+        );
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn read_disconnected() {
+        let (sender, receiver) = crossbeam_channel::unbounded::<Vec<u8>>();
+        drop(sender);
+        let mut output = [0; 4];
+
+        assert_ok_eq!(ChannelReader::new(receiver).read(&mut output), 0);
+    }
+}
@@ -0,0 +1,322 @@
+use std::char;
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::result;
+use LogicalBytes;
+use ReadToCtrlZ;
+
+/// An error yielded by [`CtrlZChars`].
+///
+/// [`CtrlZChars`]: crate::CtrlZChars
+#[derive(Debug)]
+pub enum CharsError {
+    /// An error occurred while reading from the inner reader.
+    Io(io::Error),
+    /// The bytes starting at `offset` are not a valid UTF-8 sequence.
+    InvalidUtf8 {
+        /// The offset of the first byte of the invalid sequence within the delivered bytes.
+        offset: u64,
+    },
+    /// The UTF-8 sequence starting at `offset` was cut short by the `0x1A` byte or the end of the
+    /// inner reader.
+    Truncated {
+        /// The offset of the first byte of the truncated sequence within the delivered bytes.
+        offset: u64,
+    },
+}
+
+impl Display for CharsError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            CharsError::Io(ref error) => write!(formatter, "{}", error),
+            CharsError::InvalidUtf8 { offset } => {
+                write!(formatter, "invalid UTF-8 sequence at byte {}", offset)
+            }
+            CharsError::Truncated { offset } => {
+                write!(formatter, "truncated UTF-8 sequence at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for CharsError {
+    fn description(&self) -> &str {
+        match *self {
+            CharsError::Io(_) => "I/O error",
+            CharsError::InvalidUtf8 { .. } => "invalid UTF-8 sequence",
+            CharsError::Truncated { .. } => "truncated UTF-8 sequence",
+        }
+    }
+}
+
+impl From<io::Error> for CharsError {
+    fn from(error: io::Error) -> Self {
+        CharsError::Io(error)
+    }
+}
+
+impl From<CharsError> for io::Error {
+    /// Converts a `CharsError` into an `io::Error`.
+    ///
+    /// [`CharsError::Io`] is unwrapped, while the other variants become errors of kind
+    /// [`InvalidInput`](ErrorKind::InvalidInput) wrapping the `CharsError`.
+    fn from(error: CharsError) -> Self {
+        match error {
+            CharsError::Io(error) => error,
+            error => io::Error::new(ErrorKind::InvalidInput, error),
+        }
+    }
+}
+
+/// An iterator over the `char`s preceding a `0x1A` byte, decoded from UTF-8.
+///
+/// Bytes are read through an internal buffer and decoded incrementally, so multibyte sequences
+/// split across reads of the inner reader are decoded correctly. Iteration ends at the `0x1A` byte
+/// or the end of the inner reader.
+///
+/// An invalid sequence yields [`CharsError::InvalidUtf8`], after which decoding resumes with the
+/// following byte. A sequence cut short by the `0x1A` byte or the end of the inner reader yields
+/// [`CharsError::Truncated`], ending iteration. After an I/O error has been yielded, `None` is
+/// always returned.
+///
+/// This type is created using [`ReadToCtrlZ::chars()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let chars = ReadToCtrlZ::new("caf\u{e9}\x1abar".as_bytes())
+///     .chars()
+///     .map(|c| c.unwrap())
+///     .collect::<String>();
+///
+/// assert_eq!(chars, "caf\u{e9}");
+/// ```
+///
+/// [`CharsError::InvalidUtf8`]: crate::CharsError::InvalidUtf8
+/// [`CharsError::Truncated`]: crate::CharsError::Truncated
+/// [`ReadToCtrlZ::chars()`]: crate::ReadToCtrlZ::chars()
+pub struct CtrlZChars<R> {
+    /// The bytes being decoded.
+    bytes: LogicalBytes<R>,
+    /// A byte that ended an invalid sequence, to be decoded as the start of the next one.
+    pending: Option<u8>,
+    /// The offset of the next byte to be decoded within the delivered bytes.
+    offset: u64,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `CtrlZChars`, iterating over the `char`s preceding the `0x1A` byte.
+    pub fn chars(self) -> CtrlZChars<R> {
+        CtrlZChars {
+            bytes: self.logical_bytes(),
+            pending: None,
+            offset: 0,
+        }
+    }
+}
+
+impl<R> CtrlZChars<R>
+where
+    R: Read,
+{
+    /// Returns the next byte to be decoded, advancing the offset past it.
+    fn next_byte(&mut self) -> Option<io::Result<u8>> {
+        let byte = match self.pending.take() {
+            Some(byte) => Some(Ok(byte)),
+            None => self.bytes.next(),
+        };
+        if let Some(Ok(_)) = byte {
+            self.offset += 1;
+        }
+        byte
+    }
+}
+
+impl<R> Iterator for CtrlZChars<R>
+where
+    R: Read,
+{
+    type Item = result::Result<char, CharsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let first = match self.next_byte() {
+            Some(Ok(byte)) => byte,
+            Some(Err(error)) => return Some(Err(CharsError::Io(error))),
+            None => return None,
+        };
+
+        let (width, mut code_point, min) = if first < 0x80 {
+            return Some(Ok(first as char));
+        } else if first >= 0xC2 && first <= 0xDF {
+            (2, (first & 0x1F) as u32, 0x80)
+        } else if first >= 0xE0 && first <= 0xEF {
+            (3, (first & 0x0F) as u32, 0x800)
+        } else if first >= 0xF0 && first <= 0xF4 {
+            (4, (first & 0x07) as u32, 0x10000)
+        } else {
+            return Some(Err(CharsError::InvalidUtf8 { offset: start }));
+        };
+        for _ in 1..width {
+            let byte = match self.next_byte() {
+                Some(Ok(byte)) => byte,
+                Some(Err(error)) => return Some(Err(CharsError::Io(error))),
+                None => return Some(Err(CharsError::Truncated { offset: start })),
+            };
+            if byte & 0xC0 != 0x80 {
+                // The byte may begin a valid sequence of its own, so it is decoded next.
+                self.pending = Some(byte);
+                self.offset -= 1;
+                return Some(Err(CharsError::InvalidUtf8 { offset: start }));
+            }
+            code_point = (code_point << 6) | (byte & 0x3F) as u32;
+        }
+
+        // Overlong encodings, surrogates, and code points beyond `U+10FFFF` are all invalid.
+        match char::from_u32(code_point) {
+            Some(c) if code_point >= min => Some(Ok(c)),
+            _ => Some(Err(CharsError::InvalidUtf8 { offset: start })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharsError;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    /// The input used for testing chunking, containing 1-, 2-, 3-, and 4-byte characters.
+    const INPUT: &'static str = "a\u{e9}\u{20ac}\u{1f600}b\u{e9}\u{20ac}\u{1f600}";
+
+    #[test]
+    fn every_chunking() {
+        let mut bytes = INPUT.as_bytes().to_vec();
+        bytes.extend(b"\x1a\xff".iter().cloned());
+        for size in 1..bytes.len() + 1 {
+            let mut pos = 0;
+            let reader = ClosureReader(|buf: &mut [u8]| {
+                let n = *[buf.len(), size, bytes.len() - pos].iter().min().unwrap();
+                for (dst, &src) in buf.iter_mut().zip(bytes[pos..pos + n].iter()) {
+                    *dst = src;
+                }
+                pos += n;
+                Ok(n)
+            });
+
+            let chars = ReadToCtrlZ::new(reader)
+                .chars()
+                .map(Result::unwrap)
+                .collect::<String>();
+            assert_eq!(chars, INPUT);
+        }
+    }
+
+    #[test]
+    fn every_split_point() {
+        let bytes = INPUT.as_bytes();
+        for split in 0..bytes.len() + 1 {
+            // An empty chunk would be read as the end of the input, so it is skipped.
+            let mut chunks = vec![&bytes[..split], &bytes[split..]]
+                .into_iter()
+                .filter(|chunk| !chunk.is_empty());
+            let reader = ClosureReader(|buf: &mut [u8]| match chunks.next() {
+                Some(chunk) => {
+                    for (dst, &src) in buf.iter_mut().zip(chunk.iter()) {
+                        *dst = src;
+                    }
+                    Ok(chunk.len())
+                }
+                None => Ok(0),
+            });
+
+            let chars = ReadToCtrlZ::new(reader)
+                .chars()
+                .map(Result::unwrap)
+                .collect::<String>();
+            assert_eq!(chars, INPUT);
+        }
+    }
+
+    #[test]
+    fn invalid_byte() {
+        let mut chars = ReadToCtrlZ::new(b"a\xffb" as &[u8]).chars();
+
+        assert_ok_eq!(assert_some!(chars.next()), 'a');
+        match assert_some!(chars.next()) {
+            Err(CharsError::InvalidUtf8 { offset }) => assert_eq!(offset, 1),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_ok_eq!(assert_some!(chars.next()), 'b');
+        assert_none!(chars.next());
+    }
+
+    #[test]
+    fn invalid_continuation_resumes() {
+        let mut chars = ReadToCtrlZ::new(b"\xe2\x82a" as &[u8]).chars();
+
+        match assert_some!(chars.next()) {
+            Err(CharsError::InvalidUtf8 { offset }) => assert_eq!(offset, 0),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_ok_eq!(assert_some!(chars.next()), 'a');
+        assert_none!(chars.next());
+    }
+
+    #[test]
+    fn overlong_and_surrogate() {
+        for &input in [&b"\xe0\x80\x80"[..], b"\xed\xa0\x80", b"\xf4\x90\x80\x80"].iter() {
+            let mut chars = ReadToCtrlZ::new(input).chars();
+
+            match assert_some!(chars.next()) {
+                Err(CharsError::InvalidUtf8 { offset }) => assert_eq!(offset, 0),
+                result => panic!("unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_by_ctrl_z() {
+        let mut chars = ReadToCtrlZ::new(b"a\xe2\x82\x1a\xac" as &[u8]).chars();
+
+        assert_ok_eq!(assert_some!(chars.next()), 'a');
+        match assert_some!(chars.next()) {
+            Err(CharsError::Truncated { offset }) => assert_eq!(offset, 1),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_none!(chars.next());
+    }
+
+    #[test]
+    fn truncated_by_eof() {
+        let mut chars = ReadToCtrlZ::new(b"\xf0\x9f\x98" as &[u8]).chars();
+
+        match assert_some!(chars.next()) {
+            Err(CharsError::Truncated { offset }) => assert_eq!(offset, 0),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_none!(chars.next());
+    }
+
+    #[test]
+    fn io_error() {
+        let mut chars = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }))
+        .chars();
+
+        match assert_some!(chars.next()) {
+            Err(CharsError::Io(error)) => assert_eq!(error.kind(), ErrorKind::Other),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_none!(chars.next());
+    }
+}
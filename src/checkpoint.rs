@@ -0,0 +1,202 @@
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use ReadToCtrlZ;
+
+/// The reading state of a [`ReadToCtrlZ`] at a point in time, created by
+/// [`ReadToCtrlZ::checkpoint()`].
+///
+/// A checkpoint records the position of the inner reader rather than any bytes, so it is cheap to
+/// create and to copy regardless of how much has been read ahead.
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+/// [`ReadToCtrlZ::checkpoint()`]: crate::ReadToCtrlZ::checkpoint()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    /// The position of the first undelivered byte within the inner reader.
+    position: u64,
+    /// Whether or not reading had been terminated.
+    terminated: bool,
+    /// The byte that terminated reading, if any.
+    terminator: Option<u8>,
+    /// The number of bytes delivered.
+    delivered: u64,
+    /// The number of bytes read from the inner reader, excluding read-ahead bytes.
+    inner_read: u64,
+    /// Whether or not the limit had been exceeded.
+    exceeded: bool,
+    /// Whether or not the delivered bytes ended within a quoted region.
+    quoted: bool,
+    /// Whether or not leading terminating bytes were still being discarded.
+    leading: bool,
+    /// The total number of bytes that will have been delivered at termination, if known.
+    end: Option<u64>,
+}
+
+impl Checkpoint {
+    /// Returns the number of bytes that had been delivered when the checkpoint was created.
+    pub fn bytes_delivered(&self) -> u64 {
+        self.delivered
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Seek,
+{
+    /// Creates a [`Checkpoint`] of the current reading state, which can later be returned to
+    /// using [`restore()`](ReadToCtrlZ::restore()).
+    ///
+    /// The checkpoint captures the position of the inner reader, whether reading has been
+    /// terminated, and the byte counters. Bytes read ahead but not yet delivered are not copied:
+    /// the checkpoint instead records the position of the first of them within the inner reader,
+    /// so they are read again after restoring.
+    ///
+    /// # Errors
+    /// Returns any error that occurs while determining the position of the inner reader.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Cursor;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar".to_vec()));
+    /// let mut output = String::new();
+    ///
+    /// let checkpoint = reader.checkpoint().unwrap();
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert!(reader.restore(&checkpoint).is_ok());
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foofoo");
+    /// ```
+    ///
+    /// [`Checkpoint`]: crate::Checkpoint
+    pub fn checkpoint(&mut self) -> Result<Checkpoint> {
+        let pending = (self.buffer.len() - self.pos) as u64;
+        let position = try!(self.inner.seek(SeekFrom::Current(0)));
+        Ok(Checkpoint {
+            position: position - pending,
+            terminated: self.terminated,
+            terminator: self.terminator,
+            delivered: self.delivered,
+            inner_read: self.inner_read - pending,
+            exceeded: self.exceeded,
+            quoted: self.quoted,
+            leading: self.leading,
+            end: self.end,
+        })
+    }
+
+    /// Returns to the reading state captured by `checkpoint`.
+    ///
+    /// The inner reader is seeked back to the checkpoint's position and all reading state is
+    /// restored, so subsequent reads return exactly the bytes they returned after the checkpoint
+    /// was created, provided the inner reader's content has not changed. The configuration of
+    /// this reader is left unchanged. If the inner reader is a [`BufReader`](std::io::BufReader),
+    /// its buffer is discarded by the seek.
+    ///
+    /// The checkpoint must have been created by this reader.
+    ///
+    /// # Errors
+    /// Returns any error that occurs while seeking the inner reader, in which case the reading
+    /// state is left unchanged.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+        try!(self.inner.seek(SeekFrom::Start(checkpoint.position)));
+        self.buffer.clear();
+        self.pos = 0;
+        self.terminated = checkpoint.terminated;
+        self.terminator = checkpoint.terminator;
+        self.delivered = checkpoint.delivered;
+        self.inner_read = checkpoint.inner_read;
+        self.exceeded = checkpoint.exceeded;
+        self.quoted = checkpoint.quoted;
+        self.leading = checkpoint.leading;
+        self.end = checkpoint.end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Cursor;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn restore_after_termination() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz".to_vec()));
+        let mut output = [0; 2];
+        assert_ok_eq!(reader.read(&mut output), 2);
+        let checkpoint = assert_ok!(reader.checkpoint());
+
+        let mut first = String::new();
+        assert_ok_eq!(reader.read_to_string(&mut first), 4);
+        assert!(reader.is_terminated());
+        let counters = (reader.bytes_delivered(), reader.inner_bytes_read());
+        assert_ok!(reader.restore(&checkpoint));
+
+        assert!(!reader.is_terminated());
+        assert_eq!(reader.bytes_delivered(), 2);
+        let mut second = String::new();
+        assert_ok_eq!(reader.read_to_string(&mut second), 4);
+        assert_eq!(first, second);
+        assert_eq!(
+            (reader.bytes_delivered(), reader.inner_bytes_read()),
+            counters
+        );
+    }
+
+    #[test]
+    fn restore_terminated() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar".to_vec()));
+        let mut output = String::new();
+        assert_ok!(reader.read_to_string(&mut output));
+        let checkpoint = assert_ok!(reader.checkpoint());
+        assert_ok!(reader.rewind());
+
+        assert_ok!(reader.restore(&checkpoint));
+
+        assert!(reader.is_terminated());
+        assert_some_eq!(reader.terminator(), b'\x1a');
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+
+    #[test]
+    fn restore_with_read_ahead() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz".to_vec()));
+        // Peeking moves bytes into the read-ahead buffer.
+        assert_ok_eq!(reader.peek_n(4), b"foob");
+        let checkpoint = assert_ok!(reader.checkpoint());
+        assert_eq!(checkpoint.bytes_delivered(), 0);
+
+        let mut first = String::new();
+        assert_ok!(reader.read_to_string(&mut first));
+        assert_ok!(reader.restore(&checkpoint));
+        let mut second = String::new();
+        assert_ok!(reader.read_to_string(&mut second));
+
+        assert_eq!(first, "foobar");
+        assert_eq!(second, "foobar");
+    }
+
+    #[test]
+    fn restore_buffered() {
+        let inner = BufReader::with_capacity(2, Cursor::new(b"foobar\x1abaz".to_vec()));
+        let mut reader = ReadToCtrlZ::new(inner);
+        assert_ok_eq!(reader.fill_buf(), b"fo");
+        reader.consume(1);
+        let checkpoint = assert_ok!(reader.checkpoint());
+
+        let mut first = Vec::new();
+        assert_ok!(reader.read_until(b'\n', &mut first));
+        assert_ok!(reader.restore(&checkpoint));
+        let mut second = Vec::new();
+        assert_ok!(reader.read_until(b'\n', &mut second));
+
+        assert_eq!(first, b"oobar");
+        assert_eq!(second, b"oobar");
+    }
+}
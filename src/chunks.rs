@@ -0,0 +1,197 @@
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::iter;
+#[cfg(feature = "fused")]
+use std::iter::FusedIterator;
+use ReadToCtrlZ;
+
+/// An iterator over fixed-size blocks of the bytes preceding a `0x1A` byte.
+///
+/// Each block yielded is exactly the requested size, except for the last, which holds the
+/// remaining bytes. The `0x1A` byte and anything following it are never included, and no empty
+/// block is yielded. Reads are made into an internal buffer, so each yielded `Vec` is allocated
+/// only once, at its final length.
+///
+/// This type is created using [`ReadToCtrlZ::chunks()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let chunks = ReadToCtrlZ::new(&b"foobarbaz\x1aqux"[..])
+///     .chunks(4)
+///     .map(|chunk| chunk.unwrap())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(chunks, [&b"foob"[..], b"arba", b"z"]);
+/// ```
+///
+/// [`ReadToCtrlZ::chunks()`]: crate::ReadToCtrlZ::chunks()
+pub struct CtrlZChunks<R> {
+    /// The internal reader being read.
+    inner: ReadToCtrlZ<R>,
+    /// The buffer each chunk is read into.
+    buffer: Vec<u8>,
+    /// Whether or not iteration has finished.
+    finished: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `CtrlZChunks`, iterating over blocks of `size` bytes.
+    ///
+    /// # Panics
+    /// Panics if `size` is zero.
+    pub fn chunks(self, size: usize) -> CtrlZChunks<R> {
+        assert!(size > 0, "chunk size must be nonzero");
+        CtrlZChunks {
+            inner: self,
+            buffer: iter::repeat(0).take(size).collect(),
+            finished: false,
+        }
+    }
+}
+
+impl<R> CtrlZChunks<R> {
+    /// Unwraps this `CtrlZChunks`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> CtrlZChunks<R>
+where
+    R: Read,
+{
+    /// Reads as many bytes as possible into the buffer, returning the number of bytes read.
+    fn fill(&mut self) -> Result<usize> {
+        let mut filled = 0;
+        while filled < self.buffer.len() {
+            match self.inner.read(&mut self.buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(filled)
+    }
+}
+
+impl<R> Iterator for CtrlZChunks<R>
+where
+    R: Read,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let filled = match self.fill() {
+            Ok(filled) => filled,
+            Err(error) => {
+                self.finished = true;
+                return Some(Err(error));
+            }
+        };
+        if filled < self.buffer.len() {
+            self.finished = true;
+        }
+        if filled == 0 {
+            return None;
+        }
+        let mut chunk = Vec::with_capacity(filled);
+        chunk.extend(self.buffer[..filled].iter().cloned());
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(feature = "fused")]
+#[allow(clippy::incompatible_msrv)]
+impl<R> FusedIterator for CtrlZChunks<R> where R: Read {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    #[cfg(feature = "fused")]
+    assert_impl_all!(super::CtrlZChunks<&'static [u8]>: std::iter::FusedIterator);
+
+    #[test]
+    fn multiple_of_size() {
+        let chunks = ReadToCtrlZ::new(b"foobarbaz\x1aqux" as &[u8])
+            .chunks(3)
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(chunks, [b"foo", b"bar", b"baz"]);
+    }
+
+    #[test]
+    fn not_multiple_of_size() {
+        let chunks = ReadToCtrlZ::new(b"foobarbaz\x1aqux" as &[u8])
+            .chunks(4)
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2], b"z");
+        assert_eq!(chunks[2].capacity(), 1);
+    }
+
+    #[test]
+    fn no_ctrl_z() {
+        let chunks = ReadToCtrlZ::new(b"foobar" as &[u8])
+            .chunks(4)
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(chunks, [&b"foob"[..], b"ar"]);
+    }
+
+    #[test]
+    fn empty() {
+        assert_none!(ReadToCtrlZ::new(b"\x1afoo" as &[u8]).chunks(4).next());
+    }
+
+    #[test]
+    fn short_reads() {
+        let mut count = 0;
+        let chunks = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            // Reads "foobar" followed by `0x1A` one byte at a time.
+            buf[0] = b"foobar\x1a"[count];
+            count += 1;
+            Ok(1)
+        }))
+        .chunks(4)
+        .map(|chunk| chunk.unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(chunks, [&b"foob"[..], b"ar"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn size_zero() {
+        ReadToCtrlZ::new(b"" as &[u8]).chunks(0);
+    }
+
+    #[test]
+    fn error() {
+        let mut chunks = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }))
+        .chunks(4);
+
+        assert_eq!(
+            assert_err!(assert_some!(chunks.next())).kind(),
+            ErrorKind::Other
+        );
+        assert_none!(chunks.next());
+    }
+}
@@ -0,0 +1,499 @@
+use cancel;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use CancelToken;
+
+/// The size of the buffer a reader is read into.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// The minimum proportion of printable bytes before the `0x1A` byte for input to be considered
+/// text.
+const TEXT_RATIO: f64 = 0.95;
+
+/// The proportion of printable bytes before the `0x1A` byte below which input is considered
+/// binary.
+const BINARY_RATIO: f64 = 0.75;
+
+/// The minimum position of the `0x1A` byte, as a fraction of the input's length, for it to be
+/// considered a trailing marker without padding.
+const TRAILING_FRACTION: f64 = 0.9;
+
+/// The verdict of [`classify()`] on whether the first `0x1A` byte marks the end of text.
+///
+/// [`classify()`]: crate::classify()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// The input looks like text ending at the `0x1A` byte, so truncating there is likely
+    /// correct.
+    LikelyTextWithMarker,
+    /// The input looks like binary data that merely contains a `0x1A` byte, so truncating there is
+    /// likely destructive.
+    LikelyBinary,
+    /// The input contains no `0x1A` byte.
+    NoMarker,
+    /// The evidence is inconclusive.
+    Ambiguous,
+}
+
+/// The result of [`classify()`]: a [`Verdict`] along with the evidence it was based on.
+///
+/// The evidence values are exposed so that callers can apply their own thresholds instead of
+/// relying on the verdict.
+///
+/// [`classify()`]: crate::classify()
+/// [`Verdict`]: crate::Verdict
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Classification {
+    /// The verdict reached from the evidence.
+    verdict: Verdict,
+    /// The total number of bytes examined.
+    len: u64,
+    /// The offset of the first `0x1A` byte, if any.
+    marker: Option<u64>,
+    /// The number of printable bytes before the first `0x1A` byte.
+    printable: u64,
+    /// The number of `0x00` bytes, excluding padding following the first `0x1A` byte.
+    nuls: u64,
+    /// Whether every byte following the first `0x1A` byte is `0x1A` or `0x00`.
+    padding_tail: bool,
+}
+
+impl Classification {
+    /// Returns the verdict reached from the evidence.
+    pub fn verdict(&self) -> Verdict {
+        self.verdict
+    }
+
+    /// Returns the total number of bytes examined.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns whether no bytes were examined.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the offset of the first `0x1A` byte, or `None` if there is none.
+    pub fn marker_offset(&self) -> Option<u64> {
+        self.marker
+    }
+
+    /// Returns the position of the first `0x1A` byte as a fraction of the input's length, from
+    /// `0.0` for the first byte up to, but excluding, `1.0`.
+    ///
+    /// Returns `None` if there is no `0x1A` byte.
+    pub fn marker_fraction(&self) -> Option<f64> {
+        self.marker.map(|marker| marker as f64 / self.len as f64)
+    }
+
+    /// Returns the proportion of printable bytes before the first `0x1A` byte, or in the whole
+    /// input if there is none.
+    ///
+    /// Printable bytes are `0x20` through `0x7E`, `\t`, `\n`, `\x0C`, and `\r`. This is `1.0` if
+    /// there are no such bytes to examine.
+    pub fn printable_ratio(&self) -> f64 {
+        let examined = self.marker.unwrap_or(self.len);
+        if examined == 0 {
+            1.0
+        } else {
+            self.printable as f64 / examined as f64
+        }
+    }
+
+    /// Returns the number of `0x00` bytes in the input.
+    ///
+    /// If everything following the first `0x1A` byte is padding, the `0x00` bytes within that
+    /// padding are not counted.
+    pub fn nul_count(&self) -> u64 {
+        self.nuls
+    }
+
+    /// Returns whether every byte following the first `0x1A` byte is `0x1A` or `0x00` padding.
+    ///
+    /// This is `true` if no bytes follow the `0x1A` byte, and `false` if there is none.
+    pub fn tail_is_padding(&self) -> bool {
+        self.padding_tail
+    }
+}
+
+/// Accumulates the evidence for a `Classification` from consecutive chunks of input.
+struct Classifier {
+    /// The number of bytes examined so far.
+    len: u64,
+    /// The offset of the first `0x1A` byte, if found.
+    marker: Option<u64>,
+    /// The number of printable bytes before the first `0x1A` byte.
+    printable: u64,
+    /// The number of `0x00` bytes before the first `0x1A` byte.
+    nuls: u64,
+    /// The number of `0x00` bytes following the first `0x1A` byte.
+    tail_nuls: u64,
+    /// Whether every byte following the first `0x1A` byte so far is padding.
+    padding_tail: bool,
+}
+
+impl Classifier {
+    /// Creates a new `Classifier` that has examined no bytes.
+    fn new() -> Self {
+        Classifier {
+            len: 0,
+            marker: None,
+            printable: 0,
+            nuls: 0,
+            tail_nuls: 0,
+            padding_tail: true,
+        }
+    }
+
+    /// Examines the next chunk of input.
+    fn update(&mut self, bytes: &[u8]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            if self.marker.is_some() {
+                if byte == b'\0' {
+                    self.tail_nuls += 1;
+                } else if byte != b'\x1a' {
+                    self.padding_tail = false;
+                }
+            } else if byte == b'\x1a' {
+                self.marker = Some(self.len + i as u64);
+            } else if byte == b'\0' {
+                self.nuls += 1;
+            } else if is_printable(byte) {
+                self.printable += 1;
+            }
+        }
+        self.len += bytes.len() as u64;
+    }
+
+    /// Reaches a verdict from the evidence examined.
+    fn finish(self) -> Classification {
+        let padding_tail = self.marker.is_some() && self.padding_tail;
+        let mut classification = Classification {
+            verdict: Verdict::Ambiguous,
+            len: self.len,
+            marker: self.marker,
+            printable: self.printable,
+            nuls: if padding_tail {
+                self.nuls
+            } else {
+                self.nuls + self.tail_nuls
+            },
+            padding_tail: padding_tail,
+        };
+        classification.verdict = verdict(&classification);
+        classification
+    }
+}
+
+/// Returns whether `byte` is a printable ASCII character or common whitespace.
+fn is_printable(byte: u8) -> bool {
+    (byte >= 0x20 && byte < 0x7f)
+        || byte == b'\t'
+        || byte == b'\n'
+        || byte == b'\x0c'
+        || byte == b'\r'
+}
+
+/// Reaches a verdict from the evidence in `classification`.
+fn verdict(classification: &Classification) -> Verdict {
+    let fraction = match classification.marker_fraction() {
+        Some(fraction) => fraction,
+        None => return Verdict::NoMarker,
+    };
+    let ratio = classification.printable_ratio();
+    if classification.nul_count() > 0 || ratio < BINARY_RATIO {
+        Verdict::LikelyBinary
+    } else if ratio >= TEXT_RATIO
+        && (classification.tail_is_padding() || fraction >= TRAILING_FRACTION)
+    {
+        Verdict::LikelyTextWithMarker
+    } else {
+        Verdict::Ambiguous
+    }
+}
+
+/// Guesses whether truncating `bytes` at its first `0x1A` byte is correct, because it is legacy
+/// text ending with a marker, or destructive, because it is binary data that merely contains the
+/// byte.
+///
+/// The guess is made using the following heuristics, applied in order:
+///
+/// 1. Without a `0x1A` byte, the verdict is [`NoMarker`].
+/// 2. If the input contains any `0x00` byte other than padding following the `0x1A` byte, or fewer
+///    than 75% of the bytes before the `0x1A` byte are printable, the verdict is [`LikelyBinary`].
+///    Text rarely contains `0x00` bytes, while most binary formats do.
+/// 3. If at least 95% of the bytes before the `0x1A` byte are printable, and either everything
+///    following it is `0x1A` or `0x00` padding or it lies within the last 10% of the input, the
+///    verdict is [`LikelyTextWithMarker`].
+/// 4. Otherwise, the verdict is [`Ambiguous`].
+///
+/// Printable bytes are those of printable ASCII and common whitespace. Bytes `0x80` through `0xFF`
+/// are not counted as printable, as they are as common in binary data as in 8-bit text. The
+/// returned [`Classification`] includes the evidence used, so that callers can apply their own
+/// thresholds instead.
+///
+/// # Example
+/// ```
+/// use ctrl_z::Verdict;
+///
+/// let text = ctrl_z::classify(b"Hello, world!\r\n\x1a\x1a\x1a");
+/// assert_eq!(text.verdict(), Verdict::LikelyTextWithMarker);
+///
+/// // The PNG signature contains a `0x1A` byte.
+/// let png = ctrl_z::classify(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+/// assert_eq!(png.verdict(), Verdict::LikelyBinary);
+/// ```
+///
+/// [`NoMarker`]: crate::Verdict::NoMarker
+/// [`LikelyBinary`]: crate::Verdict::LikelyBinary
+/// [`LikelyTextWithMarker`]: crate::Verdict::LikelyTextWithMarker
+/// [`Ambiguous`]: crate::Verdict::Ambiguous
+/// [`Classification`]: crate::Classification
+pub fn classify(bytes: &[u8]) -> Classification {
+    let mut classifier = Classifier::new();
+    classifier.update(bytes);
+    classifier.finish()
+}
+
+/// Guesses whether truncating the contents of `reader` at its first `0x1A` byte is correct, as
+/// [`classify()`] does, reading it in chunks rather than requiring it in memory.
+///
+/// All of `reader` is read, as the bytes following the `0x1A` byte are part of the evidence.
+///
+/// # Errors
+/// Returns any error other than [`ErrorKind::Interrupted`] that occurs while reading.
+///
+/// [`classify()`]: crate::classify()
+/// [`ErrorKind::Interrupted`]: std::io::ErrorKind::Interrupted
+pub fn classify_reader<R>(reader: R) -> Result<Classification>
+where
+    R: Read,
+{
+    classify_reader_with(reader, None)
+}
+
+/// Classifies the contents of `reader` as [`classify_reader()`] does, stopping early if `token` is
+/// cancelled.
+///
+/// `token` is checked before each read into an 8 KiB buffer, so a scan stops promptly after it is
+/// cancelled, having read at most one more buffer.
+///
+/// # Errors
+/// Returns an error of kind [`ErrorKind::Other`] wrapping a [`Cancelled`] if `token` is cancelled
+/// before all of `reader` is read. Otherwise, errors are returned as by [`classify_reader()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::CancelToken;
+/// use ctrl_z::Verdict;
+///
+/// let token = CancelToken::new();
+/// let classification = ctrl_z::classify_reader_cancellable(&b"foo\x1a"[..], &token).unwrap();
+///
+/// assert_eq!(classification.verdict(), Verdict::LikelyTextWithMarker);
+/// ```
+///
+/// [`classify_reader()`]: crate::classify_reader()
+/// [`ErrorKind::Other`]: std::io::ErrorKind::Other
+/// [`Cancelled`]: crate::Cancelled
+pub fn classify_reader_cancellable<R>(reader: R, token: &CancelToken) -> Result<Classification>
+where
+    R: Read,
+{
+    classify_reader_with(reader, Some(token))
+}
+
+/// Classifies the contents of `reader`, checking `token` for cancellation before each read.
+fn classify_reader_with<R>(mut reader: R, token: Option<&CancelToken>) -> Result<Classification>
+where
+    R: Read,
+{
+    let mut classifier = Classifier::new();
+    let mut buf = [0; BUFFER_SIZE];
+    loop {
+        if let Some(token) = token {
+            try!(cancel::check(token, classifier.len));
+        }
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(classifier.finish()),
+            Ok(n) => classifier.update(&buf[..n]),
+            Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classify;
+    use super::classify_reader;
+    use super::classify_reader_cancellable;
+    use super::Verdict;
+    use super::BUFFER_SIZE;
+    use std::cmp;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Write;
+    use std::sync::mpsc;
+    use std::thread;
+    use CancelToken;
+    use Cancelled;
+    use ClosureReader;
+
+    /// A DOS-era text file, padded with `0x1A` bytes to a whole CP/M record.
+    fn text_sample() -> Vec<u8> {
+        let mut bytes = b"README.TXT\r\n\r\nThis disk contains the following files:\r\n\r\n\
+            \tINSTALL.EXE  - Installs the program\r\n\
+            \tSETUP.CFG    - Default configuration\r\n"
+            .to_vec();
+        let padding = 128 - bytes.len() % 128;
+        bytes.extend(vec![b'\x1a'; padding]);
+        bytes
+    }
+
+    /// A binary blob with a `0x1A` byte near its start, followed by more data.
+    fn binary_sample() -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01\0\0\0\x01\0\x08\x06\0\0\0".to_vec();
+        bytes.extend((0..256).map(|byte| byte as u8));
+        bytes
+    }
+
+    #[test]
+    fn text_with_marker() {
+        let classification = classify(&text_sample());
+
+        assert_eq!(classification.verdict(), Verdict::LikelyTextWithMarker);
+        assert_eq!(classification.nul_count(), 0);
+        assert_eq!(classification.printable_ratio(), 1.0);
+        assert!(classification.tail_is_padding());
+    }
+
+    #[test]
+    fn binary_with_early_marker() {
+        let classification = classify(&binary_sample());
+
+        assert_eq!(classification.verdict(), Verdict::LikelyBinary);
+        assert_some_eq!(classification.marker_offset(), 6);
+        assert!(!classification.tail_is_padding());
+        assert!(assert_some!(classification.marker_fraction()) < 0.1);
+    }
+
+    #[test]
+    fn binary_without_nuls() {
+        let classification = classify(b"\x01\x02\x03\x80\x81\x82ab\x1acd");
+
+        assert_eq!(classification.nul_count(), 0);
+        assert_eq!(classification.printable_ratio(), 0.25);
+        assert_eq!(classification.verdict(), Verdict::LikelyBinary);
+    }
+
+    #[test]
+    fn nul_padding_not_counted() {
+        let classification = classify(b"foo\r\n\x1a\0\0\0");
+
+        assert_eq!(classification.nul_count(), 0);
+        assert_eq!(classification.verdict(), Verdict::LikelyTextWithMarker);
+    }
+
+    #[test]
+    fn no_marker() {
+        let classification = classify(b"foo bar");
+
+        assert_eq!(classification.verdict(), Verdict::NoMarker);
+        assert_none!(classification.marker_offset());
+        assert_none!(classification.marker_fraction());
+        assert!(!classification.tail_is_padding());
+    }
+
+    #[test]
+    fn empty() {
+        let classification = classify(b"");
+
+        assert_eq!(classification.verdict(), Verdict::NoMarker);
+        assert!(classification.is_empty());
+    }
+
+    #[test]
+    fn trailing_marker_without_padding() {
+        let mut bytes = vec![b'a'; 95];
+        bytes.extend(b"\x1abcde".iter().cloned());
+
+        let classification = classify(&bytes);
+
+        assert!(!classification.tail_is_padding());
+        assert_eq!(classification.verdict(), Verdict::LikelyTextWithMarker);
+    }
+
+    #[test]
+    fn ambiguous_mid_file_marker() {
+        let classification = classify(b"foo\x1abar");
+
+        assert_eq!(classification.printable_ratio(), 1.0);
+        assert_eq!(classification.verdict(), Verdict::Ambiguous);
+    }
+
+    #[test]
+    fn reader_matches_slice() {
+        for bytes in [text_sample(), binary_sample()].iter() {
+            let mut input = &bytes[..];
+            // The reader returns at most 3 bytes per read.
+            let reader = ClosureReader(|buf: &mut [u8]| {
+                let len = cmp::min(buf.len(), 3);
+                input.read(&mut buf[..len])
+            });
+
+            assert_ok_eq!(classify_reader(reader), classify(bytes));
+        }
+    }
+
+    #[test]
+    fn cancellable_completes() {
+        let token = CancelToken::new();
+
+        assert_ok_eq!(
+            classify_reader_cancellable(&text_sample()[..], &token),
+            classify(&text_sample())
+        );
+    }
+
+    #[test]
+    fn cancelled_partway_through_file() {
+        let path = env::temp_dir().join("ctrl_z_classify_cancelled_partway_through_file");
+        let len = 64 * BUFFER_SIZE;
+        assert_ok!(assert_ok!(File::create(&path)).write_all(&vec![b'a'; len]));
+        let mut file = assert_ok!(File::open(&path));
+
+        let token = CancelToken::new();
+        let handle = token.clone();
+        let (started_tx, started_rx) = mpsc::channel();
+        let (cancelled_tx, cancelled_rx) = mpsc::channel();
+        let canceller = thread::spawn(move || {
+            assert_ok!(started_rx.recv());
+            handle.cancel();
+            assert_ok!(cancelled_tx.send(()));
+        });
+        let mut first = true;
+        // The first read waits for the other thread to cancel the scan before returning.
+        let reader = ClosureReader(|buf: &mut [u8]| {
+            if first {
+                first = false;
+                assert_ok!(started_tx.send(()));
+                assert_ok!(cancelled_rx.recv());
+            }
+            file.read(buf)
+        });
+
+        let error = assert_err!(classify_reader_cancellable(reader, &token));
+        assert_ok!(canceller.join());
+        assert_eq!(error.kind(), ErrorKind::Other);
+        let cancelled = assert_some!(assert_some!(error.get_ref()).downcast_ref::<Cancelled>());
+        // Only the buffer being read when the scan was cancelled is examined.
+        assert_eq!(cancelled.bytes_scanned(), BUFFER_SIZE as u64);
+        assert_ok!(fs::remove_file(&path));
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::Read;
+use std::io::Result;
+
+/// A reader that reads by calling a closure.
+///
+/// This is a lightweight adapter for using an arbitrary read function as a [`Read`]er, without
+/// defining a custom type. Each call to [`read()`](Read::read()) is forwarded to the closure.
+///
+/// # Example
+/// ```
+/// use ctrl_z::ClosureReader;
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut count = 0;
+/// let mut reader = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+///     // Reads "foo" followed by `0x1A` one byte at a time.
+///     buf[0] = b"foo\x1a"[count];
+///     count += 1;
+///     Ok(1)
+/// }));
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// ```
+pub struct ClosureReader<F>(pub F)
+where
+    F: FnMut(&mut [u8]) -> Result<usize>;
+
+impl<F> Read for ClosureReader<F>
+where
+    F: FnMut(&mut [u8]) -> Result<usize>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (self.0)(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClosureReader;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read() {
+        let mut output = [0; 4];
+
+        assert_ok_eq!(
+            ClosureReader(|buf: &mut [u8]| {
+                buf[0] = b'a';
+                Ok(1)
+            })
+            .read(&mut output),
+            1
+        );
+        assert_eq!(output[0], b'a');
+    }
+
+    #[test]
+    fn read_error() {
+        let mut reader = ClosureReader(|_: &mut [u8]| Err(Error::new(ErrorKind::Other, "foo")));
+
+        let error = assert_err!(reader.read(&mut [0; 4]));
+        assert_eq!(error.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn read_to_ctrl_z() {
+        let mut data = b"foo\x1abar" as &[u8];
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| data.read(buf)))
+                .read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+}
@@ -0,0 +1,318 @@
+use cancel;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::path::Path;
+use CancelToken;
+use ReadToCtrlZ;
+
+/// The size of the buffers each side is read into.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Returns whether two readers have the same content preceding a `0x1A` byte.
+///
+/// Each side is read through a [`ReadToCtrlZ`], so the `0x1A` byte and anything following it,
+/// such as padding, are ignored. Content ending with a `0x1A` byte is therefore equal to the same
+/// content ending without one.
+///
+/// Both readers are streamed through fixed-size buffers, so neither is read fully into memory.
+/// Reading stops at the first difference.
+///
+/// # Example
+/// ```
+/// use ctrl_z::eq_ignoring_ctrl_z;
+///
+/// assert!(eq_ignoring_ctrl_z(&b"foo\x1a\0\0"[..], &b"foo"[..]).unwrap());
+/// assert!(!eq_ignoring_ctrl_z(&b"foo\x1a"[..], &b"fob\x1a"[..]).unwrap());
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub fn eq_ignoring_ctrl_z<R1, R2>(a: R1, b: R2) -> Result<bool>
+where
+    R1: Read,
+    R2: Read,
+{
+    eq_ignoring_ctrl_z_with(a, b, None)
+}
+
+/// Returns whether two readers have the same content preceding a `0x1A` byte, as
+/// [`eq_ignoring_ctrl_z()`] does, stopping early if `token` is cancelled.
+///
+/// `token` is checked before each pair of 8 KiB buffers is read, so a comparison stops promptly
+/// after it is cancelled, having read at most one more buffer from each side.
+///
+/// # Errors
+/// Returns an error of kind [`ErrorKind::Other`] wrapping a [`Cancelled`] if `token` is cancelled
+/// before the comparison completes. The number of bytes scanned is the number compared from each
+/// side. Otherwise, errors are returned as by [`eq_ignoring_ctrl_z()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::CancelToken;
+///
+/// let token = CancelToken::new();
+///
+/// assert!(ctrl_z::eq_ignoring_ctrl_z_cancellable(&b"foo\x1a"[..], &b"foo"[..], &token).unwrap());
+/// ```
+///
+/// [`eq_ignoring_ctrl_z()`]: crate::eq_ignoring_ctrl_z()
+/// [`ErrorKind::Other`]: std::io::ErrorKind::Other
+/// [`Cancelled`]: crate::Cancelled
+pub fn eq_ignoring_ctrl_z_cancellable<R1, R2>(a: R1, b: R2, token: &CancelToken) -> Result<bool>
+where
+    R1: Read,
+    R2: Read,
+{
+    eq_ignoring_ctrl_z_with(a, b, Some(token))
+}
+
+/// Returns whether two files have the same content preceding a `0x1A` byte.
+///
+/// This is a convenience for opening both files and calling [`eq_ignoring_ctrl_z()`] on them.
+///
+/// # Example
+/// ```no_run
+/// assert!(ctrl_z::files_eq_ignoring_ctrl_z("original.txt", "converted.txt").unwrap());
+/// ```
+///
+/// [`eq_ignoring_ctrl_z()`]: crate::eq_ignoring_ctrl_z()
+pub fn files_eq_ignoring_ctrl_z<P1, P2>(a: P1, b: P2) -> Result<bool>
+where
+    P1: AsRef<Path>,
+    P2: AsRef<Path>,
+{
+    eq_ignoring_ctrl_z(try!(File::open(a)), try!(File::open(b)))
+}
+
+/// Returns whether two files have the same content preceding a `0x1A` byte, stopping early if
+/// `token` is cancelled.
+///
+/// This is a convenience for opening both files and calling [`eq_ignoring_ctrl_z_cancellable()`]
+/// on them.
+///
+/// [`eq_ignoring_ctrl_z_cancellable()`]: crate::eq_ignoring_ctrl_z_cancellable()
+pub fn files_eq_ignoring_ctrl_z_cancellable<P1, P2>(
+    a: P1,
+    b: P2,
+    token: &CancelToken,
+) -> Result<bool>
+where
+    P1: AsRef<Path>,
+    P2: AsRef<Path>,
+{
+    eq_ignoring_ctrl_z_cancellable(try!(File::open(a)), try!(File::open(b)), token)
+}
+
+/// Compares two readers, checking `token` for cancellation before each pair of buffers is read.
+fn eq_ignoring_ctrl_z_with<R1, R2>(a: R1, b: R2, token: Option<&CancelToken>) -> Result<bool>
+where
+    R1: Read,
+    R2: Read,
+{
+    let mut a = ReadToCtrlZ::new(a);
+    let mut b = ReadToCtrlZ::new(b);
+    let mut a_buf = [0; BUFFER_SIZE];
+    let mut b_buf = [0; BUFFER_SIZE];
+    loop {
+        if let Some(token) = token {
+            try!(cancel::check(token, a.bytes_delivered()));
+        }
+        let a_len = try!(fill(&mut a, &mut a_buf));
+        let b_len = try!(fill(&mut b, &mut b_buf));
+        if a_buf[..a_len] != b_buf[..b_len] {
+            return Ok(false);
+        }
+        if a_len < BUFFER_SIZE {
+            return Ok(true);
+        }
+    }
+}
+
+/// Reads as many bytes as possible into `buf`, returning the number of bytes read.
+///
+/// Fewer bytes than the length of `buf` are only returned once the end has been reached.
+fn fill<R>(reader: &mut R, buf: &mut [u8]) -> Result<usize>
+where
+    R: Read,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::eq_ignoring_ctrl_z;
+    use super::eq_ignoring_ctrl_z_cancellable;
+    use super::files_eq_ignoring_ctrl_z;
+    use super::files_eq_ignoring_ctrl_z_cancellable;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Write;
+    use std::sync::mpsc;
+    use std::thread;
+    use CancelToken;
+    use Cancelled;
+    use ClosureReader;
+
+    #[test]
+    fn equal() {
+        assert_ok_eq!(eq_ignoring_ctrl_z(b"foo" as &[u8], b"foo" as &[u8]), true);
+        assert_ok_eq!(eq_ignoring_ctrl_z(b"" as &[u8], b"\x1a" as &[u8]), true);
+    }
+
+    #[test]
+    fn differing_in_last_byte() {
+        assert_ok_eq!(
+            eq_ignoring_ctrl_z(b"foo\x1a" as &[u8], b"fob\x1a" as &[u8]),
+            false
+        );
+        assert_ok_eq!(eq_ignoring_ctrl_z(b"foo" as &[u8], b"fo" as &[u8]), false);
+    }
+
+    #[test]
+    fn differing_only_in_tail_convention() {
+        assert_ok_eq!(
+            eq_ignoring_ctrl_z(b"foo\x1a\0\0\0" as &[u8], b"foo" as &[u8]),
+            true
+        );
+        assert_ok_eq!(
+            eq_ignoring_ctrl_z(b"foo\x1a\x1a\x1a" as &[u8], b"foo\x1abar" as &[u8]),
+            true
+        );
+    }
+
+    #[test]
+    fn longer_than_buffer() {
+        let mut a = vec![b'a'; 3 * super::BUFFER_SIZE];
+        let mut b = a.clone();
+        a.push(b'\x1a');
+
+        assert_ok_eq!(eq_ignoring_ctrl_z(&a[..], &b[..]), true);
+
+        b[2 * super::BUFFER_SIZE + 1] = b'b';
+        assert_ok_eq!(eq_ignoring_ctrl_z(&a[..], &b[..]), false);
+    }
+
+    #[test]
+    fn short_reads() {
+        let mut count = 0;
+        let reader = ClosureReader(|buf: &mut [u8]| {
+            // Reads "foobar" followed by `0x1A` one byte at a time.
+            buf[0] = b"foobar\x1a"[count];
+            count += 1;
+            Ok(1)
+        });
+
+        assert_ok_eq!(eq_ignoring_ctrl_z(reader, b"foobar" as &[u8]), true);
+    }
+
+    #[test]
+    fn error() {
+        let reader = ClosureReader(|_: &mut [u8]| Err(Error::new(ErrorKind::Other, "foo")));
+
+        assert_eq!(
+            assert_err!(eq_ignoring_ctrl_z(reader, b"foo" as &[u8])).kind(),
+            ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn files() {
+        let a = env::temp_dir().join("ctrl_z_compare_files_a");
+        let b = env::temp_dir().join("ctrl_z_compare_files_b");
+        assert_ok!(assert_ok!(File::create(&a)).write_all(b"foo\r\n\x1a\x1a\x1a"));
+        assert_ok!(assert_ok!(File::create(&b)).write_all(b"foo\r\n"));
+
+        assert_ok_eq!(files_eq_ignoring_ctrl_z(&a, &b), true);
+
+        assert_ok!(fs::remove_file(&a));
+        assert_ok!(fs::remove_file(&b));
+    }
+
+    #[test]
+    fn cancellable_completes() {
+        let token = CancelToken::new();
+
+        assert_ok_eq!(
+            eq_ignoring_ctrl_z_cancellable(b"foo\x1a" as &[u8], b"foo" as &[u8], &token),
+            true
+        );
+    }
+
+    #[test]
+    fn cancelled_before_start() {
+        let token = CancelToken::new();
+        token.cancel();
+
+        let error = assert_err!(eq_ignoring_ctrl_z_cancellable(
+            b"foo" as &[u8],
+            b"foo" as &[u8],
+            &token
+        ));
+        assert_eq!(error.kind(), ErrorKind::Other);
+        let cancelled = assert_some!(assert_some!(error.get_ref()).downcast_ref::<Cancelled>());
+        assert_eq!(cancelled.bytes_scanned(), 0);
+    }
+
+    #[test]
+    fn cancelled_unbounded_reader() {
+        let token = CancelToken::new();
+        let handle = token.clone();
+        let (tx, rx) = mpsc::channel();
+        let canceller = thread::spawn(move || {
+            assert_ok!(rx.recv());
+            handle.cancel();
+        });
+        let mut sent = false;
+        // Neither reader ever ends, so only cancellation stops the comparison.
+        let a = ClosureReader(|buf: &mut [u8]| {
+            if !sent {
+                sent = true;
+                assert_ok!(tx.send(()));
+            }
+            for byte in buf.iter_mut() {
+                *byte = b'a';
+            }
+            Ok(buf.len())
+        });
+        let b = ClosureReader(|buf: &mut [u8]| {
+            for byte in buf.iter_mut() {
+                *byte = b'a';
+            }
+            Ok(buf.len())
+        });
+
+        let error = assert_err!(eq_ignoring_ctrl_z_cancellable(a, b, &token));
+        assert_ok!(canceller.join());
+        let cancelled = assert_some!(assert_some!(error.get_ref()).downcast_ref::<Cancelled>());
+        assert_eq!(cancelled.bytes_scanned() % super::BUFFER_SIZE as u64, 0);
+    }
+
+    #[test]
+    fn files_cancelled() {
+        let a = env::temp_dir().join("ctrl_z_compare_files_cancelled_a");
+        let b = env::temp_dir().join("ctrl_z_compare_files_cancelled_b");
+        assert_ok!(assert_ok!(File::create(&a)).write_all(&vec![b'a'; 4 * super::BUFFER_SIZE]));
+        assert_ok!(assert_ok!(File::create(&b)).write_all(&vec![b'a'; 4 * super::BUFFER_SIZE]));
+        let token = CancelToken::new();
+
+        assert_ok_eq!(files_eq_ignoring_ctrl_z_cancellable(&a, &b, &token), true);
+        token.cancel();
+        assert_err!(files_eq_ignoring_ctrl_z_cancellable(&a, &b, &token));
+
+        assert_ok!(fs::remove_file(&a));
+        assert_ok!(fs::remove_file(&b));
+    }
+}
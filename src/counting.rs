@@ -0,0 +1,218 @@
+#[cfg(feature = "vectored")]
+use std::cmp;
+#[cfg(feature = "vectored")]
+use std::io::IoSlice;
+use std::io::Result;
+use std::io::Write;
+
+/// A writer that counts the `0x1A` bytes passing through it.
+///
+/// Bytes are forwarded to the inner writer unchanged. Only bytes accepted by the inner writer are
+/// counted, and nothing is buffered. To count the `0x1A` bytes within a stream without keeping
+/// it, wrap an [`io::sink()`](std::io::sink()).
+///
+/// With the `vectored` feature enabled, which requires Rust 1.36 or later, vectored writes are
+/// also forwarded to the inner writer's `write_vectored()`.
+///
+/// # Example
+/// ```
+/// use ctrl_z::CountingCtrlZSink;
+/// use std::io;
+///
+/// let mut sink = CountingCtrlZSink::new(io::sink());
+///
+/// assert!(io::copy(&mut &b"foo\x1abar\x1a"[..], &mut sink).is_ok());
+/// assert_eq!(sink.count(), 2);
+/// assert_eq!(sink.first_offset(), Some(3));
+/// ```
+pub struct CountingCtrlZSink<W> {
+    /// The internal writer being written to.
+    inner: W,
+    /// The number of `0x1A` bytes written.
+    count: u64,
+    /// The offset of the first `0x1A` byte written, if any.
+    first: Option<u64>,
+    /// The number of bytes written.
+    written: u64,
+}
+
+impl<W> CountingCtrlZSink<W> {
+    /// Creates a new `CountingCtrlZSink`, wrapping the provided writer.
+    pub fn new(inner: W) -> Self {
+        CountingCtrlZSink {
+            inner: inner,
+            count: 0,
+            first: None,
+            written: 0,
+        }
+    }
+
+    /// Returns the number of `0x1A` bytes written so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the offset of the first `0x1A` byte written, or `None` if none have been written.
+    pub fn first_offset(&self) -> Option<u64> {
+        self.first
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `CountingCtrlZSink`, returning the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Counts the `0x1A` bytes within `bytes`, which have just been written.
+    fn tally(&mut self, bytes: &[u8]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            if byte == b'\x1a' {
+                self.count += 1;
+                if self.first.is_none() {
+                    self.first = Some(self.written + i as u64);
+                }
+            }
+        }
+        self.written += bytes.len() as u64;
+    }
+}
+
+impl<W> Write for CountingCtrlZSink<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = try!(self.inner.write(buf));
+        self.tally(&buf[..n]);
+        Ok(n)
+    }
+
+    #[cfg(feature = "vectored")]
+    // The `vectored` feature requires a newer Rust version, so the MSRV does not apply here.
+    #[allow(clippy::incompatible_msrv)]
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> Result<usize> {
+        let n = try!(self.inner.write_vectored(bufs));
+        let mut remaining = n;
+        for buf in bufs {
+            if remaining == 0 {
+                break;
+            }
+            let len = cmp::min(buf.len(), remaining);
+            self.tally(&buf[..len]);
+            remaining -= len;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CountingCtrlZSink;
+    use count_ctrl_z;
+    use find_ctrl_z;
+    use std::cmp;
+    use std::io;
+    #[cfg(feature = "vectored")]
+    use std::io::IoSlice;
+    use std::io::Result;
+    use std::io::Write;
+
+    /// A writer accepting at most `limit` bytes per write.
+    struct Limited {
+        inner: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let len = cmp::min(buf.len(), self.limit);
+            self.inner.extend(buf[..len].iter().cloned());
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    const DATA: &'static [u8] = b"foo\x1abar\x1a\x1abaz\nqux\x1a";
+
+    #[test]
+    fn counts_in_chunks() {
+        for chunk_len in 1..DATA.len() + 1 {
+            let mut sink = CountingCtrlZSink::new(io::sink());
+            for chunk in DATA.chunks(chunk_len) {
+                assert_ok!(sink.write_all(chunk));
+            }
+
+            assert_eq!(sink.count(), count_ctrl_z(DATA) as u64);
+            assert_eq!(sink.first_offset(), find_ctrl_z(DATA).map(|i| i as u64));
+        }
+    }
+
+    #[test]
+    fn counts_partial_writes() {
+        for limit in 1..DATA.len() + 1 {
+            let mut sink = CountingCtrlZSink::new(Limited {
+                inner: Vec::new(),
+                limit: limit,
+            });
+            assert_ok!(sink.write_all(DATA));
+
+            assert_eq!(sink.count(), count_ctrl_z(DATA) as u64);
+            assert_eq!(sink.first_offset(), find_ctrl_z(DATA).map(|i| i as u64));
+            assert_eq!(sink.into_inner().inner, DATA);
+        }
+    }
+
+    #[test]
+    fn no_ctrl_z() {
+        let mut sink = CountingCtrlZSink::new(Vec::new());
+        assert_ok!(sink.write_all(b"foobar"));
+
+        assert_eq!(sink.count(), 0);
+        assert_none!(sink.first_offset());
+        assert_eq!(sink.get_ref(), b"foobar");
+    }
+
+    #[cfg(feature = "vectored")]
+    #[test]
+    fn counts_vectored() {
+        for split in 0..DATA.len() + 1 {
+            let mut sink = CountingCtrlZSink::new(Vec::new());
+            let bufs = [IoSlice::new(&DATA[..split]), IoSlice::new(&DATA[split..])];
+
+            assert_ok_eq!(sink.write_vectored(&bufs), DATA.len());
+            assert_eq!(sink.count(), count_ctrl_z(DATA) as u64);
+            assert_eq!(sink.first_offset(), find_ctrl_z(DATA).map(|i| i as u64));
+        }
+    }
+
+    #[cfg(feature = "vectored")]
+    #[test]
+    fn counts_partial_vectored() {
+        // The default `write_vectored()` only writes the first nonempty buffer.
+        let mut sink = CountingCtrlZSink::new(Limited {
+            inner: Vec::new(),
+            limit: 5,
+        });
+        let bufs = [IoSlice::new(b"foo\x1abar\x1a"), IoSlice::new(b"\x1a")];
+
+        assert_ok_eq!(sink.write_vectored(&bufs), 5);
+        assert_eq!(sink.count(), 1);
+        assert_some_eq!(sink.first_offset(), 3);
+    }
+}
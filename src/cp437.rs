@@ -0,0 +1,193 @@
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// The characters assigned to the bytes `0x00` through `0x1F` and `0x7F` when displayed as glyphs.
+///
+/// `0x00` has no glyph, so it is mapped to `U+0000`.
+const CONTROL_GLYPHS: [char; 32] = [
+    '\u{0}', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄',
+    '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+];
+
+/// The glyph assigned to the byte `0x7F`.
+const DELETE_GLYPH: char = '⌂';
+
+/// The characters assigned to the bytes `0x80` through `0xFF`.
+const HIGH: [char; 128] = [
+    // 0x80
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    // 0x90
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    // 0xA0
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    // 0xB0
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    // 0xC0
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    // 0xD0
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    // 0xE0
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    // 0xF0
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Decodes a single CP437 byte, optionally mapping control bytes to their glyphs.
+fn decode(byte: u8, glyphs: bool) -> char {
+    if byte >= 0x80 {
+        HIGH[(byte - 0x80) as usize]
+    } else if glyphs && byte < 0x20 {
+        CONTROL_GLYPHS[byte as usize]
+    } else if glyphs && byte == 0x7f {
+        DELETE_GLYPH
+    } else {
+        byte as char
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, decoding them from
+    /// code page 437 and appending them to `buf`.
+    ///
+    /// Code page 437 is the character set of the original IBM PC, used by most DOS-era text files.
+    /// Every byte is decoded, so this never fails on the content itself. The bytes `0x80` through
+    /// `0xFF` are decoded to their accented letters, box-drawing characters, and symbols. Control
+    /// bytes, `0x00` through `0x1F` and `0x7F`, are passed through unchanged, preserving line
+    /// endings and tabs; use
+    /// [`read_to_string_cp437_with_glyphs()`](ReadToCtrlZ::read_to_string_cp437_with_glyphs()) to
+    /// decode them to their glyphs instead.
+    ///
+    /// Returns the number of bytes read, which may differ from the number of bytes appended to
+    /// `buf`.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"\xda\xc4\xbf\r\n\xb3\xe9\xb3\x1a\xff"[..]);
+    /// let mut output = String::new();
+    ///
+    /// assert_eq!(reader.read_to_string_cp437(&mut output).unwrap(), 8);
+    /// assert_eq!(output, "┌─┐\r\n│Θ│");
+    /// ```
+    pub fn read_to_string_cp437(&mut self, buf: &mut String) -> Result<usize> {
+        self.read_cp437(buf, false)
+    }
+
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, decoding them from
+    /// code page 437, including control bytes, and appending them to `buf`.
+    ///
+    /// This is the same as [`read_to_string_cp437()`](ReadToCtrlZ::read_to_string_cp437()), except
+    /// that the control bytes `0x01` through `0x1F` and `0x7F` are decoded to the glyphs code page
+    /// 437 assigns them, such as `☺` for `0x01` and `♪` for `0x0D`. This matches how the bytes
+    /// were displayed on screen, but also replaces line endings and tabs. `0x00` is passed through
+    /// unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"\x01\x03\x7f\x1a"[..]);
+    /// let mut output = String::new();
+    ///
+    /// assert_eq!(reader.read_to_string_cp437_with_glyphs(&mut output).unwrap(), 3);
+    /// assert_eq!(output, "☺♥⌂");
+    /// ```
+    pub fn read_to_string_cp437_with_glyphs(&mut self, buf: &mut String) -> Result<usize> {
+        self.read_cp437(buf, true)
+    }
+
+    /// Reads all bytes until the `0x1A` byte, decoding them from code page 437 into `buf`.
+    fn read_cp437(&mut self, buf: &mut String, glyphs: bool) -> Result<usize> {
+        let mut bytes = Vec::new();
+        let n = try!(self.read_to_end(&mut bytes));
+        buf.extend(bytes.iter().map(|&byte| decode(byte, glyphs)));
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn well_known_mappings() {
+        assert_eq!(decode(0xb3, false), '│');
+        assert_eq!(decode(0xe9, false), 'Θ');
+        assert_eq!(decode(0x80, false), 'Ç');
+        assert_eq!(decode(0x82, false), 'é');
+        assert_eq!(decode(0xc9, false), '╔');
+        assert_eq!(decode(0xdb, false), '█');
+        assert_eq!(decode(0xe1, false), 'ß');
+        assert_eq!(decode(0xff, false), '\u{a0}');
+    }
+
+    #[test]
+    fn ascii_unchanged() {
+        for byte in 0x20..0x7f {
+            assert_eq!(decode(byte, false), byte as char);
+            assert_eq!(decode(byte, true), byte as char);
+        }
+    }
+
+    #[test]
+    fn control_bytes() {
+        assert_eq!(decode(0x01, false), '\u{1}');
+        assert_eq!(decode(0x0d, false), '\r');
+        assert_eq!(decode(0x7f, false), '\u{7f}');
+
+        assert_eq!(decode(0x00, true), '\u{0}');
+        assert_eq!(decode(0x01, true), '☺');
+        assert_eq!(decode(0x0d, true), '♪');
+        assert_eq!(decode(0x1a, true), '→');
+        assert_eq!(decode(0x7f, true), '⌂');
+    }
+
+    #[test]
+    fn read_to_string_cp437_stops_at_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"caf\x82\x1a\xb3" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string_cp437(&mut output), 4);
+        assert_eq!(output, "café");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn read_to_string_cp437_appends() {
+        let mut output = String::from("foo");
+        let mut reader = ReadToCtrlZ::new(b"\xe0\xe1" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string_cp437(&mut output), 2);
+        assert_eq!(output, "fooαß");
+    }
+
+    #[test]
+    fn read_to_string_cp437_with_glyphs_stops_at_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"a\tb\x1a\x01" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string_cp437_with_glyphs(&mut output), 3);
+        assert_eq!(output, "a○b");
+    }
+
+    #[test]
+    fn read_to_string_cp437_error() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+
+        let error = assert_err!(reader.read_to_string_cp437(&mut output));
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(output, "");
+    }
+}
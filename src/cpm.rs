@@ -0,0 +1,292 @@
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::iter;
+#[cfg(feature = "fused")]
+use std::iter::FusedIterator;
+
+/// The size of a CP/M record.
+const CPM_RECORD_SIZE: usize = 128;
+
+/// A single fixed-size record read by [`CpmRecords`].
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the fields `data`, `len`,
+/// and `partial`.
+///
+/// [`CpmRecords`]: crate::CpmRecords
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Record {
+    /// The raw bytes of the record, including any padding.
+    data: Vec<u8>,
+    /// The number of bytes preceding the `0x1A` byte, or the length of `data` if there is none.
+    len: usize,
+    /// Whether the physical input ended before the record was complete.
+    partial: bool,
+}
+
+impl Record {
+    /// Returns the raw bytes of the record, including the `0x1A` byte and any padding following
+    /// it.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the logical bytes of the record, which are the bytes preceding the `0x1A` byte.
+    pub fn logical_data(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+
+    /// Returns the logical length of the record.
+    ///
+    /// This is the full record size for records without a `0x1A` byte, and the number of bytes
+    /// preceding it otherwise.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the record's logical length is zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether the physical input ended before the record was complete.
+    ///
+    /// CP/M files are always a multiple of the record size, so this indicates the input was
+    /// truncated or did not originate from CP/M.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+}
+
+/// An iterator over the fixed-size records of a CP/M file.
+///
+/// CP/M stores files as a sequence of 128-byte records, marking the end of the text within the
+/// final record with a `0x1A` byte and padding the remainder. Each [`Record`] yielded provides the
+/// raw record bytes along with its logical length. Iteration stops after the record containing the
+/// `0x1A` byte, or at the end of the reader.
+///
+/// # Example
+/// ```
+/// use ctrl_z::CpmRecords;
+///
+/// let mut file = vec![b'a'; 128];
+/// file.extend(b"foo\x1a".iter().cloned());
+/// file.extend(vec![0x1a; 124]);
+///
+/// let records = CpmRecords::new(&file[..]).map(|record| record.unwrap());
+/// assert_eq!(records.map(|record| record.len()).collect::<Vec<_>>(), [128, 3]);
+/// ```
+///
+/// [`Record`]: crate::Record
+pub struct CpmRecords<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// The size of each record.
+    record_size: usize,
+    /// Whether or not iteration has finished.
+    finished: bool,
+}
+
+impl<R> CpmRecords<R> {
+    /// Creates a new `CpmRecords`, reading 128-byte records from the provided reader.
+    pub fn new(inner: R) -> Self {
+        CpmRecords::with_record_size(CPM_RECORD_SIZE, inner)
+    }
+
+    /// Creates a new `CpmRecords`, reading records of `record_size` bytes.
+    ///
+    /// # Panics
+    /// Panics if `record_size` is zero.
+    pub fn with_record_size(record_size: usize, inner: R) -> Self {
+        assert!(record_size > 0, "record size must be nonzero");
+        CpmRecords {
+            inner: inner,
+            record_size: record_size,
+            finished: false,
+        }
+    }
+
+    /// Unwraps this `CpmRecords`, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> CpmRecords<R>
+where
+    R: Read,
+{
+    /// Reads as many bytes as possible into `buf`, returning the number of bytes read.
+    fn fill(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.inner.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(filled)
+    }
+}
+
+impl<R> Iterator for CpmRecords<R>
+where
+    R: Read,
+{
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let mut data = iter::repeat(0).take(self.record_size).collect::<Vec<_>>();
+        let filled = match self.fill(&mut data) {
+            Ok(filled) => filled,
+            Err(error) => {
+                self.finished = true;
+                return Some(Err(error));
+            }
+        };
+        if filled == 0 {
+            self.finished = true;
+            return None;
+        }
+        data.truncate(filled);
+
+        let len = match data.iter().position(|&byte| byte == b'\x1a') {
+            Some(len) => {
+                self.finished = true;
+                len
+            }
+            None => filled,
+        };
+        Some(Ok(Record {
+            data: data,
+            len: len,
+            partial: filled < self.record_size,
+        }))
+    }
+}
+
+#[cfg(feature = "fused")]
+#[allow(clippy::incompatible_msrv)]
+impl<R> FusedIterator for CpmRecords<R> where R: Read {}
+
+#[cfg(test)]
+mod tests {
+    use super::CpmRecords;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+
+    #[cfg(feature = "fused")]
+    assert_impl_all!(super::CpmRecords<&'static [u8]>: std::iter::FusedIterator);
+
+    #[test]
+    fn exact_multiple() {
+        let file = [b'a'; 256];
+        let records = CpmRecords::new(&file[..])
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 2);
+        assert!(records
+            .iter()
+            .all(|record| record.len() == 128 && !record.is_partial()));
+    }
+
+    #[test]
+    fn padded_final_record() {
+        let mut file = vec![b'a'; 128];
+        file.extend(b"foo\x1a".iter().cloned());
+        file.extend(vec![b'\x1a'; 124]);
+        // Bytes in records following the `0x1A` byte are not read.
+        file.extend(vec![b'b'; 128]);
+        let records = CpmRecords::new(&file[..])
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), 128);
+        assert_eq!(records[1].len(), 3);
+        assert_eq!(records[1].logical_data(), b"foo");
+        assert_eq!(records[1].data().len(), 128);
+        assert!(!records[1].is_partial());
+    }
+
+    #[test]
+    fn no_ctrl_z() {
+        let file = [b'a'; 200];
+        let records = CpmRecords::new(&file[..])
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), 128);
+        assert!(!records[0].is_partial());
+        assert_eq!(records[1].len(), 72);
+        assert!(records[1].is_partial());
+    }
+
+    #[test]
+    fn partial_record_with_ctrl_z() {
+        let records = CpmRecords::new(b"foo\x1a" as &[u8])
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].len(), 3);
+        assert!(records[0].is_partial());
+    }
+
+    #[test]
+    fn empty() {
+        assert_none!(CpmRecords::new(b"" as &[u8]).next());
+    }
+
+    #[test]
+    fn record_size() {
+        let records = CpmRecords::with_record_size(4, b"foobar\x1a\x1a" as &[u8])
+            .map(|record| record.unwrap().len())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records, [4, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn record_size_zero() {
+        CpmRecords::with_record_size(0, b"" as &[u8]);
+    }
+
+    #[test]
+    fn error() {
+        let mut records = CpmRecords::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+
+        assert_eq!(
+            assert_err!(assert_some!(records.next())).kind(),
+            ErrorKind::Other
+        );
+        assert_none!(records.next());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let record = super::Record {
+            data: b"ab\x1a".to_vec(),
+            len: 2,
+            partial: true,
+        };
+        let json = assert_ok!(serde_json::to_string(&record));
+
+        assert_eq!(json, r#"{"data":[97,98,26],"len":2,"partial":true}"#);
+        assert_ok_eq!(serde_json::from_str::<super::Record>(&json), record);
+    }
+}
@@ -0,0 +1,408 @@
+use scan::find_ctrl_z;
+use std::cmp;
+use std::io::BufRead;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::iter;
+
+/// An in-memory file image whose logical content ends at its first `0x1A` byte.
+///
+/// This is like a [`Cursor`](std::io::Cursor) over a `Vec<u8>`, except that the first `0x1A` byte
+/// of the image is treated as the end-of-file marker. Reading stops at the marker, while writing
+/// overwrites or extends the logical content in front of it. Seeking is relative to the whole
+/// image, including the marker and anything following it, such as padding.
+///
+/// A write that would overwrite the marker is cut short in front of it. Once no room remains, an
+/// error of kind [`ErrorKind::InvalidInput`] is returned. With
+/// [`relocate_marker()`](CtrlZCursor::relocate_marker()) enabled, the marker is instead moved
+/// directly after the written bytes. Writing starting past the marker is always an error. A
+/// written `0x1A` byte becomes the new marker if it precedes the current one.
+///
+/// If the image contains no `0x1A` byte, it has no marker, and reading and writing behave exactly
+/// as for a `Cursor`.
+///
+/// # Example
+/// ```
+/// use ctrl_z::CtrlZCursor;
+/// use std::io::Read;
+/// use std::io::Write;
+///
+/// let mut cursor = CtrlZCursor::new(b"foo\x1a\x1a\x1a".to_vec()).relocate_marker(true);
+/// cursor.set_position(3);
+/// cursor.write_all(b"bar").unwrap();
+/// assert_eq!(cursor.get_ref(), b"foobar\x1a");
+///
+/// cursor.set_position(0);
+/// let mut output = String::new();
+/// assert!(cursor.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foobar");
+/// ```
+///
+/// [`ErrorKind::InvalidInput`]: std::io::ErrorKind::InvalidInput
+#[derive(Clone, Debug)]
+pub struct CtrlZCursor {
+    /// The whole image, including the marker and anything following it.
+    data: Vec<u8>,
+    /// The position of the cursor within the image.
+    pos: u64,
+    /// The offset of the marker, or the length of the image if it has no marker.
+    logical: usize,
+    /// Whether a write overwriting the marker moves it, rather than being cut short.
+    relocate: bool,
+}
+
+impl CtrlZCursor {
+    /// Creates a new `CtrlZCursor` over `data`, positioned at its start.
+    pub fn new(data: Vec<u8>) -> Self {
+        let logical = find_ctrl_z(&data).unwrap_or(data.len());
+        CtrlZCursor {
+            data: data,
+            pos: 0,
+            logical: logical,
+            relocate: false,
+        }
+    }
+
+    /// Sets whether a write overwriting the marker moves it directly after the written bytes.
+    ///
+    /// By default, such writes are cut short in front of the marker.
+    pub fn relocate_marker(mut self, relocate: bool) -> Self {
+        self.relocate = relocate;
+        self
+    }
+
+    /// Returns the length of the logical content, which is the offset of the marker.
+    ///
+    /// If the image has no marker, this is the length of the whole image.
+    pub fn logical_len(&self) -> usize {
+        self.logical
+    }
+
+    /// Returns whether the image has a marker.
+    pub fn has_marker(&self) -> bool {
+        self.logical < self.data.len()
+    }
+
+    /// Moves the marker to offset `pos`, making the first `pos` bytes the logical content.
+    ///
+    /// Anything following the current marker, such as padding, is discarded. If `pos` is past the
+    /// current logical content, the gap is filled with `0x00` bytes.
+    pub fn set_marker_at(&mut self, pos: usize) {
+        let len = cmp::min(pos, self.logical);
+        self.data.truncate(len);
+        self.data.extend(iter::repeat(0).take(pos - len));
+        self.data.push(b'\x1a');
+        self.logical = pos;
+    }
+
+    /// Removes the marker, along with anything following it.
+    ///
+    /// Returns whether the image had a marker.
+    pub fn remove_marker(&mut self) -> bool {
+        let had_marker = self.has_marker();
+        self.data.truncate(self.logical);
+        had_marker
+    }
+
+    /// Returns the position of the cursor within the image.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Sets the position of the cursor within the image.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+
+    /// Returns the logical content, preceding the marker.
+    pub fn logical(&self) -> &[u8] {
+        &self.data[..self.logical]
+    }
+
+    /// Returns the whole image, including the marker and anything following it.
+    pub fn get_ref(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Unwraps this `CtrlZCursor`, returning the whole image.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl Read for CtrlZCursor {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = {
+            let available = try!(self.fill_buf());
+            let n = cmp::min(available.len(), buf.len());
+            for (dst, &src) in buf.iter_mut().zip(&available[..n]) {
+                *dst = src;
+            }
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for CtrlZCursor {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        let start = cmp::min(self.pos, self.logical as u64) as usize;
+        Ok(&self.data[start..self.logical])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos += amount as u64;
+    }
+}
+
+impl Write for CtrlZCursor {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let marker = self.has_marker();
+        if marker && self.pos > self.logical as u64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "write starts past the 0x1A marker",
+            ));
+        }
+        let pos = self.pos as usize;
+        let len = if marker && !self.relocate {
+            cmp::min(buf.len(), self.logical - pos)
+        } else {
+            buf.len()
+        };
+        if len == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "write would overwrite the 0x1A marker",
+            ));
+        }
+        let buf = &buf[..len];
+        let end = pos + len;
+        if pos > self.data.len() {
+            let gap = pos - self.data.len();
+            self.data.extend(iter::repeat(0).take(gap));
+        }
+        let overlap = cmp::min(end, self.data.len()) - pos;
+        for (dst, &src) in self.data[pos..pos + overlap].iter_mut().zip(buf) {
+            *dst = src;
+        }
+        self.data.extend(buf[overlap..].iter().cloned());
+        if marker && end > self.logical {
+            // The marker was overwritten, so it is moved directly after the written bytes.
+            if end < self.data.len() {
+                self.data[end] = b'\x1a';
+            } else {
+                self.data.push(b'\x1a');
+            }
+            self.logical = end;
+        } else if !marker {
+            self.logical = self.data.len();
+        }
+        if let Some(i) = find_ctrl_z(buf) {
+            self.logical = pos + i;
+        }
+        self.pos = end as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for CtrlZCursor {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(offset) => {
+                self.pos = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(offset) => (self.data.len() as u64, offset),
+            SeekFrom::Current(offset) => (self.pos, offset),
+        };
+        let pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(0u64.wrapping_sub(offset as u64))
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CtrlZCursor;
+    use std::io::BufRead;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::io::Write;
+
+    #[test]
+    fn read_stops_at_marker() {
+        let mut output = Vec::new();
+        let mut cursor = CtrlZCursor::new(b"foo\x1abar".to_vec());
+
+        assert_ok_eq!(cursor.read_to_end(&mut output), 3);
+        assert_eq!(output, b"foo");
+        assert_eq!(cursor.logical_len(), 3);
+        assert!(cursor.has_marker());
+    }
+
+    #[test]
+    fn read_without_marker() {
+        let mut output = Vec::new();
+        let mut cursor = CtrlZCursor::new(b"foo".to_vec());
+
+        assert_ok_eq!(cursor.read_to_end(&mut output), 3);
+        assert_eq!(cursor.logical_len(), 3);
+        assert!(!cursor.has_marker());
+    }
+
+    #[test]
+    fn buf_read() {
+        let mut cursor = CtrlZCursor::new(b"foo\nbar\x1abaz".to_vec());
+
+        let lines = (&mut cursor)
+            .lines()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["foo", "bar"]);
+        assert_ok_eq!(cursor.fill_buf(), b"");
+    }
+
+    #[test]
+    fn write_cut_short_at_marker() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1a".to_vec());
+
+        assert_ok_eq!(cursor.write(b"barbaz"), 3);
+        assert_eq!(cursor.get_ref(), b"bar\x1a");
+        let error = assert_err!(cursor.write(b"baz"));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn write_all_past_marker() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1a".to_vec());
+
+        assert_err!(cursor.write_all(b"barbaz"));
+        assert_eq!(cursor.get_ref(), b"bar\x1a");
+    }
+
+    #[test]
+    fn write_starting_past_marker() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1abar".to_vec()).relocate_marker(true);
+        cursor.set_position(5);
+
+        assert_err!(cursor.write(b"baz"));
+        assert_eq!(cursor.get_ref(), b"foo\x1abar");
+    }
+
+    #[test]
+    fn write_relocates_marker() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1aXYZ".to_vec()).relocate_marker(true);
+        cursor.set_position(3);
+
+        assert_ok!(cursor.write_all(b"ab"));
+        // The marker follows the written bytes, and the rest of the padding is kept.
+        assert_eq!(cursor.get_ref(), b"fooab\x1aZ");
+        assert_eq!(cursor.logical_len(), 5);
+
+        assert_ok!(cursor.write_all(b"cdef"));
+        assert_eq!(cursor.get_ref(), b"fooabcdef\x1a");
+        assert_eq!(cursor.logical(), b"fooabcdef");
+    }
+
+    #[test]
+    fn write_marker_byte() {
+        let mut cursor = CtrlZCursor::new(b"foobar\x1a".to_vec());
+
+        assert_ok!(cursor.write_all(b"a\x1a"));
+        assert_eq!(cursor.logical(), b"a");
+    }
+
+    #[test]
+    fn write_without_marker_extends() {
+        let mut cursor = CtrlZCursor::new(Vec::new());
+        cursor.set_position(2);
+
+        assert_ok!(cursor.write_all(b"foo"));
+        assert_eq!(cursor.get_ref(), b"\0\0foo");
+        assert_eq!(cursor.logical_len(), 5);
+    }
+
+    #[test]
+    fn set_marker_earlier() {
+        let mut cursor = CtrlZCursor::new(b"foobar\x1a\x1a".to_vec());
+
+        cursor.set_marker_at(3);
+
+        assert_eq!(cursor.get_ref(), b"foo\x1a");
+    }
+
+    #[test]
+    fn set_marker_later() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1a\x1a".to_vec());
+
+        cursor.set_marker_at(5);
+
+        assert_eq!(cursor.get_ref(), b"foo\0\0\x1a");
+        assert_eq!(cursor.logical_len(), 5);
+    }
+
+    #[test]
+    fn remove_marker() {
+        let mut cursor = CtrlZCursor::new(b"foo\x1a\0\0".to_vec());
+
+        assert!(cursor.remove_marker());
+        assert_eq!(cursor.get_ref(), b"foo");
+        assert!(!cursor.remove_marker());
+    }
+
+    #[test]
+    fn seek() {
+        let mut cursor = CtrlZCursor::new(b"foobar\x1abaz".to_vec());
+
+        assert_ok_eq!(cursor.seek(SeekFrom::End(-4)), 6);
+        assert_ok_eq!(cursor.read(&mut [0; 4]), 0);
+        assert_ok_eq!(cursor.seek(SeekFrom::Current(-3)), 3);
+        let mut output = String::new();
+        assert_ok!(cursor.read_to_string(&mut output));
+        assert_eq!(output, "bar");
+        assert_err!(cursor.seek(SeekFrom::Current(-7)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut cursor = CtrlZCursor::new(Vec::new()).relocate_marker(true);
+        assert_ok!(cursor.write_all(b"foo\r\n"));
+        cursor.set_marker_at(5);
+        assert_ok!(cursor.write_all(b"bar\r\n"));
+        assert_eq!(cursor.get_ref(), b"foo\r\nbar\r\n\x1a");
+
+        cursor.set_marker_at(3);
+        assert_ok!(cursor.seek(SeekFrom::Start(0)));
+        let mut output = String::new();
+        assert_ok!(cursor.read_to_string(&mut output));
+        assert_eq!(output, "foo");
+    }
+}
@@ -0,0 +1,224 @@
+use std::cmp;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+
+/// A reader to read a dBASE III memo field, ending at two consecutive `0x1A` bytes.
+///
+/// dBASE III memo (`.DBT`) files terminate each memo field with the pair `0x1A 0x1A`. A single
+/// `0x1A` byte is read as a regular byte, since it may legitimately appear within the field. A pair
+/// split across buffers of the inner reader is still recognized.
+///
+/// A lone `0x1A` byte directly before the end of the inner reader is not the end of the field, and
+/// is read as a regular byte, as it would be anywhere else.
+///
+/// Unlike [`ReadToCtrlZ`], no bytes past the terminating pair are read from the inner reader, so it
+/// is positioned directly after the pair once reading has been terminated.
+///
+/// # Example
+/// ```
+/// use ctrl_z::DbaseMemoReader;
+/// use std::io::Read;
+///
+/// let mut reader = DbaseMemoReader::new(&b"foo\x1abar\x1a\x1abaz"[..]);
+/// let mut output = Vec::new();
+///
+/// // The single `0x1A` byte is part of the field.
+/// assert!(reader.read_to_end(&mut output).is_ok());
+/// assert_eq!(output, b"foo\x1abar");
+/// assert_eq!(reader.into_inner(), b"baz");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct DbaseMemoReader<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// Whether a `0x1A` byte has been consumed from the inner reader but not yet delivered,
+    /// because it was the last byte of the inner reader's buffer.
+    pending: bool,
+    /// Whether or not two consecutive `0x1A` bytes have been reached.
+    terminated: bool,
+}
+
+impl<R> DbaseMemoReader<R> {
+    /// Creates a new `DbaseMemoReader`, wrapping the provided reader.
+    pub fn new(inner: R) -> Self {
+        DbaseMemoReader {
+            inner: inner,
+            pending: false,
+            terminated: false,
+        }
+    }
+
+    /// Returns whether reading has been terminated by two consecutive `0x1A` bytes.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// A `0x1A` byte at the end of the inner reader's buffer may already have been consumed, while
+    /// waiting to see whether the next byte is also `0x1A`.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `DbaseMemoReader`, returning the underlying reader.
+    ///
+    /// A `0x1A` byte at the end of the inner reader's buffer may already have been consumed, while
+    /// waiting to see whether the next byte is also `0x1A`.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for DbaseMemoReader<R>
+where
+    R: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.terminated || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pending {
+            let second = {
+                let available = try!(self.inner.fill_buf());
+                available.first() == Some(&b'\x1a')
+            };
+            self.pending = false;
+            if second {
+                self.inner.consume(1);
+                self.terminated = true;
+                return Ok(0);
+            }
+            buf[0] = b'\x1a';
+            return Ok(1);
+        }
+        let (n, consumed) = {
+            let available = try!(self.inner.fill_buf());
+            let len = cmp::min(available.len(), buf.len());
+            let mut end = (len, len);
+            for i in 0..len {
+                if available[i] != b'\x1a' {
+                    continue;
+                }
+                if i + 1 == available.len() {
+                    // The second byte of a pair may be in the next buffer.
+                    self.pending = true;
+                    end = (i, i + 1);
+                    break;
+                }
+                if available[i + 1] == b'\x1a' {
+                    self.terminated = true;
+                    end = (i, i + 2);
+                    break;
+                }
+            }
+            for (dst, &src) in buf.iter_mut().zip(&available[..end.0]) {
+                *dst = src;
+            }
+            end
+        };
+        self.inner.consume(consumed);
+        if n == 0 && self.pending {
+            return self.read(buf);
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DbaseMemoReader;
+    use std::io::BufReader;
+    use std::io::Read;
+
+    #[test]
+    fn memo_field_with_interior_marker() {
+        let mut output = Vec::new();
+        let mut reader = DbaseMemoReader::new(b"Notes:\x1a see below\x1a\x1a\0\0\0" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 17);
+        assert_eq!(output, b"Notes:\x1a see below");
+        assert!(reader.is_terminated());
+        assert_eq!(reader.into_inner(), b"\0\0\0");
+    }
+
+    #[test]
+    fn pair_at_start() {
+        let mut output = Vec::new();
+        let mut reader = DbaseMemoReader::new(b"\x1a\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 0);
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn no_pair() {
+        let mut output = Vec::new();
+        let mut reader = DbaseMemoReader::new(b"\x1afoo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 8);
+        assert_eq!(output, b"\x1afoo\x1abar");
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn lone_marker_before_eof() {
+        let mut output = Vec::new();
+        let mut reader = DbaseMemoReader::new(b"foo\x1a" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"foo\x1a");
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn pair_straddles_buffers() {
+        let mut output = Vec::new();
+        let inner = BufReader::with_capacity(4, b"foo\x1a\x1abar" as &[u8]);
+        let mut reader = DbaseMemoReader::new(inner);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        assert_eq!(output, b"foo");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn single_marker_straddles_buffers() {
+        let mut output = Vec::new();
+        let inner = BufReader::with_capacity(4, b"foo\x1abar\x1a\x1a" as &[u8]);
+        let mut reader = DbaseMemoReader::new(inner);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 7);
+        assert_eq!(output, b"foo\x1abar");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn one_byte_reads() {
+        let mut reader = DbaseMemoReader::new(b"a\x1ab\x1a\x1ac" as &[u8]);
+        let mut output = Vec::new();
+        let mut byte = [0];
+
+        while assert_ok!(reader.read(&mut byte)) == 1 {
+            output.push(byte[0]);
+        }
+
+        assert_eq!(output, b"a\x1ab");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn read_after_termination() {
+        let mut reader = DbaseMemoReader::new(b"\x1a\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+}
@@ -0,0 +1,438 @@
+use std::cmp;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+#[cfg(all(unix, feature = "from_raw"))]
+use std::os::unix::io::FromRawFd;
+#[cfg(all(unix, feature = "from_raw"))]
+use std::os::unix::io::RawFd;
+#[cfg(all(windows, feature = "from_raw"))]
+use std::os::windows::io::FromRawHandle;
+#[cfg(all(windows, feature = "from_raw"))]
+use std::os::windows::io::RawHandle;
+use std::path::Path;
+use ReadToCtrlZ;
+
+/// The number of bytes examined at a time when counting trailing `0x1A` bytes.
+const TAIL_CHUNK_SIZE: u64 = 128;
+
+/// Reads the contents of a file until a `0x1A` byte or the end of the file.
+///
+/// This is a convenience for opening the file and calling [`read_to_end()`](Read::read_to_end())
+/// on a [`ReadToCtrlZ`] wrapping it.
+///
+/// # Example
+/// ```no_run
+/// let bytes = ctrl_z::read_ctrl_z_file("foo.txt").unwrap();
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub fn read_ctrl_z_file<P>(path: P) -> Result<Vec<u8>>
+where
+    P: AsRef<Path>,
+{
+    let mut bytes = Vec::new();
+    try!(ReadToCtrlZ::new(try!(File::open(path))).read_to_end(&mut bytes));
+    Ok(bytes)
+}
+
+/// Reads the contents of a file until a `0x1A` byte or the end of the file into a `String`.
+///
+/// This is a convenience for opening the file and calling
+/// [`read_to_string()`](Read::read_to_string()) on a [`ReadToCtrlZ`] wrapping it. An error is
+/// returned if the contents are not valid UTF-8.
+///
+/// # Example
+/// ```no_run
+/// let string = ctrl_z::read_ctrl_z_file_to_string("foo.txt").unwrap();
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub fn read_ctrl_z_file_to_string<P>(path: P) -> Result<String>
+where
+    P: AsRef<Path>,
+{
+    let mut string = String::new();
+    try!(ReadToCtrlZ::new(try!(File::open(path))).read_to_string(&mut string));
+    Ok(string)
+}
+
+#[cfg(feature = "from_raw")]
+// The `from_raw` feature requires a newer Rust version, so the MSRV does not apply here.
+#[allow(clippy::incompatible_msrv)]
+impl ReadToCtrlZ<File> {
+    /// Creates a new `ReadToCtrlZ` reading from the file referred to by a raw file descriptor.
+    ///
+    /// This is only available on Unix with the `from_raw` feature enabled, which requires Rust 1.1
+    /// or later.
+    ///
+    /// # Safety
+    /// `fd` must be an open file descriptor. Ownership of it is transferred to the returned
+    /// reader, which closes it when dropped, so it must not be used or closed elsewhere. See
+    /// [`FromRawFd::from_raw_fd()`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(unix)]
+    /// # fn main() {
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::fs::File;
+    /// use std::os::unix::io::IntoRawFd;
+    ///
+    /// let fd = File::open("foo.txt").unwrap().into_raw_fd();
+    /// let reader = unsafe { ReadToCtrlZ::from_raw_fd(fd) };
+    /// # }
+    /// # #[cfg(not(unix))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(unix)]
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        ReadToCtrlZ::new(File::from_raw_fd(fd))
+    }
+
+    /// Creates a new `ReadToCtrlZ` reading from the file referred to by a raw handle.
+    ///
+    /// This is only available on Windows with the `from_raw` feature enabled, which requires Rust
+    /// 1.1 or later.
+    ///
+    /// # Safety
+    /// `handle` must be an open file handle. Ownership of it is transferred to the returned
+    /// reader, which closes it when dropped, so it must not be used or closed elsewhere. See
+    /// [`FromRawHandle::from_raw_handle()`].
+    #[cfg(windows)]
+    pub unsafe fn from_raw_handle(handle: RawHandle) -> Self {
+        ReadToCtrlZ::new(File::from_raw_handle(handle))
+    }
+}
+
+/// Options for [`ensure_terminated_with_options()`].
+///
+/// By default, multiple trailing `0x1A` bytes are left as they are.
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the field `collapse`.
+///
+/// [`ensure_terminated_with_options()`]: crate::ensure_terminated_with_options()
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnsureOptions {
+    /// Whether or not to collapse multiple trailing `0x1A` bytes into one.
+    collapse: bool,
+}
+
+impl EnsureOptions {
+    /// Creates a new `EnsureOptions` with the default options.
+    pub fn new() -> Self {
+        EnsureOptions { collapse: false }
+    }
+
+    /// Sets whether multiple trailing `0x1A` bytes are collapsed into a single one.
+    pub fn collapse(mut self, collapse: bool) -> Self {
+        self.collapse = collapse;
+        self
+    }
+}
+
+/// The change made by [`ensure_terminated()`].
+///
+/// With the `serde` feature enabled, this is serialized as an enum with the variants
+/// `AlreadyTerminated`, `Appended`, and `Collapsed`, the last of which has the field `removed`.
+///
+/// [`ensure_terminated()`]: crate::ensure_terminated()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EnsureOutcome {
+    /// The file already ended with a `0x1A` byte, so it was left unchanged.
+    AlreadyTerminated,
+    /// A `0x1A` byte was appended to the file.
+    Appended,
+    /// The file ended with multiple `0x1A` bytes, and the extra ones were removed.
+    Collapsed {
+        /// The number of `0x1A` bytes removed.
+        removed: u64,
+    },
+}
+
+/// Ensures that a file ends with a `0x1A` byte, appending one if it does not.
+///
+/// Only the end of the file is examined and modified, so the rest of the file is never
+/// rewritten. An empty file has a single `0x1A` byte appended.
+///
+/// # Example
+/// ```no_run
+/// use ctrl_z::EnsureOutcome;
+///
+/// match ctrl_z::ensure_terminated("foo.txt").unwrap() {
+///     EnsureOutcome::Appended => println!("appended a 0x1A byte"),
+///     _ => {}
+/// }
+/// ```
+pub fn ensure_terminated<P>(path: P) -> Result<EnsureOutcome>
+where
+    P: AsRef<Path>,
+{
+    ensure_terminated_with_options(path, EnsureOptions::new())
+}
+
+/// Ensures that a file ends with a `0x1A` byte, using the provided options.
+///
+/// This behaves like [`ensure_terminated()`]. If [collapsing](EnsureOptions::collapse()) is
+/// enabled, a file ending with multiple `0x1A` bytes is truncated so that exactly one remains.
+///
+/// [`ensure_terminated()`]: crate::ensure_terminated()
+pub fn ensure_terminated_with_options<P>(path: P, options: EnsureOptions) -> Result<EnsureOutcome>
+where
+    P: AsRef<Path>,
+{
+    let mut file = try!(OpenOptions::new().read(true).write(true).open(path));
+    let len = try!(file.seek(SeekFrom::End(0)));
+
+    let trailing = if options.collapse {
+        try!(count_trailing_ctrl_z(&mut file, len))
+    } else {
+        try!(count_trailing_ctrl_z(&mut file, cmp::min(len, 1)))
+    };
+    match trailing {
+        0 => {
+            try!(file.seek(SeekFrom::End(0)));
+            try!(file.write_all(b"\x1a"));
+            Ok(EnsureOutcome::Appended)
+        }
+        1 => Ok(EnsureOutcome::AlreadyTerminated),
+        _ => {
+            try!(file.set_len(len - (trailing - 1)));
+            Ok(EnsureOutcome::Collapsed {
+                removed: trailing - 1,
+            })
+        }
+    }
+}
+
+/// Counts the consecutive `0x1A` bytes ending the file, examining at most `limit` bytes.
+fn count_trailing_ctrl_z(file: &mut File, limit: u64) -> Result<u64> {
+    let len = try!(file.seek(SeekFrom::End(0)));
+    let mut chunk = [0; TAIL_CHUNK_SIZE as usize];
+    let mut count = 0;
+    while count < limit {
+        let size = cmp::min(TAIL_CHUNK_SIZE, limit - count);
+        let end = len - count;
+        try!(file.seek(SeekFrom::Start(end - size)));
+        let chunk = &mut chunk[..size as usize];
+        try!(read_fully(file, chunk));
+        let trailing = chunk
+            .iter()
+            .rev()
+            .take_while(|&&byte| byte == b'\x1a')
+            .count() as u64;
+        count += trailing;
+        if trailing < size {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+/// Reads bytes from `file` until `buf` is filled, retrying if interrupted.
+fn read_fully(file: &mut File, buf: &mut [u8]) -> Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..]) {
+            Ok(0) => return Err(Error::new(ErrorKind::Other, "file shortened while reading")),
+            Ok(n) => filled += n,
+            Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ensure_terminated;
+    use super::ensure_terminated_with_options;
+    use super::read_ctrl_z_file;
+    use super::read_ctrl_z_file_to_string;
+    use super::EnsureOptions;
+    use super::EnsureOutcome;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Write;
+    use std::path::Path;
+
+    /// Returns the entire contents of the file at `path`.
+    fn contents(path: &Path) -> Vec<u8> {
+        let mut contents = Vec::new();
+        assert_ok!(assert_ok!(File::open(path)).read_to_end(&mut contents));
+        contents
+    }
+
+    #[test]
+    fn read_file() {
+        let path = env::temp_dir().join("ctrl_z_fs_read_file");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1abar"));
+
+        assert_ok_eq!(read_ctrl_z_file(&path), b"foo".to_vec());
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn read_file_to_string() {
+        let path = env::temp_dir().join("ctrl_z_fs_read_file_to_string");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1a\xff"));
+
+        // Bytes following the `0x1A` byte are not required to be valid UTF-8.
+        assert_ok_eq!(read_ctrl_z_file_to_string(&path), "foo".to_owned());
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn read_file_to_string_invalid_utf8() {
+        let path = env::temp_dir().join("ctrl_z_fs_read_file_to_string_invalid_utf8");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\xff\x1a"));
+
+        assert_err!(read_ctrl_z_file_to_string(&path));
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let path = env::temp_dir().join("ctrl_z_fs_read_missing_file");
+
+        assert_eq!(
+            assert_err!(read_ctrl_z_file(&path)).kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn ensure_already_terminated() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_already_terminated");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1a"));
+
+        assert_ok_eq!(ensure_terminated(&path), EnsureOutcome::AlreadyTerminated);
+        assert_eq!(contents(&path), b"foo\x1a");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn ensure_unterminated() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_unterminated");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo"));
+
+        assert_ok_eq!(ensure_terminated(&path), EnsureOutcome::Appended);
+        assert_eq!(contents(&path), b"foo\x1a");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn ensure_empty() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_empty");
+        assert_ok!(File::create(&path));
+
+        assert_ok_eq!(ensure_terminated(&path), EnsureOutcome::Appended);
+        assert_eq!(contents(&path), b"\x1a");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn ensure_multiple_markers() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_multiple_markers");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1a\x1a\x1a"));
+
+        assert_ok_eq!(ensure_terminated(&path), EnsureOutcome::AlreadyTerminated);
+        assert_eq!(contents(&path), b"foo\x1a\x1a\x1a");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn ensure_collapse() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_collapse");
+        let mut input = b"foo".to_vec();
+        // Spans multiple chunks of the tail.
+        input.extend(vec![b'\x1a'; 300]);
+        assert_ok!(assert_ok!(File::create(&path)).write_all(&input));
+
+        assert_ok_eq!(
+            ensure_terminated_with_options(&path, EnsureOptions::new().collapse(true)),
+            EnsureOutcome::Collapsed { removed: 299 }
+        );
+        assert_eq!(contents(&path), b"foo\x1a");
+        assert_ok_eq!(
+            ensure_terminated_with_options(&path, EnsureOptions::new().collapse(true)),
+            EnsureOutcome::AlreadyTerminated
+        );
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn ensure_collapse_only_markers() {
+        let path = env::temp_dir().join("ctrl_z_fs_ensure_collapse_only_markers");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"\x1a\x1a"));
+
+        assert_ok_eq!(
+            ensure_terminated_with_options(&path, EnsureOptions::new().collapse(true)),
+            EnsureOutcome::Collapsed { removed: 1 }
+        );
+        assert_eq!(contents(&path), b"\x1a");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[cfg(all(unix, feature = "from_raw"))]
+    #[test]
+    fn from_raw_fd() {
+        use std::os::unix::io::IntoRawFd;
+        use ReadToCtrlZ;
+
+        let path = env::temp_dir().join("ctrl_z_fs_from_raw_fd");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1abar"));
+        let fd = assert_ok!(File::open(&path)).into_raw_fd();
+        let mut output = String::new();
+
+        let mut reader = unsafe { ReadToCtrlZ::from_raw_fd(fd) };
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let options = EnsureOptions::new().collapse(true);
+        let json = assert_ok!(serde_json::to_string(&options));
+        assert_eq!(json, r#"{"collapse":true}"#);
+        assert_ok_eq!(serde_json::from_str::<EnsureOptions>(&json), options);
+
+        for &(outcome, expected) in [
+            (EnsureOutcome::AlreadyTerminated, r#""AlreadyTerminated""#),
+            (EnsureOutcome::Appended, r#""Appended""#),
+            (
+                EnsureOutcome::Collapsed { removed: 2 },
+                r#"{"Collapsed":{"removed":2}}"#,
+            ),
+        ]
+        .iter()
+        {
+            let json = assert_ok!(serde_json::to_string(&outcome));
+            assert_eq!(json, expected);
+            assert_ok_eq!(serde_json::from_str::<EnsureOutcome>(&json), outcome);
+        }
+    }
+}
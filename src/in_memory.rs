@@ -0,0 +1,301 @@
+use std::cmp;
+use std::io::Cursor;
+use ReadToCtrlZ;
+
+/// A reader whose remaining bytes are held contiguously in memory.
+///
+/// This is used by [`ReadToCtrlZ::as_logical_slice()`] to borrow the logical content directly
+/// from the inner reader, rather than copying it. It is implemented for `&[u8]` and for
+/// [`Cursor`]s over byte buffers.
+///
+/// [`ReadToCtrlZ::as_logical_slice()`]: crate::ReadToCtrlZ::as_logical_slice()
+pub trait AsInMemory {
+    /// Returns the bytes that remain to be read.
+    fn remaining_slice(&self) -> &[u8];
+
+    /// Marks the first `amount` remaining bytes as read.
+    ///
+    /// `amount` must not exceed the length of [`remaining_slice()`](AsInMemory::remaining_slice()).
+    fn advance(&mut self, amount: usize);
+}
+
+impl<'a> AsInMemory for &'a [u8] {
+    fn remaining_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn advance(&mut self, amount: usize) {
+        let bytes: &'a [u8] = self;
+        *self = &bytes[amount..];
+    }
+}
+
+impl<T> AsInMemory for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn remaining_slice(&self) -> &[u8] {
+        let bytes = self.get_ref().as_ref();
+        let pos = cmp::min(self.position(), bytes.len() as u64) as usize;
+        &bytes[pos..]
+    }
+
+    fn advance(&mut self, amount: usize) {
+        let position = self.position();
+        self.set_position(position + amount as u64);
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: AsInMemory,
+{
+    /// Returns the bytes preceding the next terminating byte, borrowed directly from the inner
+    /// reader without copying them.
+    ///
+    /// The slice starts at the current logical position. Nothing is consumed: the bytes are still
+    /// returned by subsequent reads unless they are marked as read using
+    /// [`consume_logical()`](ReadToCtrlZ::consume_logical()). The inner reader is only scanned
+    /// once; later calls reuse the offset of the terminating byte found by that scan, as does
+    /// [`len_hint()`](ReadToCtrlZ::len_hint()).
+    ///
+    /// [Leading markers](ReadToCtrlZ::skip_leading_markers()) are skipped first. In
+    /// [lenient](ReadToCtrlZ::lenient()) and [quote-aware](ReadToCtrlZ::quote_aware()) modes, the
+    /// end of the logical content cannot be found by a scan alone, so the returned slice is always
+    /// empty. Bytes read ahead, such as by [`peek_n()`](ReadToCtrlZ::peek_n()), are no longer
+    /// contiguous with the inner reader's bytes, so while any remain, only they are returned.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foo\nbar\x1abaz"[..]);
+    ///
+    /// assert_eq!(reader.as_logical_slice(), b"foo\nbar");
+    /// reader.consume_logical(4);
+    ///
+    /// let mut output = String::new();
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "bar");
+    /// ```
+    pub fn as_logical_slice(&mut self) -> &[u8] {
+        let len = self.logical_len();
+        if self.pos < self.buffer.len() {
+            &self.buffer[self.pos..self.pos + len]
+        } else {
+            &self.inner.remaining_slice()[..len]
+        }
+    }
+
+    /// Marks the first `amount` bytes returned by
+    /// [`as_logical_slice()`](ReadToCtrlZ::as_logical_slice()) as read.
+    ///
+    /// The bytes are counted as delivered, exactly as if they had been read.
+    ///
+    /// # Panics
+    /// Panics if `amount` exceeds the length of the slice returned by
+    /// [`as_logical_slice()`](ReadToCtrlZ::as_logical_slice()).
+    pub fn consume_logical(&mut self, amount: usize) {
+        let len = self.logical_len();
+        assert!(
+            amount <= len,
+            "cannot consume {} bytes of a {} byte logical slice",
+            amount,
+            len
+        );
+        if self.pos < self.buffer.len() {
+            self.advance(amount);
+        } else {
+            self.inner.advance(amount);
+            self.inner_read += amount as u64;
+        }
+        self.delivered += amount as u64;
+    }
+
+    /// Returns the length of the slice returned by `as_logical_slice()`.
+    fn logical_len(&mut self) -> usize {
+        if self.terminated || self.exceeded || self.lenient || self.quote.is_some() {
+            return 0;
+        }
+        let terminators = self.terminators;
+        while self.leading {
+            let first = if self.pos < self.buffer.len() {
+                Some(self.buffer[self.pos])
+            } else {
+                self.inner.remaining_slice().first().cloned()
+            };
+            match first {
+                None => return 0,
+                Some(byte) if terminators.contains(byte) => {
+                    if self.pos < self.buffer.len() {
+                        self.advance(1);
+                    } else {
+                        self.inner.advance(1);
+                        self.inner_read += 1;
+                    }
+                }
+                Some(_) => self.leading = false,
+            }
+        }
+        if self.pos < self.buffer.len() {
+            let pending = &self.buffer[self.pos..];
+            return pending
+                .iter()
+                .position(|&byte| terminators.contains(byte))
+                .unwrap_or(pending.len());
+        }
+        if let Some(end) = self.end {
+            return (end - self.delivered) as usize;
+        }
+        let remaining = self.inner.remaining_slice();
+        let len = remaining
+            .iter()
+            .position(|&byte| terminators.contains(byte))
+            .unwrap_or(remaining.len());
+        self.end = Some(self.delivered + len as u64);
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::Cursor;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn slice() {
+        let input = b"foo\x1abar" as &[u8];
+        let mut reader = ReadToCtrlZ::new(input);
+
+        let logical = reader.as_logical_slice();
+        assert_eq!(logical, b"foo");
+        // The slice is borrowed from the input rather than copied.
+        assert_eq!(logical.as_ptr(), input.as_ptr());
+    }
+
+    #[test]
+    fn cursor() {
+        let mut cursor = Cursor::new(b"foobar\x1abaz".to_vec());
+        cursor.set_position(2);
+        let mut reader = ReadToCtrlZ::new(cursor);
+
+        assert_eq!(reader.as_logical_slice(), b"obar");
+    }
+
+    #[test]
+    fn does_not_consume() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_eq!(reader.as_logical_slice(), b"foo");
+        assert_eq!(reader.as_logical_slice(), b"foo");
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn after_read() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+        assert_ok_eq!(reader.read(&mut [0; 2]), 2);
+
+        assert_eq!(reader.as_logical_slice(), b"obar");
+    }
+
+    #[test]
+    fn consume_logical() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz".to_vec()));
+
+        assert_eq!(reader.as_logical_slice(), b"foobar");
+        reader.consume_logical(2);
+        assert_eq!(reader.bytes_delivered(), 2);
+        assert_eq!(reader.as_logical_slice(), b"obar");
+        // The offset of the `0x1A` byte is still correct after consuming.
+        assert_ok_eq!(reader.len_hint(), Some(4));
+
+        let mut output = String::new();
+        assert_ok_eq!(reader.read_to_string(&mut output), 4);
+        assert_eq!(output, "obar");
+    }
+
+    #[test]
+    fn consume_logical_all() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        reader.consume_logical(3);
+
+        assert_eq!(reader.as_logical_slice(), b"");
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn skip_leading_markers() {
+        let mut output = [0; 3];
+        let mut reader = ReadToCtrlZ::new(b"\x1afoobar\x1a" as &[u8]).skip_leading_markers(true);
+
+        assert_eq!(reader.as_logical_slice(), b"foobar");
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(&output, b"foo");
+        assert_eq!(reader.as_logical_slice(), b"bar");
+    }
+
+    #[test]
+    fn quote_aware() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"\"a\x1ab\"cd\x1a" as &[u8]).quote_aware(b'"');
+
+        assert_eq!(reader.as_logical_slice(), b"");
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_eq!(reader.as_logical_slice(), b"");
+
+        let mut rest = Vec::new();
+        assert_ok_eq!(reader.read_to_end(&mut rest), 5);
+        assert_eq!(rest, b"\x1ab\"cd");
+    }
+
+    #[test]
+    fn lenient() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).lenient(true);
+
+        assert_eq!(reader.as_logical_slice(), b"");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot consume 4 bytes of a 3 byte logical slice")]
+    fn consume_logical_too_many() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        reader.consume_logical(4);
+    }
+
+    #[test]
+    fn with_read_ahead() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+        assert_ok_eq!(reader.peek_n(2), b"fo");
+
+        // Only the read-ahead bytes are returned until they are consumed.
+        assert_eq!(reader.as_logical_slice(), b"fo");
+        reader.consume_logical(2);
+        assert_eq!(reader.as_logical_slice(), b"obar");
+    }
+
+    #[test]
+    fn without_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foobar" as &[u8]);
+
+        assert_eq!(reader.as_logical_slice(), b"foobar");
+    }
+
+    #[test]
+    fn terminated() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+        assert_ok!(reader.read_to_end(&mut output));
+
+        assert_eq!(reader.as_logical_slice(), b"");
+    }
+}
@@ -0,0 +1,202 @@
+use is_padding;
+use serde::de::DeserializeOwned;
+use serde_json;
+use serde_json::Deserializer;
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::io::Read;
+use ReadToCtrlZ;
+use TerminatorSet;
+
+/// An error returned by [`json_from_ctrl_z_reader()`].
+///
+/// This type is only available with the `json` feature enabled.
+///
+/// [`json_from_ctrl_z_reader()`]: crate::json_from_ctrl_z_reader()
+#[derive(Debug)]
+pub enum JsonCtrlZError {
+    /// An error occurred while reading from the inner reader.
+    Io(io::Error),
+    /// The bytes preceding the `0x1A` byte are not a valid JSON document.
+    Json {
+        /// The error returned while parsing.
+        error: serde_json::Error,
+        /// The offset of the byte at which the error was detected.
+        offset: u64,
+    },
+    /// Bytes other than padding follow the JSON document before the `0x1A` byte.
+    TrailingData {
+        /// The offset of the first byte following the JSON document that is not padding.
+        offset: u64,
+    },
+}
+
+impl Display for JsonCtrlZError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            JsonCtrlZError::Io(ref error) => write!(formatter, "{}", error),
+            JsonCtrlZError::Json { ref error, offset } => {
+                write!(formatter, "invalid JSON at byte {}: {}", offset, error)
+            }
+            JsonCtrlZError::TrailingData { offset } => {
+                write!(formatter, "trailing data after JSON at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for JsonCtrlZError {
+    fn description(&self) -> &str {
+        match *self {
+            JsonCtrlZError::Io(_) => "I/O error",
+            JsonCtrlZError::Json { .. } => "invalid JSON",
+            JsonCtrlZError::TrailingData { .. } => "trailing data after JSON",
+        }
+    }
+}
+
+impl From<io::Error> for JsonCtrlZError {
+    fn from(error: io::Error) -> Self {
+        JsonCtrlZError::Io(error)
+    }
+}
+
+/// Returns the byte offset within `bytes` of the one-based `line` and `column`.
+fn offset_of(bytes: &[u8], line: usize, column: usize) -> u64 {
+    // The start of each line after the first follows a `\n` byte.
+    let start = if line > 1 {
+        bytes
+            .iter()
+            .enumerate()
+            .filter(|&(_, &byte)| byte == b'\n')
+            .map(|(i, _)| i + 1)
+            .nth(line - 2)
+            .unwrap_or(bytes.len())
+    } else {
+        0
+    };
+    (start + column.saturating_sub(1)) as u64
+}
+
+/// Parses a JSON document from `reader`, reading until a `0x1A` byte.
+///
+/// Only whitespace and `\0` padding may follow the JSON document before the `0x1A` byte or the
+/// end of the reader. Bytes following the `0x1A` byte are never read.
+///
+/// This function is only available with the `json` feature enabled.
+///
+/// # Example
+/// ```
+/// extern crate serde_json;
+/// # extern crate ctrl_z;
+///
+/// use serde_json::Value;
+///
+/// # fn main() {
+/// let value: Value = ctrl_z::json_from_ctrl_z_reader(&b"{\"foo\": 42}\r\n\x1a\x1a"[..]).unwrap();
+///
+/// assert_eq!(value["foo"], 42);
+/// # }
+/// ```
+pub fn json_from_ctrl_z_reader<T, R>(reader: R) -> Result<T, JsonCtrlZError>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    try!(ReadToCtrlZ::new(reader).read_to_end(&mut bytes));
+
+    let mut stream = Deserializer::from_slice(&bytes).into_iter::<T>();
+    let value = match stream.next() {
+        Some(Ok(value)) => value,
+        Some(Err(error)) => {
+            let offset = if error.is_eof() {
+                bytes.len() as u64
+            } else {
+                offset_of(&bytes, error.line(), error.column())
+            };
+            return Err(JsonCtrlZError::Json {
+                error: error,
+                offset: offset,
+            });
+        }
+        None => {
+            // The input is empty, so parsing it again produces the appropriate error.
+            let error = serde_json::from_slice::<T>(&bytes).err().unwrap();
+            return Err(JsonCtrlZError::Json {
+                error: error,
+                offset: bytes.len() as u64,
+            });
+        }
+    };
+
+    let end = stream.byte_offset();
+    let terminators = TerminatorSet::ctrl_z();
+    match bytes[end..]
+        .iter()
+        .position(|&byte| !is_padding(&terminators, byte))
+    {
+        Some(i) => Err(JsonCtrlZError::TrailingData {
+            offset: (end + i) as u64,
+        }),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_from_ctrl_z_reader;
+    use super::JsonCtrlZError;
+    use serde_json::Value;
+
+    #[test]
+    fn valid_json() {
+        let value: Value = assert_ok!(json_from_ctrl_z_reader(
+            b"{\"foo\": [1, 2]}\r\n\0\x1a\x1a" as &[u8]
+        ));
+
+        assert_eq!(value["foo"][1], 2);
+    }
+
+    #[test]
+    fn valid_json_garbage_after_marker() {
+        let value: Vec<u32> = assert_ok!(json_from_ctrl_z_reader(b"[1, 2]\x1agarbage" as &[u8]));
+
+        assert_eq!(value, [1, 2]);
+    }
+
+    #[test]
+    fn trailing_garbage() {
+        match json_from_ctrl_z_reader::<Value, _>(b"[1, 2] \nfoo\x1a" as &[u8]) {
+            Err(JsonCtrlZError::TrailingData { offset }) => assert_eq!(offset, 8),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn truncated_json() {
+        match json_from_ctrl_z_reader::<Value, _>(b"{\"foo\":\n [1, \x1a2]}" as &[u8]) {
+            Err(JsonCtrlZError::Json { offset, .. }) => assert_eq!(offset, 13),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn invalid_json() {
+        match json_from_ctrl_z_reader::<Value, _>(b"[1, x]\x1a" as &[u8]) {
+            Err(JsonCtrlZError::Json { offset, .. }) => assert_eq!(offset, 4),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn empty() {
+        match json_from_ctrl_z_reader::<Value, _>(b" \x1a" as &[u8]) {
+            Err(JsonCtrlZError::Json { offset, .. }) => assert_eq!(offset, 1),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
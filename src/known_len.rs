@@ -0,0 +1,178 @@
+use std::cmp;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use TerminatorSet;
+
+/// A reader whose remaining length can be determined without consuming any of its bytes.
+///
+/// This is used by [`ReadToCtrlZ::len_hint()`] to determine the exact number of bytes remaining
+/// before the next terminating byte. It is implemented for in-memory sources, such as `&[u8]` and
+/// [`Cursor`], which can be scanned directly, and for seekable sources, such as [`File`], which
+/// are scanned by reading ahead and then seeking back to the original position.
+///
+/// [`ReadToCtrlZ::len_hint()`]: crate::ReadToCtrlZ::len_hint()
+pub trait KnownLen {
+    /// Returns the number of bytes remaining before the first byte contained in `terminators`, or
+    /// before the end of the reader if no such byte remains.
+    ///
+    /// The position of the reader must be left unchanged.
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64>;
+}
+
+/// Returns the number of bytes in `bytes` before the first byte contained in `terminators`.
+fn len_before(bytes: &[u8], terminators: &TerminatorSet) -> u64 {
+    bytes
+        .iter()
+        .position(|&byte| terminators.contains(byte))
+        .unwrap_or(bytes.len()) as u64
+}
+
+/// Reads from `reader` until a byte contained in `terminators` or the end of the reader is found.
+fn read_len_before<R>(reader: &mut R, terminators: &TerminatorSet) -> Result<u64>
+where
+    R: Read,
+{
+    let mut buf = [0; 4096];
+    let mut len = 0;
+    loop {
+        let n = cmp::min(try!(reader.read(&mut buf)), buf.len());
+        if n == 0 {
+            return Ok(len);
+        }
+        let before = len_before(&buf[..n], terminators);
+        len += before;
+        if before < n as u64 {
+            return Ok(len);
+        }
+    }
+}
+
+/// Scans `reader` from its current position, seeking back to that position afterwards.
+fn seek_len_before<R>(reader: &mut R, terminators: &TerminatorSet) -> Result<u64>
+where
+    R: Read + Seek,
+{
+    let start = try!(reader.seek(SeekFrom::Current(0)));
+    let result = read_len_before(reader, terminators);
+    try!(reader.seek(SeekFrom::Start(start)));
+    result
+}
+
+impl<'a> KnownLen for &'a [u8] {
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64> {
+        let bytes: &'a [u8] = self;
+        Ok(len_before(bytes, terminators))
+    }
+}
+
+impl<'a, R> KnownLen for &'a mut R
+where
+    R: KnownLen + ?Sized,
+{
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64> {
+        let inner: &mut &'a mut R = self;
+        (**inner).len_before(terminators)
+    }
+}
+
+impl<T> KnownLen for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64> {
+        let bytes = self.get_ref().as_ref();
+        let pos = cmp::min(self.position(), bytes.len() as u64) as usize;
+        Ok(len_before(&bytes[pos..], terminators))
+    }
+}
+
+impl KnownLen for File {
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64> {
+        seek_len_before(self, terminators)
+    }
+}
+
+impl<R> KnownLen for BufReader<R>
+where
+    R: Read + Seek,
+{
+    fn len_before(&mut self, terminators: &TerminatorSet) -> Result<u64> {
+        seek_len_before(self, terminators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KnownLen;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::io::Cursor;
+    use std::io::Read;
+    use std::io::Write;
+    use TerminatorSet;
+
+    #[test]
+    fn slice() {
+        assert_ok_eq!(
+            (b"foo\x1abar" as &[u8]).len_before(&TerminatorSet::ctrl_z()),
+            3
+        );
+    }
+
+    #[test]
+    fn slice_no_terminator() {
+        assert_ok_eq!((b"foobar" as &[u8]).len_before(&TerminatorSet::ctrl_z()), 6);
+    }
+
+    #[test]
+    fn cursor() {
+        let mut cursor = Cursor::new(b"foo\x1abar".to_vec());
+        cursor.set_position(1);
+
+        assert_ok_eq!(cursor.len_before(&TerminatorSet::ctrl_z()), 2);
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn cursor_past_end() {
+        let mut cursor = Cursor::new(b"foo".to_vec());
+        cursor.set_position(5);
+
+        assert_ok_eq!(cursor.len_before(&TerminatorSet::ctrl_z()), 0);
+    }
+
+    #[test]
+    fn buf_reader_seek() {
+        let mut reader = BufReader::new(Cursor::new(b"foo\x1abar".to_vec()));
+        let mut byte = [0];
+        assert_ok_eq!(reader.read(&mut byte), 1);
+
+        assert_ok_eq!(reader.len_before(&TerminatorSet::ctrl_z()), 2);
+        let mut output = Vec::new();
+        assert_ok_eq!(reader.read_to_end(&mut output), 6);
+        assert_eq!(output, b"oo\x1abar");
+    }
+
+    #[test]
+    fn file() {
+        let path = env::temp_dir().join("ctrl_z_known_len_file");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1abar"));
+        let mut file = assert_ok!(File::open(&path));
+        let mut byte = [0];
+        assert_ok_eq!(file.read(&mut byte), 1);
+
+        assert_ok_eq!(file.len_before(&TerminatorSet::ctrl_z()), 2);
+        let mut output = Vec::new();
+        assert_ok_eq!(file.read_to_end(&mut output), 6);
+        assert_eq!(output, b"oo\x1abar");
+
+        assert_ok!(fs::remove_file(&path));
+    }
+}
@@ -38,19 +38,245 @@
 //! assert!(reader.read_to_string(&mut output).is_ok());
 //! assert_eq!(output, "foo");
 //! ```
+//!
+//! Data already held in memory, such as a fixed-size allocation, can be read without copying by
+//! wrapping it in a [`Cursor`](std::io::Cursor). For a `Box<[u8]>`, the
+//! [`ReadToCtrlZ::from_boxed_bytes()`] constructor does exactly this.
+//!
+//! ```
+//! use ctrl_z::ReadToCtrlZ;
+//! use std::io::BufRead;
+//!
+//! let data = b"foo\nbar\x1a\0\0".to_vec().into_boxed_slice();
+//! let lines = ReadToCtrlZ::from_boxed_bytes(data).lines().map(|line| line.unwrap());
+//!
+//! assert_eq!(lines.collect::<Vec<_>>(), ["foo", "bar"]);
+//! ```
+//!
+//! For full usage, the [`prelude`] module re-exports every public type, trait, and function, so
+//! that a single `use ctrl_z::prelude::*;` brings the whole crate into scope.
 
 #![allow(deprecated)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 #[cfg(test)]
 #[macro_use]
 extern crate claim;
+#[cfg(test)]
+#[macro_use]
+extern crate static_assertions;
+#[cfg(feature = "bytes")]
+extern crate bytes as bytes_crate;
+#[cfg(feature = "crossbeam")]
+extern crate crossbeam_channel;
+#[cfg(all(test, feature = "stream"))]
+extern crate futures;
+#[cfg(feature = "stream")]
+extern crate futures_core;
+#[cfg(all(unix, feature = "timeout"))]
+extern crate libc;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "nom")]
+extern crate nom;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(any(test, feature = "json"))]
+extern crate serde_json;
+#[cfg(feature = "tracing")]
+#[macro_use]
+extern crate tracing;
 
+mod buffered;
+mod bytes;
+#[cfg(feature = "bytes")]
+mod bytes_mut;
+mod cancel;
+#[cfg(feature = "crossbeam")]
+mod channel;
+mod chars;
+mod checkpoint;
+mod chunks;
+mod classify;
+mod closure;
+mod compare;
+mod counting;
+mod cp437;
+mod cpm;
+mod cursor;
+mod dbase;
+mod fs;
+mod in_memory;
+#[cfg(feature = "json")]
+mod json;
+mod known_len;
+mod limited;
+mod line_start;
+mod lines;
+#[cfg(feature = "log")]
+mod logged;
+mod lossy;
+mod multi;
+mod mutex;
+#[cfg(feature = "nom")]
+mod nom_input;
+mod offset_bound;
+pub mod prelude;
+mod progress;
+#[cfg(feature = "rc")]
+mod rc;
+mod read_at;
+mod safe_buf;
+mod scan;
+mod segmenting;
+mod segments;
+mod shared;
+mod split;
+mod stdin;
+#[cfg(feature = "stream")]
+mod stream;
+mod strict;
+#[cfg(feature = "strip")]
+mod strip;
+mod terminators;
+#[cfg(all(unix, feature = "timeout"))]
+mod timeout;
+#[cfg(feature = "tracing")]
+mod traced;
+mod transparent;
+mod validate;
+mod write;
+
+pub use buffered::BufReadToCtrlZ;
+pub use bytes::LogicalBytes;
+pub use cancel::CancelToken;
+pub use cancel::Cancelled;
+#[cfg(feature = "crossbeam")]
+pub use channel::ChannelReader;
+pub use chars::CharsError;
+pub use chars::CtrlZChars;
+pub use checkpoint::Checkpoint;
+pub use chunks::CtrlZChunks;
+pub use classify::classify;
+pub use classify::classify_reader;
+pub use classify::classify_reader_cancellable;
+pub use classify::Classification;
+pub use classify::Verdict;
+pub use closure::ClosureReader;
+pub use compare::eq_ignoring_ctrl_z;
+pub use compare::eq_ignoring_ctrl_z_cancellable;
+pub use compare::files_eq_ignoring_ctrl_z;
+pub use compare::files_eq_ignoring_ctrl_z_cancellable;
+pub use counting::CountingCtrlZSink;
+pub use cpm::CpmRecords;
+pub use cpm::Record;
+pub use cursor::CtrlZCursor;
+pub use dbase::DbaseMemoReader;
+pub use fs::ensure_terminated;
+pub use fs::ensure_terminated_with_options;
+pub use fs::read_ctrl_z_file;
+pub use fs::read_ctrl_z_file_to_string;
+pub use fs::EnsureOptions;
+pub use fs::EnsureOutcome;
+pub use in_memory::AsInMemory;
+#[cfg(feature = "json")]
+pub use json::json_from_ctrl_z_reader;
+#[cfg(feature = "json")]
+pub use json::JsonCtrlZError;
+pub use known_len::KnownLen;
+pub use limited::LimitedReadToCtrlZ;
+pub use line_start::LineStartCtrlZReader;
+pub use lines::CpmLines;
+#[cfg(feature = "log")]
+pub use logged::LoggingReadToCtrlZ;
+pub use lossy::LinesLossy;
+pub use multi::MultiCtrlZWriter;
+pub use mutex::MutexReader;
+#[cfg(feature = "nom")]
+pub use nom_input::CtrlZInput;
+pub use offset_bound::OffsetBoundReadToCtrlZ;
+pub use progress::Progress;
+pub use progress::ProgressReadToCtrlZ;
+#[cfg(feature = "rc")]
+pub use rc::RcReader;
+pub use read_at::CtrlZReadAt;
+pub use read_at::ReadAt;
+pub use safe_buf::SafeBufReader;
+pub use scan::count_ctrl_z;
+pub use scan::find_ctrl_z;
+#[cfg(feature = "rayon")]
+pub use scan::par_find_ctrl_z;
+#[cfg(feature = "rayon")]
+pub use scan::par_scan;
+pub use scan::scan;
+pub use scan::scan_resumable;
+pub use scan::ScanReport;
+pub use scan::ScanState;
+pub use segmenting::SegmentingWriter;
+pub use segments::SegmentOffsets;
+pub use segments::SegmentRef;
+pub use segments::Segments;
+pub use shared::SharedReadToCtrlZ;
+pub use split::SplitReadWriteToCtrlZ;
+pub use stdin::read_ctrl_z_from_stdin;
+#[cfg(feature = "stream")]
+pub use stream::CtrlZByteStream;
+pub use strict::ReadError;
+pub use strict::StrictReadToCtrlZ;
+#[cfg(feature = "strip")]
+pub use strip::strip_files;
+#[cfg(feature = "strip")]
+pub use strip::strip_files_with_progress;
+#[cfg(feature = "strip")]
+pub use strip::strip_in_place;
+#[cfg(feature = "strip")]
+pub use strip::BatchEntry;
+#[cfg(feature = "strip")]
+pub use strip::BatchReport;
+#[cfg(feature = "strip")]
+pub use strip::StripOptions;
+#[cfg(feature = "strip")]
+pub use strip::StripOutcome;
+pub use terminators::TerminatorSet;
+#[cfg(all(unix, feature = "timeout"))]
+pub use timeout::TimeoutReadToCtrlZ;
+#[cfg(feature = "tracing")]
+pub use traced::TracedReadToCtrlZ;
+pub use transparent::Transparent;
+pub use validate::TextProfile;
+pub use validate::Violation;
+pub use write::IntoInnerError;
+pub use write::WriteToCtrlZ;
+
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Cursor;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::iter;
+use std::mem;
 use std::slice;
+use std::str;
+use validate::Validator;
+
+/// The capacity of the [`BufReader`] created by [`ReadToCtrlZ::buffered()`].
+///
+/// This is larger than the 8 KiB default of `BufReader`, so that the search for the `0x1A` byte in
+/// `fill_buf()` runs over longer stretches between reads of the inner reader.
+const BUFFERED_CAPACITY: usize = 64 * 1024;
 
 /// A composable reader to read until a `0x1A` byte (commonly known as `CTRL-Z` or the "substitute
 /// character") is encountered.
@@ -84,11 +310,147 @@ use std::slice;
 /// assert!(reader.read_to_string(&mut output).is_ok());
 /// assert_eq!(output, "foo");
 /// ```
+///
+/// # Combining with `take()`
+/// A [`Take`](std::io::Take) limit can be applied on either side of a `ReadToCtrlZ`, with
+/// different semantics. Applied to the inner reader, as in `ReadToCtrlZ::new(reader.take(n))`, it
+/// limits the raw bytes examined: a `0x1A` byte beyond the first `n` bytes is never seen, and
+/// reading ends as if the inner reader had reached its end. Applied to the `ReadToCtrlZ`, as in
+/// `ReadToCtrlZ::new(reader).take(n)`, it limits the bytes delivered, while the `0x1A` byte is
+/// still recognized wherever it occurs.
+///
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut output = String::new();
+/// let mut reader = ReadToCtrlZ::new((&b"foobar\x1abaz"[..]).take(6));
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foobar");
+/// // The `0x1A` byte lies beyond the limit, so it is never reached.
+/// assert!(!reader.is_terminated());
+///
+/// let mut output = String::new();
+/// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]).take(6);
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foobar");
+/// // Reading past the limit reaches the `0x1A` byte.
+/// let mut reader = reader.into_inner();
+/// assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+/// assert!(reader.is_terminated());
+/// ```
+///
+/// # Sharing buffers
+/// A [`Cursor`] over an `Arc<[u8]>` implements both [`Read`] and [`BufRead`], so a buffer shared
+/// between threads can be read without copying it. Cloning the cursor only increments the
+/// reference count, and each clone keeps its own position.
+///
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Cursor;
+/// use std::io::Read;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let buffer: Arc<[u8]> = Arc::from(b"foo\x1abar".to_vec());
+/// let cursor = Cursor::new(buffer);
+///
+/// let mut reader = ReadToCtrlZ::new(cursor.clone());
+/// let handle = thread::spawn(move || {
+///     let mut output = String::new();
+///     reader.read_to_string(&mut output).unwrap();
+///     output
+/// });
+/// assert_eq!(handle.join().unwrap(), "foo");
+///
+/// // The original cursor is unaffected by the clone being read.
+/// assert_eq!(cursor.position(), 0);
+/// ```
+///
+/// # Authenticated readers
+/// Decrypting readers, such as those wrapping an AEAD cipher, may fail with an authentication
+/// error, return no bytes from a read before the end of their input, or only verify the
+/// authentication tag on a final read once all of the data has been returned. A `ReadToCtrlZ`
+/// handles each of these as follows:
+///
+/// - Errors from the inner reader are returned unchanged, including their [`ErrorKind`]. An error
+///   does not terminate reading, and bytes delivered before it are kept.
+/// - A read returning no bytes is treated as the end of the input, as the [`Read`] contract
+///   requires, but does not terminate reading. A later read consults the inner reader again.
+/// - Reading stops at the `0x1A` byte, so the inner reader is not read to its end, and a tag
+///   verified on the final read is never checked. If authenticity matters, drain the inner reader
+///   once the logical content has been read.
+///
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io;
+/// use std::io::Read;
+/// #
+/// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+/// # trait AsSlice {
+/// #     fn as_slice(&self) -> &[u8];
+/// # }
+/// #
+/// # impl AsSlice for [u8] {
+/// #     fn as_slice(&self) -> &[u8] {
+/// #         self
+/// #     }
+/// # }
+///
+/// # let decrypting_reader = b"foo\x1a\0\0".as_slice();
+/// let mut reader = ReadToCtrlZ::new(decrypting_reader);
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output).unwrap();
+///
+/// // Reading the rest of the inner reader verifies the authentication tag.
+/// let (mut inner, _) = reader.into_parts();
+/// io::copy(&mut inner, &mut io::sink()).unwrap();
+/// ```
 pub struct ReadToCtrlZ<R> {
     /// The internal reader being read.
     inner: R,
+    /// Bytes read ahead from the inner reader that have not yet been delivered.
+    buffer: Vec<u8>,
+    /// The position of the first undelivered byte in `buffer`.
+    pos: usize,
     /// Whether or not the EOF `0x1A` byte has been reached.
     terminated: bool,
+    /// The bytes that terminate reading.
+    terminators: TerminatorSet,
+    /// The byte that terminated reading, if any.
+    terminator: Option<u8>,
+    /// The number of bytes delivered to the caller.
+    delivered: u64,
+    /// The number of bytes read from the inner reader.
+    inner_read: u64,
+    /// The number of bytes within which the `0x1A` byte must be found, if any.
+    limit: Option<u64>,
+    /// Whether or not the limit has been exceeded without finding a `0x1A` byte.
+    exceeded: bool,
+    /// Whether a `0x1A` byte only terminates reading when followed solely by whitespace.
+    lenient: bool,
+    /// The byte delimiting quoted regions, within which terminating bytes are regular bytes.
+    quote: Option<u8>,
+    /// Whether or not the delivered bytes end within a quoted region.
+    quoted: bool,
+    /// Whether terminating bytes preceding the first data byte are discarded.
+    skip_leading: bool,
+    /// Whether or not terminating bytes are still being discarded, as no data byte has been seen.
+    leading: bool,
+    /// Whether a terminating byte at the start of the input is reported as an error.
+    error_on_leading: bool,
+    /// The total number of bytes that will have been delivered at termination, once known.
+    end: Option<u64>,
+    /// The validation of delivered bytes, if text is being validated.
+    validator: Option<Validator>,
+    /// Whether a `\n` byte is appended to content that does not already end with one.
+    trailing_newline: bool,
+    /// The last byte delivered, tracked only while guaranteeing a trailing newline.
+    last: Option<u8>,
+    /// Whether the logical content has been read to its end, after which a newline may follow.
+    finished: bool,
+    /// Whether or not the trailing newline has been delivered.
+    newline_added: bool,
 }
 
 impl<R> ReadToCtrlZ<R> {
@@ -116,168 +478,4232 @@ impl<R> ReadToCtrlZ<R> {
     pub fn new(inner: R) -> Self {
         ReadToCtrlZ {
             inner: inner,
+            buffer: Vec::new(),
+            pos: 0,
             terminated: false,
+            terminators: TerminatorSet::ctrl_z(),
+            terminator: None,
+            delivered: 0,
+            inner_read: 0,
+            limit: None,
+            exceeded: false,
+            lenient: false,
+            quote: None,
+            quoted: false,
+            skip_leading: false,
+            leading: false,
+            error_on_leading: false,
+            end: None,
+            validator: None,
+            trailing_newline: false,
+            last: None,
+            finished: false,
+            newline_added: false,
         }
     }
-}
-
-impl<R> Read for ReadToCtrlZ<R>
-where
-    R: Read,
-{
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        if self.terminated {
-            return Ok(0);
-        }
 
-        let n = try!(self.inner.read(buf));
-        for i in 0..n {
-            if *try!(buf.get(i).ok_or_else(|| {
-                Error::new(ErrorKind::Other, "buffer smaller than amount of bytes read")
-            })) == b'\x1a'
-            {
-                self.terminated = true;
-                return Ok(i);
-            }
-        }
-        Ok(n)
+    /// Creates a new `ReadToCtrlZ`, using `buffer` to store bytes read ahead from the inner
+    /// reader.
+    ///
+    /// Any contents of `buffer` are cleared, but its capacity is kept. The buffer can be
+    /// retrieved again using [`into_buffer()`](ReadToCtrlZ::into_buffer()) or
+    /// [`into_parts()`](ReadToCtrlZ::into_parts()), allowing a single allocation to be reused
+    /// across many readers.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut buffer = Vec::with_capacity(1024);
+    /// for input in [b"foo\x1a", b"bar\x1a"].iter() {
+    ///     let reader = ReadToCtrlZ::with_buffer(input.as_slice(), buffer);
+    ///     // ...
+    ///     buffer = reader.into_buffer();
+    /// }
+    ///
+    /// assert_eq!(buffer.capacity(), 1024);
+    /// ```
+    pub fn with_buffer(inner: R, mut buffer: Vec<u8>) -> Self {
+        buffer.clear();
+        let mut reader = ReadToCtrlZ::new(inner);
+        reader.buffer = buffer;
+        reader
     }
-}
-
-impl<R> BufRead for ReadToCtrlZ<R>
-where
-    R: BufRead,
-{
-    fn fill_buf(&mut self) -> Result<&[u8]> {
-        if self.terminated {
-            return Ok(&[]);
-        }
 
-        let buf = try!(self.inner.fill_buf());
-        for i in 0..buf.len() {
-            // SAFETY: `i` is guaranteed to be a valid index into `buf`.
-            if *unsafe { buf.get_unchecked(i) } == b'\x1a' {
-                if i == 0 {
-                    self.terminated = true;
-                }
-                // SAFETY: The range `..i` is guaranteed to be a valid index into `buf`.
-                return Ok(unsafe { slice::from_raw_parts(buf.as_ptr(), i) });
-            }
-        }
-        Ok(buf)
+    /// Unwraps this `ReadToCtrlZ`, returning its internal buffer.
+    ///
+    /// The buffer is returned cleared, with its capacity unchanged. Any bytes read ahead but not
+    /// yet delivered are discarded.
+    pub fn into_buffer(self) -> Vec<u8> {
+        self.into_parts().1
     }
 
-    fn consume(&mut self, amount: usize) {
-        self.inner.consume(amount);
+    /// Unwraps this `ReadToCtrlZ`, returning the inner reader and the internal buffer.
+    ///
+    /// The buffer is returned cleared, with its capacity unchanged. Any bytes read ahead but not
+    /// yet delivered are discarded.
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        let mut buffer = self.buffer;
+        buffer.clear();
+        (self.inner, buffer)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::BufRead;
-    use std::io::ErrorKind;
-    use std::io::Read;
-    use std::io::Result;
-
-    #[test]
-    fn read_exclude_ctrl_z() {
-        let mut output = String::new();
 
-        assert_ok_eq!(
-            ReadToCtrlZ::new(b"foo\x1a" as &[u8]).read_to_string(&mut output),
-            3
-        );
-        assert_eq!(output, "foo");
+    /// Requires a `0x1A` byte to be found within the first `limit` bytes.
+    ///
+    /// Up to `limit` bytes are delivered normally. If the byte following them is not `0x1A` (and
+    /// the inner reader has not reached its end), the next read returns an error of kind
+    /// [`ErrorKind::InvalidInput`] wrapping a [`MarkerNotFound`]. The error is only returned once;
+    /// subsequent reads behave as if the end of the input was reached.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1a".as_slice()).require_marker_within(3);
+    /// let mut output = String::new();
+    ///
+    /// // The marker was not found within the first 3 bytes.
+    /// assert!(reader.read_to_string(&mut output).is_err());
+    /// ```
+    pub fn require_marker_within(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
     }
 
-    #[test]
-    fn read_no_ctrl_z() {
-        let mut output = String::new();
-
-        assert_ok_eq!(
-            ReadToCtrlZ::new(b"foo" as &[u8]).read_to_string(&mut output),
-            3
-        );
-        assert_eq!(output, "foo");
+    /// Terminates reading on any of the bytes in `terminators`, rather than only on `0x1A`.
+    ///
+    /// # Example
+    /// Here is an example of reading until any C0 control character other than `\t`, `\r`, or
+    /// `\n`.
+    ///
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::TerminatorSet;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo\tbar\r\n\x07baz".as_slice())
+    ///     .terminate_on(TerminatorSet::c0_controls());
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo\tbar\r\n");
+    /// assert_eq!(reader.terminator(), Some(b'\x07'));
+    /// ```
+    pub fn terminate_on(mut self, terminators: TerminatorSet) -> Self {
+        self.terminators = terminators;
+        self
     }
 
-    #[test]
-    fn read_stop_at_ctrl_z() {
-        let mut output = String::new();
-
-        assert_ok_eq!(
-            ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).read_to_string(&mut output),
-            3
-        );
-        assert_eq!(output, "foo");
+    /// Only treats a `0x1A` byte as the end of the file if everything following it is whitespace.
+    ///
+    /// When enabled, a `0x1A` byte that is followed by anything other than whitespace is read as a
+    /// regular byte. The bytes considered to be whitespace are `' '`, `\t`, `\n`, `\x0B`, `\x0C`,
+    /// `\r`, the `\0` padding byte, and further terminating bytes.
+    ///
+    /// Deciding whether a `0x1A` byte terminates requires reading ahead to the next
+    /// non-whitespace byte or the end of the inner reader. Bytes read ahead are buffered
+    /// internally until they are delivered.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo\x1abar\x1a\r\n".as_slice()).lenient(true);
+    /// let mut output = String::new();
+    ///
+    /// // Only the final `0x1A` byte terminates.
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo\x1abar");
+    /// ```
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
     }
 
-    #[test]
-    fn read_after_ctrl_z() {
-        let mut output = String::new();
-        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
-
-        assert_ok_eq!(reader.read_to_string(&mut output), 3);
-        assert_eq!(output, "foo");
-
-        // This indicates the reader has reached EOF.
-        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+    /// Only terminates reading on `0x1A` bytes outside of regions delimited by `quote`.
+    ///
+    /// This is useful for CSV-style content, where a quoted field may contain a `0x1A` byte as
+    /// data. Each `quote` byte toggles whether the following bytes are quoted, so an escaped quote
+    /// written as two consecutive `quote` bytes leaves the region quoted. The quoting state is
+    /// tracked across reads and is reset by [`rewind()`](ReadToCtrlZ::rewind()).
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo,\"bar\x1a\"\x1abaz".as_slice()).quote_aware(b'"');
+    /// let mut output = String::new();
+    ///
+    /// // Only the `0x1A` byte outside of quotes terminates.
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo,\"bar\x1a\"");
+    /// ```
+    pub fn quote_aware(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
     }
 
-    struct BadReader;
+    /// Discards terminating bytes at the very start of the input, rather than terminating on them.
+    ///
+    /// When enabled, terminating bytes encountered before any other byte are silently skipped,
+    /// after which terminating bytes are handled as usual. Input consisting solely of terminating
+    /// bytes is read as empty, without terminating. Skipping starts again when the reader is
+    /// [rewound](ReadToCtrlZ::rewind()).
+    ///
+    /// Skipped bytes are never delivered, so [`bytes_delivered()`](ReadToCtrlZ::bytes_delivered())
+    /// and offsets returned by [`find_ctrl_z()`](ReadToCtrlZ::find_ctrl_z()) are relative to the
+    /// first byte following them. They are still read from the inner reader, so they are counted
+    /// by [`inner_bytes_read()`](ReadToCtrlZ::inner_bytes_read()) and
+    /// [`excess()`](ReadToCtrlZ::excess()).
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader =
+    ///     ReadToCtrlZ::new(b"\x1a\x1ahello\x1aworld".as_slice()).skip_leading_markers(true);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "hello");
+    /// assert_eq!(reader.bytes_delivered(), 5);
+    /// ```
+    pub fn skip_leading_markers(mut self, skip: bool) -> Self {
+        self.skip_leading = skip;
+        self.leading = skip;
+        self
+    }
 
-    impl Read for BadReader {
-        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    /// Reports a terminating byte at the very start of the input as an error, rather than as an
+    /// empty input.
+    ///
+    /// Some formats treat a leading `0x1A` byte as an indication of corruption. When enabled, a
+    /// read that terminates before any byte has been delivered returns an error of kind
+    /// [`ErrorKind::InvalidInput`] instead of `Ok(0)`. The error is only returned once; subsequent
+    /// reads behave as if the end of the input was reached. Input that is empty without a
+    /// terminating byte is not an error.
+    ///
+    /// If [leading markers are skipped](ReadToCtrlZ::skip_leading_markers()), they are skipped
+    /// before this check, so only a terminating byte following them counts as leading.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"\x1afoo".as_slice()).error_on_leading_ctrl_z(true);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_err());
+    /// ```
+    pub fn error_on_leading_ctrl_z(mut self, error: bool) -> Self {
+        self.error_on_leading = error;
+        self
+    }
+
+    /// Guarantees that non-empty content ends with a `\n` byte.
+    ///
+    /// Files ending in a `0x1A` byte often have no newline after their final line, so tools
+    /// expecting POSIX text would join it with whatever follows. When enabled, once the `0x1A`
+    /// byte or the end of the inner reader is reached, a single `\n` byte is delivered as the last
+    /// byte if any bytes were delivered and the last of them was not already `\n`. Nothing is
+    /// added to empty content.
+    ///
+    /// The added byte is not read from the inner reader, so it is not counted by
+    /// [`bytes_delivered()`](ReadToCtrlZ::bytes_delivered()). No byte is added after reading was
+    /// stopped by an error.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foo\nbar\x1a"[..]).ensure_trailing_newline(true);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo\nbar\n");
+    /// ```
+    pub fn ensure_trailing_newline(mut self, ensure: bool) -> Self {
+        self.trailing_newline = ensure;
+        self
+    }
+
+    /// Returns the byte that terminated reading, if reading has been terminated.
+    ///
+    /// This is `None` if no terminating byte has been encountered yet, including when the inner
+    /// reader reached its end without one.
+    pub fn terminator(&self) -> Option<u8> {
+        self.terminator
+    }
+
+    /// Returns whether reading has been terminated by a terminating byte.
+    ///
+    /// Reaching the end of the inner reader without a terminating byte does not count as
+    /// termination, so this remains `false` in that case.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foo"[..]);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert!(!reader.is_terminated());
+    /// ```
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Returns the number of bytes delivered by this reader.
+    ///
+    /// The terminating byte and anything following it are never counted.
+    pub fn bytes_delivered(&self) -> u64 {
+        self.delivered
+    }
+
+    /// Returns the number of bytes read from the inner reader.
+    ///
+    /// This includes bytes that have been read from the inner reader but not delivered, such as
+    /// the terminating byte, any bytes following it within the same read, and bytes that have been
+    /// read ahead. When reading through [`BufRead`], only bytes consumed from the inner reader are
+    /// counted.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo\x1abar".as_slice());
+    /// let mut output = [0; 16];
+    ///
+    /// assert_eq!(reader.read(&mut output).unwrap(), 3);
+    /// assert_eq!(reader.bytes_delivered(), 3);
+    /// assert_eq!(reader.inner_bytes_read(), 7);
+    /// assert_eq!(reader.excess(), 4);
+    /// ```
+    pub fn inner_bytes_read(&self) -> u64 {
+        self.inner_read
+    }
+
+    /// Returns the number of bytes read from the inner reader that were not delivered.
+    ///
+    /// This is the difference between [`inner_bytes_read()`](ReadToCtrlZ::inner_bytes_read()) and
+    /// [`bytes_delivered()`](ReadToCtrlZ::bytes_delivered()).
+    pub fn excess(&self) -> u64 {
+        self.inner_read.saturating_sub(self.delivered)
+    }
+
+    /// Marks reading as terminated by `byte`.
+    fn terminate(&mut self, byte: u8) {
+        self.terminated = true;
+        self.terminator = Some(byte);
+    }
+
+    /// Returns an error if reading was terminated before any byte was delivered and this is
+    /// configured to be an error.
+    fn check_leading(&self) -> Result<()> {
+        if self.error_on_leading && self.delivered == 0 {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                "terminating byte at start of input",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the error to be returned when the limit is exceeded, marking it as returned.
+    fn exceed(&mut self, limit: u64) -> Error {
+        self.exceeded = true;
+        Error::new(
+            ErrorKind::InvalidInput,
+            MarkerNotFound {
+                limit: limit,
+                seen: limit + 1,
+            },
+        )
+    }
+
+    /// Resets all reading state, leaving the configuration intact.
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.pos = 0;
+        self.terminated = false;
+        self.terminator = None;
+        self.delivered = 0;
+        self.inner_read = 0;
+        self.exceeded = false;
+        self.quoted = false;
+        self.leading = self.skip_leading;
+        self.end = None;
+        self.last = None;
+        self.finished = false;
+        self.newline_added = false;
+        if let Some(ref mut validator) = self.validator {
+            validator.reset();
+        }
+    }
+
+    /// Returns whether reading has been stopped by a disallowed byte while validating text.
+    fn is_stopped_by_violation(&self) -> bool {
+        self.validator
+            .as_ref()
+            .map_or(false, |validator| validator.is_stopped())
+    }
+
+    /// Returns whether the trailing newline should be delivered next.
+    fn newline_due(&self) -> bool {
+        self.trailing_newline
+            && self.finished
+            && !self.newline_added
+            && !self.exceeded
+            && !self.is_stopped_by_violation()
+            && self.last.map_or(false, |byte| byte != b'\n')
+    }
+
+    /// Returns `bytes` to the front of the read-ahead buffer, to be delivered by the next read.
+    ///
+    /// The buffer is rearranged in place, so its allocation is kept.
+    fn unread(&mut self, bytes: &[u8]) {
+        let len = bytes.len();
+        let pending = self.buffer.len() - self.pos;
+        if self.buffer.len() < len + pending {
+            let additional = len + pending - self.buffer.len();
+            self.buffer.extend(iter::repeat(0).take(additional));
+        }
+        // The pending bytes only ever move toward the end, so copying backwards is safe.
+        for i in (0..pending).rev() {
+            self.buffer[len + i] = self.buffer[self.pos + i];
+        }
+        self.buffer.truncate(len + pending);
+        for (dst, &src) in self.buffer.iter_mut().zip(bytes) {
+            *dst = src;
+        }
+        self.pos = 0;
+    }
+
+    /// Marks `amount` bytes of the read-ahead buffer as delivered.
+    fn advance(&mut self, amount: usize) {
+        self.pos += amount;
+        if self.pos >= self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+        }
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Reads and discards up to `n` bytes, stopping early if a `0x1A` byte is encountered.
+    ///
+    /// Returns the number of bytes skipped. This is useful for inner readers that do not implement
+    /// [`Seek`](std::io::Seek). If the inner reader implements [`BufRead`], prefer
+    /// [`skip_n_bytes_buffered()`](ReadToCtrlZ::skip_n_bytes_buffered()), which avoids copying.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz".as_slice());
+    /// let mut output = String::new();
+    ///
+    /// assert_eq!(reader.skip_n_bytes(3).unwrap(), 3);
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "bar");
+    /// ```
+    pub fn skip_n_bytes(&mut self, n: u64) -> Result<u64> {
+        let mut scratch = [0; 1024];
+        let mut skipped = 0;
+        while skipped < n {
+            let len = cmp::min(n - skipped, scratch.len() as u64) as usize;
+            let read = match self.read(&mut scratch[..len]) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            skipped += read as u64;
+        }
+        Ok(skipped)
+    }
+
+    /// Reads and discards all bytes until the `0x1A` byte or the end of the inner reader.
+    ///
+    /// Returns the number of bytes discarded. The bytes are read through a small scratch buffer
+    /// and never allocated. If the inner reader implements [`BufRead`], prefer
+    /// [`discard_to_marker_buffered()`](ReadToCtrlZ::discard_to_marker_buffered()), which avoids
+    /// copying.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz".as_slice());
+    ///
+    /// assert_eq!(reader.discard_to_marker().unwrap(), 6);
+    /// assert_eq!(reader.terminator(), Some(b'\x1a'));
+    /// ```
+    pub fn discard_to_marker(&mut self) -> Result<u64> {
+        let mut scratch = [0; 1024];
+        let mut discarded = 0;
+        loop {
+            let read = match self.read(&mut scratch) {
+                Ok(0) => return Ok(discarded),
+                Ok(read) => read,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            discarded += read as u64;
+        }
+    }
+
+    /// Writes all bytes until the `0x1A` byte or the end of the inner reader to `writer`.
+    ///
+    /// Returns the number of bytes written. This is equivalent to [`io::copy()`] with this reader
+    /// as the source: the bytes are streamed through a small scratch buffer, rather than collected
+    /// first. If the inner reader implements [`BufRead`], prefer
+    /// [`read_to_writer_buffered()`](ReadToCtrlZ::read_to_writer_buffered()), which writes directly
+    /// from the inner reader's buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    /// let mut output = Vec::new();
+    ///
+    /// assert_eq!(reader.read_to_writer(&mut output).unwrap(), 6);
+    /// assert_eq!(output, b"foobar");
+    /// ```
+    ///
+    /// [`io::copy()`]: std::io::copy()
+    pub fn read_to_writer<W>(&mut self, writer: &mut W) -> Result<u64>
+    where
+        W: Write,
+    {
+        let mut scratch = [0; 8 * 1024];
+        let mut written = 0;
+        loop {
+            let read = match self.read(&mut scratch) {
+                Ok(0) => return Ok(written),
+                Ok(read) => read,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            try!(writer.write_all(&scratch[..read]));
+            written += read as u64;
+        }
+    }
+
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, allocating no more
+    /// than `limit` bytes.
+    ///
+    /// This is safer than [`read_to_end()`](Read::read_to_end()) for untrusted inputs.
+    ///
+    /// # Errors
+    /// If more than `limit` bytes would be read, an error of kind [`ErrorKind::InvalidInput`]
+    /// wrapping a [`MarkerNotFound`] is returned instead. [`ErrorKind::InvalidData`] would
+    /// describe the input more precisely, but requires Rust 1.2, which is newer than the minimum
+    /// version supported by this crate.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// assert_eq!(
+    ///     ReadToCtrlZ::new(b"foo\x1abar".as_slice()).collect_until_ctrl_z(3).unwrap(),
+    ///     b"foo"
+    /// );
+    /// assert!(ReadToCtrlZ::new(b"foobar\x1a".as_slice()).collect_until_ctrl_z(3).is_err());
+    /// ```
+    pub fn collect_until_ctrl_z(&mut self, limit: usize) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        try!(self
+            .by_ref()
+            .take(limit as u64 + 1)
+            .read_to_end(&mut output));
+        if output.len() > limit {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                MarkerNotFound {
+                    limit: limit as u64,
+                    seen: output.len() as u64,
+                },
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, appending them to
+    /// `buf`, but reading no more than `max` bytes.
+    ///
+    /// Returns the number of bytes appended. If more than `max` bytes precede the `0x1A` byte, an
+    /// error of kind [`ErrorKind::InvalidInput`] wrapping a [`MarkerNotFound`] is returned
+    /// instead. In that case, the first `max` bytes have still been appended to `buf`, and the
+    /// remaining bytes can still be read from this reader. Unlike
+    /// [`collect_until_ctrl_z()`](ReadToCtrlZ::collect_until_ctrl_z()), this appends to an
+    /// existing buffer, which makes it a bounded replacement for
+    /// [`read_to_end()`](Read::read_to_end()) on untrusted inputs.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut output = Vec::new();
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    ///
+    /// assert!(reader.read_to_end_limited(&mut output, 4).is_err());
+    /// assert_eq!(output, b"foob");
+    /// ```
+    pub fn read_to_end_limited(&mut self, buf: &mut Vec<u8>, max: usize) -> Result<usize> {
+        let read = try!(self.by_ref().take(max as u64).read_to_end(buf));
+        // Peeking detects further content without consuming it.
+        if read == max && !try!(self.peek_n(1)).is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                MarkerNotFound {
+                    limit: max as u64,
+                    seen: max as u64 + 1,
+                },
+            ));
+        }
+        Ok(read)
+    }
+
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, appending them to
+    /// `buf` as UTF-8, but reading no more than `max` bytes.
+    ///
+    /// Returns the number of bytes appended. If the bytes are not valid UTF-8, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned and nothing is appended to `buf`.
+    ///
+    /// If more than `max` bytes precede the `0x1A` byte, an error of kind
+    /// [`ErrorKind::InvalidInput`] wrapping a [`MarkerNotFound`] is returned, as by
+    /// [`read_to_end_limited()`](ReadToCtrlZ::read_to_end_limited()). In that case, the first
+    /// `max` bytes are still appended to `buf`, except for a trailing character split by the
+    /// limit. Nothing is appended if those bytes are otherwise not valid UTF-8.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut output = String::new();
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    ///
+    /// assert_eq!(reader.read_to_string_limited(&mut output, 6).unwrap(), 6);
+    /// assert_eq!(output, "foobar");
+    /// ```
+    pub fn read_to_string_limited(&mut self, buf: &mut String, max: usize) -> Result<usize> {
+        let mut bytes = Vec::new();
+        match self.read_to_end_limited(&mut bytes, max) {
+            Ok(read) => match str::from_utf8(&bytes) {
+                Ok(string) => {
+                    buf.push_str(string);
+                    Ok(read)
+                }
+                Err(_) => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "stream did not contain valid UTF-8",
+                )),
+            },
+            Err(error) => {
+                // A multibyte character may have been split by the limit.
+                for split in 0..cmp::min(4, bytes.len() + 1) {
+                    if let Ok(string) = str::from_utf8(&bytes[..bytes.len() - split]) {
+                        buf.push_str(string);
+                        break;
+                    }
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns up to the next `n` bytes without consuming them.
+    ///
+    /// The bytes are read ahead into an internal buffer, and are returned first by subsequent
+    /// reads. Fewer than `n` bytes are returned only if the `0x1A` byte or the end of the inner
+    /// reader is reached first. The `0x1A` byte and any bytes following it are never returned.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz".as_slice());
+    /// let mut output = String::new();
+    ///
+    /// assert_eq!(reader.peek_n(4).unwrap(), b"foob");
+    /// assert_eq!(reader.peek_n(8).unwrap(), b"foobar");
+    ///
+    /// // The peeked bytes are still read.
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foobar");
+    /// ```
+    pub fn peek_n(&mut self, n: usize) -> Result<&[u8]> {
+        if self.terminated || self.exceeded {
+            return Ok(&[]);
+        }
+        try!(self.skip_leading());
+        let n = match self.limit {
+            Some(limit) => cmp::min(n as u64, limit - self.delivered) as usize,
+            None => n,
+        };
+
+        // Read ahead until at least `n` bytes are buffered.
+        while self.buffer.len() - self.pos < n {
+            let mut chunk = [0; 1024];
+            let len = cmp::min(n - (self.buffer.len() - self.pos), chunk.len());
+            let read = match self.inner.read(&mut chunk[..len]) {
+                Ok(read) => cmp::min(read, len),
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            self.inner_read += read as u64;
+            if read == 0 {
+                break;
+            }
+            self.buffer.extend(chunk[..read].iter().cloned());
+        }
+
+        let available = cmp::min(n, self.buffer.len() - self.pos);
+        let mut end = available;
+        let mut quoted = self.quoted;
+        for i in 0..available {
+            let byte = self.buffer[self.pos + i];
+            if self.quote == Some(byte) {
+                quoted = !quoted;
+            } else if !quoted && self.terminators.contains(byte) {
+                let rest = self.pos + i + 1;
+                if !try!(self.buffered_terminator_is_data(rest)) {
+                    end = i;
+                    break;
+                }
+            }
+        }
+        Ok(&self.buffer[self.pos..self.pos + end])
+    }
+
+    /// Discards terminating bytes at the start of the input, while no other byte has been read.
+    fn skip_leading(&mut self) -> Result<()> {
+        while self.leading {
+            let mut byte = [0];
+            if try!(self.read_raw(&mut byte)) == 0 {
+                return Ok(());
+            }
+            if !self.terminators.contains(byte[0]) {
+                self.leading = false;
+                // The byte is returned to the read-ahead buffer, to be delivered by the next read.
+                if self.pos > 0 {
+                    self.pos -= 1;
+                } else {
+                    self.buffer.insert(0, byte[0]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads bytes without checking for terminating bytes, preferring read-ahead bytes.
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pos < self.buffer.len() {
+            let n = try!((&self.buffer[self.pos..]).read(buf));
+            self.advance(n);
+            Ok(n)
+        } else {
+            let n = try!(self.inner.read(buf));
+            self.inner_read += n as u64;
+            Ok(n)
+        }
+    }
+
+    /// Returns whether a terminating byte followed by `rest` should be read as a regular byte.
+    ///
+    /// This is only ever the case in lenient mode, where bytes are read ahead into the internal
+    /// buffer until either a non-whitespace byte or the end of the inner reader is found.
+    fn terminator_is_data(&mut self, rest: &[u8]) -> Result<bool> {
+        if !self.lenient {
+            return Ok(false);
+        }
+        let terminators = self.terminators;
+        if rest.iter().any(|&byte| !is_padding(&terminators, byte)) {
+            return Ok(true);
+        }
+        let pos = self.pos;
+        self.buffered_terminator_is_data(pos)
+    }
+
+    /// Returns whether a terminating byte followed by the read-ahead bytes starting at `checked`
+    /// should be read as a regular byte.
+    fn buffered_terminator_is_data(&mut self, mut checked: usize) -> Result<bool> {
+        if !self.lenient {
+            return Ok(false);
+        }
+        let terminators = self.terminators;
+        loop {
+            if self.buffer[checked..]
+                .iter()
+                .any(|&byte| !is_padding(&terminators, byte))
+            {
+                return Ok(true);
+            }
+            checked = self.buffer.len();
+            let mut chunk = [0; 1024];
+            let n = try!(self.inner.read(&mut chunk));
+            self.inner_read += n as u64;
+            if n == 0 {
+                return Ok(false);
+            }
+            self.buffer.extend(chunk.iter().take(n).cloned());
+        }
+    }
+}
+
+impl<R> ReadToCtrlZ<BufReader<R>>
+where
+    R: Read,
+{
+    /// Creates a new `ReadToCtrlZ`, wrapping the provided reader in a [`BufReader`] with the
+    /// specified capacity.
+    ///
+    /// This is a shorthand for `ReadToCtrlZ::new(BufReader::with_capacity(capacity, inner))`.
+    /// Smaller capacities can be useful for sector-aligned legacy files, such as CP/M files with
+    /// 128-byte records.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::BufRead;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::with_capacity(128, b"foo\x1a".as_slice());
+    ///
+    /// assert_eq!(reader.fill_buf().unwrap(), b"foo");
+    /// ```
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        ReadToCtrlZ::new(BufReader::with_capacity(capacity, inner))
+    }
+
+    /// Creates a new `ReadToCtrlZ`, wrapping the provided reader in a [`BufReader`] with a
+    /// capacity suited to searching for the `0x1A` byte.
+    ///
+    /// This is the most common way to read an unbuffered source, such as a [`File`], avoiding
+    /// writing out the nested types. The capacity is 64 KiB; use
+    /// [`with_capacity()`](ReadToCtrlZ::with_capacity()) to choose a different one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::fs::File;
+    /// use std::io::BufRead;
+    ///
+    /// let reader = ReadToCtrlZ::buffered(File::open("README.TXT").unwrap());
+    /// for line in reader.lines() {
+    ///     println!("{}", line.unwrap());
+    /// }
+    /// ```
+    ///
+    /// [`File`]: std::fs::File
+    pub fn buffered(inner: R) -> Self {
+        ReadToCtrlZ::with_capacity(BUFFERED_CAPACITY, inner)
+    }
+
+    /// Unwraps this `ReadToCtrlZ` and its [`BufReader`], returning the innermost reader.
+    ///
+    /// Any bytes read ahead into either buffer are discarded, so the innermost reader may be
+    /// positioned past the bytes delivered so far. Use [`into_parts()`](ReadToCtrlZ::into_parts())
+    /// instead to keep the `BufReader` and the bytes it holds.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Cursor;
+    ///
+    /// let reader = ReadToCtrlZ::buffered(Cursor::new(b"foo\x1a".to_vec()));
+    ///
+    /// assert_eq!(reader.into_innermost().into_inner(), b"foo\x1a");
+    /// ```
+    pub fn into_innermost(self) -> R {
+        self.into_parts().0.into_inner()
+    }
+}
+
+impl ReadToCtrlZ<Cursor<Box<[u8]>>> {
+    /// Creates a new `ReadToCtrlZ` reading from the provided boxed bytes.
+    ///
+    /// The bytes are read in place through a [`Cursor`], so the [`BufRead`] methods return slices
+    /// of `data` directly without copying.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::from_boxed_bytes(b"foo\x1abar".to_vec().into_boxed_slice());
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo");
+    /// ```
+    pub fn from_boxed_bytes(data: Box<[u8]>) -> Self {
+        ReadToCtrlZ::new(Cursor::new(data))
+    }
+}
+
+impl<'a, R> ReadToCtrlZ<&'a mut R>
+where
+    R: ?Sized,
+{
+    /// Creates a new `ReadToCtrlZ` borrowing the provided reader.
+    ///
+    /// This is a cheap, temporary wrapper that can be dropped and recreated without losing the
+    /// underlying reader, such as when extracting consecutive `0x1A`-terminated segments from a
+    /// reader that is also read in other ways.
+    ///
+    /// When reading through [`BufRead`] methods, the underlying reader is left positioned directly
+    /// after the terminating `0x1A` byte once it is reached, so the next segment can be read by a
+    /// new wrapper. Reading through [`Read`] methods copies whole chunks from the underlying
+    /// reader, so any bytes following the `0x1A` byte within the same chunk are lost. In either
+    /// case, bytes read ahead by a [lenient](ReadToCtrlZ::lenient()) wrapper are lost when it is
+    /// dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::BufRead;
+    /// use std::io::BufReader;
+    ///
+    /// let mut inner = BufReader::new(&b"foo\nbar\x1abaz\x1a"[..]);
+    ///
+    /// let first = ReadToCtrlZ::by_ref(&mut inner).lines().map(|line| line.unwrap());
+    /// assert_eq!(first.collect::<Vec<_>>(), ["foo", "bar"]);
+    ///
+    /// let second = ReadToCtrlZ::by_ref(&mut inner).lines().map(|line| line.unwrap());
+    /// assert_eq!(second.collect::<Vec<_>>(), ["baz"]);
+    /// ```
+    pub fn by_ref(inner: &'a mut R) -> Self {
+        ReadToCtrlZ::new(inner)
+    }
+}
+
+impl<R> Read for ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // A zero-length read is not an indication of EOF, so the inner reader is not consulted.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if !self.trailing_newline {
+            return self.read_logical(buf);
+        }
+        if !self.finished {
+            let n = try!(self.read_logical(buf));
+            if n > 0 {
+                self.last = Some(buf[n - 1]);
+                return Ok(n);
+            }
+            self.finished = true;
+        }
+        if self.newline_due() {
+            buf[0] = b'\n';
+            self.newline_added = true;
+            return Ok(1);
+        }
+        Ok(0)
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Reads bytes until the terminating byte, without the trailing newline.
+    fn read_logical(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.terminated || self.exceeded || self.is_stopped_by_violation() {
+            return Ok(0);
+        }
+        try!(self.skip_leading());
+
+        let len = match self.limit {
+            Some(limit) if self.delivered == limit => {
+                // Only the `0x1A` byte or the end of the input may follow.
+                let mut byte = [0];
+                if try!(self.read_raw(&mut byte)) == 0 {
+                    return Ok(0);
+                }
+                if !self.quoted
+                    && self.terminators.contains(byte[0])
+                    && !try!(self.terminator_is_data(&[]))
+                {
+                    self.terminate(byte[0]);
+                    try!(self.check_leading());
+                    return Ok(0);
+                }
+                return Err(self.exceed(limit));
+            }
+            Some(limit) => cmp::min(buf.len() as u64, limit - self.delivered) as usize,
+            None => buf.len(),
+        };
+        let buf = &mut buf[..len];
+
+        let n = try!(self.read_raw(buf));
+        if n > buf.len() {
+            // A reader violating the `Read` contract is a bug in that reader, so debug builds
+            // surface it immediately instead of returning an error that may be silently retried.
+            if cfg!(debug_assertions) {
+                panic!(
+                    "inner reader violated the `Read` contract by reading {} bytes into a {} byte \
+                     buffer",
+                    n,
+                    buf.len()
+                );
+            }
+            return Err(Error::new(
+                ErrorKind::Other,
+                "buffer smaller than amount of bytes read",
+            ));
+        }
+        for (i, &byte) in buf[..n].iter().enumerate() {
+            if self.quote == Some(byte) {
+                self.quoted = !self.quoted;
+            } else if !self.quoted
+                && self.terminators.contains(byte)
+                && !try!(self.terminator_is_data(&buf[i + 1..n]))
+            {
+                self.terminate(byte);
+                self.delivered += i as u64;
+                if i == 0 {
+                    try!(self.check_leading());
+                }
+                return Ok(i);
+            }
+            if let Some(ref mut validator) = self.validator {
+                if !validator.allows(byte) {
+                    let offset = self.delivered + i as u64;
+                    if !validator.fail_fast() {
+                        validator.record(offset, byte);
+                    } else if i == 0 {
+                        return Err(validator.fail(offset, byte));
+                    } else {
+                        // The bytes preceding the violation are delivered first, so the rest are
+                        // returned to the read-ahead buffer to be read again.
+                        self.unread(&buf[i..n]);
+                        self.delivered += i as u64;
+                        return Ok(i);
+                    }
+                }
+            }
+        }
+        self.delivered += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Returns the buffered bytes without checking for terminating bytes, preferring read-ahead
+    /// bytes.
+    fn fill_raw(&mut self) -> Result<&[u8]> {
+        if self.pos < self.buffer.len() {
+            Ok(&self.buffer[self.pos..])
+        } else {
+            self.inner.fill_buf()
+        }
+    }
+
+    /// Updates the quoting state for the first `amount` buffered bytes, which are being consumed.
+    fn track_quotes(&mut self, amount: usize) {
+        let quote = self.quote;
+        // The bytes were already buffered by `fill_buf()`, so this does not read from the inner
+        // reader.
+        let toggles = match self.fill_raw() {
+            Ok(buf) => buf
+                .iter()
+                .take(amount)
+                .filter(|&&byte| quote == Some(byte))
+                .count(),
+            Err(_) => 0,
+        };
+        if toggles % 2 == 1 {
+            self.quoted = !self.quoted;
+        }
+    }
+
+    /// Discards terminating bytes at the start of the input, while no other byte has been read,
+    /// without copying.
+    fn skip_leading_buffered(&mut self) -> Result<()> {
+        while self.leading {
+            let first = try!(self.fill_raw()).first().cloned();
+            match first {
+                None => return Ok(()),
+                Some(byte) if self.terminators.contains(byte) => self.consume_raw(1),
+                Some(_) => self.leading = false,
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes buffered bytes, preferring read-ahead bytes.
+    fn consume_raw(&mut self, amount: usize) {
+        if self.pos < self.buffer.len() {
+            self.advance(amount);
+        } else {
+            self.inner.consume(amount);
+            self.inner_read += amount as u64;
+        }
+    }
+
+    /// Returns whether the terminating byte at the start of the buffered bytes should be read as
+    /// a regular byte.
+    fn lookahead_is_data(&mut self) -> Result<bool> {
+        if !self.lenient {
+            return Ok(false);
+        }
+        if self.pos == self.buffer.len() {
+            // Move the inner reader's buffer into the read-ahead buffer, so it can be extended.
+            let terminators = self.terminators;
+            let (is_data, len) = {
+                let buf = try!(self.inner.fill_buf());
+                let is_data = buf.iter().any(|&byte| !is_padding(&terminators, byte));
+                if !is_data {
+                    self.buffer.extend(buf.iter().cloned());
+                }
+                (is_data, buf.len())
+            };
+            if is_data {
+                return Ok(true);
+            }
+            self.inner.consume(len);
+            self.inner_read += len as u64;
+        }
+        self.terminator_is_data(&[])
+    }
+}
+
+impl<R> BufRead for ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if !self.trailing_newline {
+            return self.fill_logical();
+        }
+        if !self.finished && try!(self.fill_logical()).is_empty() {
+            self.finished = true;
+        }
+        if self.finished {
+            return Ok(if self.newline_due() { b"\n" } else { &[] });
+        }
+        self.fill_logical()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        if self.trailing_newline && amount > 0 {
+            if self.finished {
+                // Only the trailing newline remains to be consumed.
+                self.newline_added = true;
+                return;
+            }
+            // The bytes were already buffered by `fill_buf()`, so this does not read from the
+            // inner reader.
+            let last = self
+                .fill_raw()
+                .ok()
+                .and_then(|buf| buf.get(amount - 1).cloned());
+            if last.is_some() {
+                self.last = last;
+            }
+        }
+        self.consume_logical_buffered(amount);
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Returns the buffered bytes preceding the terminating byte, without the trailing newline.
+    fn fill_logical(&mut self) -> Result<&[u8]> {
+        if self.terminated || self.exceeded || self.is_stopped_by_violation() {
+            return Ok(&[]);
+        }
+        try!(self.skip_leading_buffered());
+
+        let remaining = self.limit.map(|limit| limit - self.delivered);
+        let first = try!(self.fill_raw()).first().cloned();
+        let start = match first {
+            None => return Ok(&[]),
+            Some(byte) if !self.quoted && self.terminators.contains(byte) => {
+                if !try!(self.lookahead_is_data()) {
+                    self.terminate(byte);
+                    // The terminating byte is consumed, so the inner reader is left positioned
+                    // directly after it.
+                    self.consume_raw(1);
+                    try!(self.check_leading());
+                    return Ok(&[]);
+                }
+                // The terminating byte is read as a regular byte.
+                1
+            }
+            Some(_) => 0,
+        };
+        if let Some(0) = remaining {
+            // Only the `0x1A` byte or the end of the input may follow.
+            let limit = self.delivered;
+            return Err(self.exceed(limit));
+        }
+
+        let terminators = self.terminators;
+        let quote = self.quote;
+        let mut quoted = self.quoted;
+        let validation = self
+            .validator
+            .as_ref()
+            .map(|validator| (validator.allowed(), validator.fail_fast()));
+        let mut violations = Vec::new();
+        let mut failed = None;
+        let end = {
+            let buf = try!(self.fill_raw());
+            let mut end = buf.len();
+            if quote.is_none() && terminators == TerminatorSet::ctrl_z() && validation.is_none() {
+                // The general loop below checks every byte against the terminator set and the
+                // quote byte, which the compiler does not optimize away even when no `0x1A` byte
+                // is present. In the common configuration, a dedicated search for the `0x1A` byte
+                // roughly doubles the throughput of the `read` benchmark.
+                if let Some(i) = find_ctrl_z(&buf[start..]) {
+                    end = start + i;
+                }
+            } else {
+                for i in 0..buf.len() {
+                    // SAFETY: `i` is guaranteed to be a valid index into `buf`.
+                    let byte = *unsafe { buf.get_unchecked(i) };
+                    if i < start {
+                        // The terminating byte is read as a regular byte, but is still validated.
+                    } else if quote == Some(byte) {
+                        quoted = !quoted;
+                    } else if !quoted && terminators.contains(byte) {
+                        end = i;
+                        break;
+                    }
+                    if let Some((allowed, fail_fast)) = validation {
+                        if !allowed.contains(byte) {
+                            if fail_fast {
+                                if i == 0 {
+                                    failed = Some(byte);
+                                }
+                                end = i;
+                                break;
+                            }
+                            violations.push((i, byte));
+                        }
+                    }
+                }
+            }
+            if let Some(remaining) = remaining {
+                end = cmp::min(end as u64, remaining) as usize;
+            }
+            end
+        };
+        if let Some(ref mut validator) = self.validator {
+            let delivered = self.delivered;
+            if let Some(byte) = failed {
+                return Err(validator.fail(delivered, byte));
+            }
+            for &(i, byte) in violations.iter().filter(|&&(i, _)| i < end) {
+                validator.record(delivered + i as u64, byte);
+            }
+            validator.check_to(delivered + end as u64);
+        }
+        // The bytes were already buffered above, so this does not read from the inner reader.
+        let buf = try!(self.fill_raw());
+        // SAFETY: The range `..end` is guaranteed to be a valid index into `buf`.
+        Ok(unsafe { slice::from_raw_parts(buf.as_ptr(), end) })
+    }
+
+    /// Consumes buffered bytes preceding the terminating byte.
+    fn consume_logical_buffered(&mut self, amount: usize) {
+        if self.quote.is_some() {
+            self.track_quotes(amount);
+        }
+        self.delivered += amount as u64;
+        self.consume_raw(amount);
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Consumes up to `n` bytes, stopping early if a `0x1A` byte is encountered.
+    ///
+    /// Returns the number of bytes skipped. Unlike [`skip_n_bytes()`](ReadToCtrlZ::skip_n_bytes()),
+    /// this skips bytes directly within the inner reader's buffer, without copying them.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::BufRead;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz".as_slice());
+    ///
+    /// assert_eq!(reader.skip_n_bytes_buffered(10).unwrap(), 6);
+    /// assert!(reader.fill_buf().unwrap().is_empty());
+    /// ```
+    pub fn skip_n_bytes_buffered(&mut self, n: u64) -> Result<u64> {
+        let mut skipped = 0;
+        while skipped < n {
+            let len = match self.fill_buf() {
+                Ok(buf) => cmp::min(buf.len() as u64, n - skipped) as usize,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if len == 0 {
+                break;
+            }
+            self.consume(len);
+            skipped += len as u64;
+        }
+        Ok(skipped)
+    }
+
+    /// Consumes all bytes until the `0x1A` byte or the end of the inner reader.
+    ///
+    /// Returns the number of bytes consumed. Unlike
+    /// [`discard_to_marker()`](ReadToCtrlZ::discard_to_marker()), this consumes bytes directly
+    /// within the inner reader's buffer, without copying them. The inner reader is left positioned
+    /// directly after the `0x1A` byte, so the following bytes can be read from it afterwards.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut inner = &b"foobar\x1abaz"[..];
+    /// assert_eq!(ReadToCtrlZ::new(&mut inner).discard_to_marker_buffered().unwrap(), 6);
+    ///
+    /// // The inner reader is positioned after the `0x1A` byte.
+    /// assert_eq!(inner, b"baz");
+    /// ```
+    pub fn discard_to_marker_buffered(&mut self) -> Result<u64> {
+        let mut discarded = 0;
+        loop {
+            let len = match self.fill_buf() {
+                Ok(buf) => buf.len(),
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if len == 0 {
+                return Ok(discarded);
+            }
+            self.consume(len);
+            discarded += len as u64;
+        }
+    }
+
+    /// Reads all bytes until the `0x1A` byte or the end of the inner reader, appending them to
+    /// `buf`.
+    ///
+    /// Returns the number of bytes read. This is equivalent to [`Read::read_to_end()`], but copies
+    /// each of the inner reader's buffers into `buf` in a single step after scanning it for the
+    /// `0x1A` byte, rather than through repeated small reads. Like
+    /// [`discard_to_marker_buffered()`](ReadToCtrlZ::discard_to_marker_buffered()), the inner
+    /// reader is left positioned directly after the `0x1A` byte.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    /// let mut output = Vec::new();
+    ///
+    /// assert_eq!(reader.read_to_end_buffered(&mut output).unwrap(), 6);
+    /// assert_eq!(output, b"foobar");
+    /// ```
+    pub fn read_to_end_buffered(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        let mut read = 0;
+        loop {
+            let len = match self.fill_buf() {
+                Ok(chunk) => {
+                    buf.extend(chunk.iter().cloned());
+                    chunk.len()
+                }
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if len == 0 {
+                return Ok(read);
+            }
+            self.consume(len);
+            read += len;
+        }
+    }
+
+    /// Writes all bytes until the `0x1A` byte or the end of the inner reader to `writer`, directly
+    /// from the inner reader's buffer.
+    ///
+    /// Returns the number of bytes written. Unlike
+    /// [`read_to_writer()`](ReadToCtrlZ::read_to_writer()), each buffer is written to `writer`
+    /// without first being copied into a scratch buffer. The inner reader is left positioned
+    /// directly after the `0x1A` byte.
+    ///
+    /// Bytes are consumed as soon as `writer` accepts them. If writing fails, the bytes already
+    /// accepted by `writer` have been consumed, while the bytes of the failing write have not, so
+    /// reading resumes with the first byte `writer` did not accept.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    /// let mut output = Vec::new();
+    ///
+    /// assert_eq!(reader.read_to_writer_buffered(&mut output).unwrap(), 6);
+    /// assert_eq!(output, b"foobar");
+    /// ```
+    pub fn read_to_writer_buffered<W>(&mut self, writer: &mut W) -> Result<u64>
+    where
+        W: Write,
+    {
+        let mut written = 0;
+        loop {
+            let (len, result) = match self.fill_buf() {
+                Ok(chunk) => {
+                    if chunk.is_empty() {
+                        return Ok(written);
+                    }
+                    match writer.write(chunk) {
+                        Ok(0) => (
+                            0,
+                            Err(Error::new(
+                                ErrorKind::WriteZero,
+                                "failed to write whole buffer",
+                            )),
+                        ),
+                        Ok(n) => (n, Ok(())),
+                        Err(ref error) if error.kind() == ErrorKind::Interrupted => (0, Ok(())),
+                        Err(error) => (0, Err(error)),
+                    }
+                }
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            // Only the bytes accepted by the writer are consumed.
+            self.consume(len);
+            written += len as u64;
+            try!(result);
+        }
+    }
+
+    /// Returns the number of bytes preceding the next `0x1A` byte, or `None` if the inner reader
+    /// ends without one.
+    ///
+    /// No bytes are consumed: everything scanned remains available to be read afterwards. This is
+    /// done by moving the scanned bytes out of the inner reader's buffer and into this reader's
+    /// read-ahead buffer, so memory usage grows with the distance to the `0x1A` byte. For inner
+    /// readers implementing [`KnownLen`], such as a [`Cursor`], [`len_hint()`] finds the same
+    /// offset by seeking instead, without buffering anything.
+    ///
+    /// Quoted regions are skipped when [quote-aware](ReadToCtrlZ::quote_aware()), but
+    /// [lenient](ReadToCtrlZ::lenient()) mode is not considered: the offset of the first `0x1A`
+    /// byte is returned even if it would be read as a regular byte. Any
+    /// [limit](ReadToCtrlZ::require_marker_within()) is also ignored while scanning. `None` is
+    /// returned if reading has already terminated.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\x1abaz"[..]);
+    /// let mut output = String::new();
+    ///
+    /// assert_eq!(reader.find_ctrl_z().unwrap(), Some(6));
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foobar");
+    /// ```
+    ///
+    /// [`len_hint()`]: ReadToCtrlZ::len_hint()
+    pub fn find_ctrl_z(&mut self) -> Result<Option<u64>> {
+        if self.terminated || self.exceeded {
+            return Ok(None);
+        }
+        try!(self.skip_leading_buffered());
+
+        let terminators = self.terminators;
+        let quote = self.quote;
+        let mut quoted = self.quoted;
+        let mut find = |buf: &[u8]| {
+            buf.iter().position(|&byte| {
+                if quote == Some(byte) {
+                    quoted = !quoted;
+                    false
+                } else {
+                    !quoted && terminators.contains(byte)
+                }
+            })
+        };
+
+        let pending = self.buffer.len() - self.pos;
+        if let Some(i) = find(&self.buffer[self.pos..]) {
+            return Ok(Some(i as u64));
+        }
+        let mut offset = pending as u64;
+        loop {
+            let (found, len) = {
+                let buf = match self.inner.fill_buf() {
+                    Ok(buf) => buf,
+                    Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error),
+                };
+                let found = find(buf);
+                if found.is_none() {
+                    self.buffer.extend(buf.iter().cloned());
+                }
+                (found, buf.len())
+            };
+            if let Some(i) = found {
+                return Ok(Some(offset + i as u64));
+            }
+            if len == 0 {
+                return Ok(None);
+            }
+            self.inner.consume(len);
+            self.inner_read += len as u64;
+            offset += len as u64;
+        }
+    }
+
+    /// Reads a single record of fields separated by `delimiter`.
+    ///
+    /// A record ends at a `\n` byte (which is consumed), at the `0x1A` byte, or at the end of the
+    /// inner reader. An empty `Vec` is returned if there are no more records to read.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo\tbar\nbaz\x1a".as_slice());
+    ///
+    /// assert_eq!(reader.read_fields(b'\t').unwrap(), [b"foo", b"bar"]);
+    /// assert_eq!(reader.read_fields(b'\t').unwrap(), [b"baz"]);
+    /// assert!(reader.read_fields(b'\t').unwrap().is_empty());
+    /// ```
+    pub fn read_fields(&mut self, delimiter: u8) -> Result<Vec<Vec<u8>>> {
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut started = false;
+        loop {
+            let (done, used) = {
+                let buf = match self.fill_buf() {
+                    Ok(buf) => buf,
+                    Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+                    Err(error) => return Err(error),
+                };
+                if buf.is_empty() {
+                    (true, 0)
+                } else {
+                    started = true;
+                    match buf
+                        .iter()
+                        .position(|&byte| byte == delimiter || byte == b'\n')
+                    {
+                        Some(i) => {
+                            field.extend(buf[..i].iter().cloned());
+                            if buf[i] == b'\n' {
+                                (true, i + 1)
+                            } else {
+                                fields.push(mem::replace(&mut field, Vec::new()));
+                                (false, i + 1)
+                            }
+                        }
+                        None => {
+                            field.extend(buf.iter().cloned());
+                            (false, buf.len())
+                        }
+                    }
+                }
+            };
+            self.consume(used);
+            if done {
+                break;
+            }
+        }
+        if started {
+            fields.push(field);
+        }
+        Ok(fields)
+    }
+
+    /// Reads bytes into `buf` until `delimiter`, the `0x1A` byte, or the end of the inner reader.
+    ///
+    /// Like [`read_until()`](BufRead::read_until()), the delimiter is appended to `buf` if found.
+    /// The `0x1A` byte is never appended. The returned [`UntilOutcome`] indicates which of the
+    /// three conditions ended the read.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::UntilOutcome;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foo\xffbar\x1abaz".as_slice());
+    /// let mut record = Vec::new();
+    ///
+    /// let outcome = reader.read_until_or_marker(b'\xff', &mut record).unwrap();
+    /// assert_eq!(outcome, UntilOutcome::Delimiter);
+    /// assert_eq!(record, b"foo\xff");
+    ///
+    /// record.clear();
+    /// let outcome = reader.read_until_or_marker(b'\xff', &mut record).unwrap();
+    /// assert_eq!(outcome, UntilOutcome::Marker);
+    /// assert_eq!(record, b"bar");
+    /// ```
+    ///
+    /// [`UntilOutcome`]: crate::UntilOutcome
+    pub fn read_until_or_marker(
+        &mut self,
+        delimiter: u8,
+        buf: &mut Vec<u8>,
+    ) -> Result<UntilOutcome> {
+        loop {
+            if self.terminated {
+                return Ok(UntilOutcome::Marker);
+            }
+
+            // The raw bytes are scanned for both the delimiter and a terminating byte in a single
+            // pass. Terminating bytes at the start of the buffer, leading markers, the limit,
+            // quoting, and validation are handled by `fill_buf()` below.
+            let fast = if self.exceeded
+                || self.quote.is_some()
+                || self.leading
+                || self.validator.is_some()
+            {
+                None
+            } else {
+                let remaining = self.limit.map(|limit| limit - self.delivered);
+                let terminators = self.terminators;
+                let raw = try!(self.fill_raw());
+                let (end, outcome) = match raw
+                    .iter()
+                    .position(|&byte| byte == delimiter || terminators.contains(byte))
+                {
+                    Some(i) if !terminators.contains(raw[i]) => {
+                        (i + 1, Some(UntilOutcome::Delimiter))
+                    }
+                    Some(i) => (i, None),
+                    None => (raw.len(), None),
+                };
+                if end == 0 || remaining.map_or(false, |remaining| end as u64 > remaining) {
+                    None
+                } else {
+                    buf.extend(raw[..end].iter().cloned());
+                    Some((outcome, end))
+                }
+            };
+            let (outcome, used) = match fast {
+                Some(fast) => fast,
+                None => {
+                    let available = try!(self.fill_buf());
+                    match available.iter().position(|&byte| byte == delimiter) {
+                        Some(i) => {
+                            buf.extend(available[..i + 1].iter().cloned());
+                            (Some(UntilOutcome::Delimiter), i + 1)
+                        }
+                        None => {
+                            buf.extend(available.iter().cloned());
+                            (None, available.len())
+                        }
+                    }
+                }
+            };
+            self.consume(used);
+            if let Some(outcome) = outcome {
+                return Ok(outcome);
+            }
+            if used == 0 {
+                if self.terminated {
+                    return Ok(UntilOutcome::Marker);
+                }
+                return Ok(UntilOutcome::Eof);
+            }
+        }
+    }
+
+    /// Reads bytes into `buf` until `delimiter`, the `0x1A` byte, or the end of the inner reader,
+    /// as [`read_until_or_marker()`](ReadToCtrlZ::read_until_or_marker()) does, but appending no
+    /// more than `max` bytes.
+    ///
+    /// If `max` bytes are appended without reaching any of the three, [`UntilOutcome::Limit`] is
+    /// returned. The bytes appended so far are left in `buf`, and no further bytes are consumed, so
+    /// calling this again continues where it stopped. A delimiter that is the `max`th byte is
+    /// appended, and the `0x1A` byte or the end of the inner reader directly following the `max`th
+    /// byte is reported as such rather than as the limit.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::UntilOutcome;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foobar\nbaz\x1a"[..]);
+    /// let mut line = Vec::new();
+    ///
+    /// let outcome = reader.read_until_limited(b'\n', &mut line, 4).unwrap();
+    /// assert_eq!(outcome, UntilOutcome::Limit);
+    /// assert_eq!(line, b"foob");
+    ///
+    /// let outcome = reader.read_until_limited(b'\n', &mut line, 4).unwrap();
+    /// assert_eq!(outcome, UntilOutcome::Delimiter);
+    /// assert_eq!(line, b"foobar\n");
+    /// ```
+    ///
+    /// [`UntilOutcome::Limit`]: crate::UntilOutcome::Limit
+    pub fn read_until_limited(
+        &mut self,
+        delimiter: u8,
+        buf: &mut Vec<u8>,
+        max: usize,
+    ) -> Result<UntilOutcome> {
+        let mut read = 0;
+        loop {
+            let (used, found) = {
+                let available = try!(self.fill_buf());
+                if available.is_empty() {
+                    return Ok(if self.terminated {
+                        UntilOutcome::Marker
+                    } else {
+                        UntilOutcome::Eof
+                    });
+                }
+                if read == max {
+                    return Ok(UntilOutcome::Limit);
+                }
+                let available = &available[..cmp::min(available.len(), max - read)];
+                match available.iter().position(|&byte| byte == delimiter) {
+                    Some(i) => {
+                        buf.extend(available[..i + 1].iter().cloned());
+                        (i + 1, true)
+                    }
+                    None => {
+                        buf.extend(available.iter().cloned());
+                        (available.len(), false)
+                    }
+                }
+            };
+            self.consume(used);
+            read += used;
+            if found {
+                return Ok(UntilOutcome::Delimiter);
+            }
+        }
+    }
+
+    /// Reads a line into `buf`, ending at a `\n` byte, the `0x1A` byte, or the end of the inner
+    /// reader, but appending no more than `max` bytes.
+    ///
+    /// This bounds the memory used when reading input that may contain neither newlines nor a
+    /// `0x1A` byte, which [`read_line()`](BufRead::read_line()) would read entirely into memory.
+    /// The `\n` byte is appended if found, and the outcome is reported as by
+    /// [`read_until_limited()`](ReadToCtrlZ::read_until_limited()).
+    ///
+    /// When the limit is reached, a character split by it is not appended or consumed, so the next
+    /// call reads it whole. `max` should therefore be at least 4 bytes, the longest UTF-8
+    /// character, to guarantee progress.
+    ///
+    /// # Errors
+    /// If the line is not valid UTF-8, an error of kind [`ErrorKind::InvalidInput`] is returned
+    /// and nothing is appended to `buf`. The line is still consumed.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::UntilOutcome;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foo\nbar\x1abaz"[..]);
+    /// let mut line = String::new();
+    ///
+    /// assert_eq!(reader.read_line_limited(&mut line, 16).unwrap(), UntilOutcome::Delimiter);
+    /// assert_eq!(line, "foo\n");
+    ///
+    /// line.clear();
+    /// assert_eq!(reader.read_line_limited(&mut line, 16).unwrap(), UntilOutcome::Marker);
+    /// assert_eq!(line, "bar");
+    /// ```
+    pub fn read_line_limited(&mut self, buf: &mut String, max: usize) -> Result<UntilOutcome> {
+        let mut bytes = Vec::new();
+        let outcome = try!(self.read_until_limited(b'\n', &mut bytes, max));
+        let mut valid = bytes.len();
+        if outcome == UntilOutcome::Limit {
+            // A multibyte character may have been split by the limit.
+            for split in 0..cmp::min(4, bytes.len() + 1) {
+                if str::from_utf8(&bytes[..bytes.len() - split]).is_ok() {
+                    valid = bytes.len() - split;
+                    break;
+                }
+            }
+        }
+        match str::from_utf8(&bytes[..valid]) {
+            Ok(string) => buf.push_str(string),
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "stream did not contain valid UTF-8",
+                ))
+            }
+        }
+        if valid < bytes.len() {
+            // The split character is returned to the read-ahead buffer, to be read whole next.
+            self.unread(&bytes[valid..]);
+            self.delivered -= (bytes.len() - valid) as u64;
+        }
+        Ok(outcome)
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: KnownLen + Read,
+{
+    /// Returns the exact number of bytes remaining before the `0x1A` byte or the end of the inner
+    /// reader.
+    ///
+    /// No bytes are consumed and the position of the inner reader is left unchanged, except that
+    /// [leading markers](ReadToCtrlZ::skip_leading_markers()) are skipped first, so the length is
+    /// that of the bytes a later read delivers. The inner reader is only scanned once; later calls
+    /// are answered from the result of that scan.
+    ///
+    /// `None` is returned in [lenient mode](ReadToCtrlZ::lenient()), where the remaining length
+    /// cannot be known without deciding which `0x1A` bytes terminate. It is also returned while
+    /// [a trailing newline](ReadToCtrlZ::ensure_trailing_newline()) may still be added, since
+    /// whether it is depends on the final byte. If
+    /// [a marker is required](ReadToCtrlZ::require_marker_within()), the length is capped at the
+    /// number of bytes delivered before the limit is exceeded.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Read;
+    /// #
+    /// # // Redefines `[u8]:as_slice()` for backwards compatibility.
+    /// # trait AsSlice {
+    /// #     fn as_slice(&self) -> &[u8];
+    /// # }
+    /// #
+    /// # impl AsSlice for [u8] {
+    /// #     fn as_slice(&self) -> &[u8] {
+    /// #         self
+    /// #     }
+    /// # }
+    ///
+    /// let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz".as_slice());
+    /// let mut output = [0; 2];
+    ///
+    /// assert_eq!(reader.len_hint().unwrap(), Some(6));
+    /// assert!(reader.read(&mut output).is_ok());
+    /// assert_eq!(reader.len_hint().unwrap(), Some(4));
+    /// ```
+    pub fn len_hint(&mut self) -> Result<Option<u64>> {
+        if self.exceeded {
+            return Ok(Some(0));
+        }
+        if self.trailing_newline && !self.newline_added && (!self.finished || self.newline_due()) {
+            return Ok(None);
+        }
+        if self.terminated {
+            return Ok(Some(0));
+        }
+        if self.lenient || self.quote.is_some() {
+            return Ok(None);
+        }
+        let remaining = self.limit.map(|limit| limit - self.delivered);
+        if let Some(end) = self.end {
+            let len = end - self.delivered;
+            return Ok(Some(
+                remaining.map_or(len, |remaining| cmp::min(len, remaining)),
+            ));
+        }
+        try!(self.skip_leading());
+
+        let terminators = self.terminators;
+        let pending = &self.buffer[self.pos..];
+        let mut len = pending
+            .iter()
+            .position(|&byte| terminators.contains(byte))
+            .unwrap_or(pending.len()) as u64;
+        if len == pending.len() as u64 {
+            len += try!(self.inner.len_before(&terminators));
+        }
+        self.end = Some(self.delivered + len);
+        Ok(Some(
+            remaining.map_or(len, |remaining| cmp::min(len, remaining)),
+        ))
+    }
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Seek,
+{
+    /// Seeks the inner reader back to its start, restoring this reader to its freshly constructed
+    /// state.
+    ///
+    /// Termination, the byte counters, and any read-ahead bytes are all cleared. The configuration
+    /// of this reader is left unchanged. If the inner reader is a
+    /// [`BufReader`](std::io::BufReader), its buffer is discarded by the seek.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::Cursor;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar".to_vec()));
+    /// let mut output = String::new();
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    ///
+    /// assert!(reader.rewind().is_ok());
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foofoo");
+    /// ```
+    pub fn rewind(&mut self) -> Result<()> {
+        try!(self.inner.seek(SeekFrom::Start(0)));
+        self.reset();
+        Ok(())
+    }
+}
+
+/// Returns whether `byte` may follow a terminating byte in lenient mode.
+fn is_padding(terminators: &TerminatorSet, byte: u8) -> bool {
+    match byte {
+        b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r' | b'\0' => true,
+        _ => terminators.contains(byte),
+    }
+}
+
+/// The condition that ended a call to [`ReadToCtrlZ::read_until_or_marker()`] or
+/// [`ReadToCtrlZ::read_until_limited()`].
+///
+/// With the `serde` feature enabled, this is serialized as an enum with the variants `Delimiter`,
+/// `Marker`, `Eof`, and `Limit`.
+///
+/// [`ReadToCtrlZ::read_until_or_marker()`]: crate::ReadToCtrlZ::read_until_or_marker()
+/// [`ReadToCtrlZ::read_until_limited()`]: crate::ReadToCtrlZ::read_until_limited()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UntilOutcome {
+    /// The delimiter was found and appended to the buffer.
+    Delimiter,
+    /// The `0x1A` byte was found.
+    Marker,
+    /// The end of the inner reader was reached.
+    Eof,
+    /// The maximum number of bytes was read without reaching any of the other conditions.
+    ///
+    /// This is only returned by the limited methods, such as
+    /// [`ReadToCtrlZ::read_until_limited()`].
+    ///
+    /// [`ReadToCtrlZ::read_until_limited()`]: crate::ReadToCtrlZ::read_until_limited()
+    Limit,
+}
+
+/// An error indicating that no `0x1A` byte was found within a limit, such as the one set by
+/// [`ReadToCtrlZ::require_marker_within()`], [`ReadToCtrlZ::collect_until_ctrl_z()`], or
+/// [`ReadToCtrlZ::read_to_end_limited()`].
+///
+/// This error is returned wrapped in an [`Error`] of kind [`ErrorKind::InvalidInput`].
+#[derive(Debug)]
+pub struct MarkerNotFound {
+    /// The number of bytes within which the `0x1A` byte was required.
+    limit: u64,
+    /// The number of bytes read without finding the `0x1A` byte.
+    seen: u64,
+}
+
+impl MarkerNotFound {
+    /// Returns the number of bytes within which the `0x1A` byte was required.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the number of bytes read without finding the `0x1A` byte.
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+}
+
+impl Display for MarkerNotFound {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "no 0x1A byte found within {} bytes ({} bytes read)",
+            self.limit, self.seen
+        )
+    }
+}
+
+impl error::Error for MarkerNotFound {
+    fn description(&self) -> &str {
+        "no 0x1A byte found within the limit"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Cursor;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Result;
+    use std::panic;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn read_exclude_ctrl_z() {
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo\x1a" as &[u8]).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_no_ctrl_z() {
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo" as &[u8]).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_stop_at_ctrl_z() {
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_after_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+
+        // This indicates the reader has reached EOF.
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+    }
+
+    #[test]
+    fn read_c0_controls() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\tbar\r\nbaz\x07qux\x1a" as &[u8])
+            .terminate_on(TerminatorSet::c0_controls());
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 12);
+        assert_eq!(output, "foo\tbar\r\nbaz");
+        assert_some_eq!(reader.terminator(), b'\x07');
+    }
+
+    #[test]
+    fn read_no_terminator() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_none!(reader.terminator());
+    }
+
+    #[test]
+    fn read_lenient_ctrl_z_followed_by_text() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\r\nbar" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 9);
+        assert_eq!(output, "foo\x1a\r\nbar");
+        assert_none!(reader.terminator());
+    }
+
+    #[test]
+    fn read_lenient_ctrl_z_followed_by_whitespace() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\r\n\r\n \x1a\0" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn read_lenient_across_chunks() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(
+            2,
+            b"foo\x1a\n\n\nbar\x1a\n\n\n" as &[u8],
+        ))
+        .lenient(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 10);
+        assert_eq!(output, "foo\x1a\n\n\nbar");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn read_empty_buffer() {
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut []), 0);
+        assert!(!reader.terminated);
+    }
+
+    struct BadReader;
+
+    impl Read for BadReader {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
             Ok(buf.len() + 1)
         }
     }
 
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn read_with_bad_inner() {
+        let error = assert_err!(ReadToCtrlZ::new(BadReader).read(&mut [0]));
+
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(
+            error.to_string(),
+            "buffer smaller than amount of bytes read"
+        )
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    // `catch_unwind()` requires a newer Rust version, but is only used in this test.
+    #[allow(clippy::incompatible_msrv)]
+    fn read_with_bad_inner_panics() {
+        let payload = assert_err!(panic::catch_unwind(|| {
+            ReadToCtrlZ::new(BadReader).read(&mut [0])
+        }));
+
+        assert_eq!(
+            assert_some!(payload.downcast_ref::<String>()),
+            "inner reader violated the `Read` contract by reading 2 bytes into a 1 byte buffer"
+        );
+    }
+
+    #[test]
+    fn read_ctrl_z_at_limit() {
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo\x1abar" as &[u8])
+                .require_marker_within(3)
+                .read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_end_at_limit() {
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo" as &[u8])
+                .require_marker_within(3)
+                .read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_ctrl_z_after_limit() {
+        let mut output = [0; 8];
+        let mut reader = ReadToCtrlZ::new(b"foob\x1a" as &[u8]).require_marker_within(3);
+
+        // Bytes up to the limit are still delivered.
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(&output[..3], b"foo");
+
+        let error = assert_err!(reader.read(&mut output));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "no 0x1A byte found within 3 bytes (4 bytes read)"
+        );
+
+        // The error is only returned once.
+        assert_ok_eq!(reader.read(&mut output), 0);
+    }
+
+    #[test]
+    fn buf_read_exclude_ctrl_z() {
+        assert_ok_eq!(ReadToCtrlZ::new(b"foo\x1a" as &[u8]).fill_buf(), b"foo");
+    }
+
+    #[test]
+    fn buf_read_no_ctrl_z() {
+        assert_ok_eq!(ReadToCtrlZ::new(b"foo" as &[u8]).fill_buf(), b"foo");
+    }
+
+    #[test]
+    fn buf_read_stop_at_ctrl_z() {
+        assert_ok_eq!(ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).fill_buf(), b"foo");
+    }
+
+    #[test]
+    fn buf_read_after_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+
+        // The reader should return nothing else, since the EOF `0x1A` was reached.
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn buf_read_after_starting_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"\x1abar" as &[u8]);
+
+        // Should stop before "bar".
+        assert_ok_eq!(reader.fill_buf(), b"");
+
+        // The reader should return nothing else, since the EOF `0x1A` was reached.
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn buf_read_until_stop_at_ctrl_z() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1a\n" as &[u8]);
+
+        // The delimiter follows the `0x1A` byte, so it is never reached.
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 6);
+        assert_eq!(output, b"foobar");
+
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 0);
+        assert_eq!(output, b"foobar");
+    }
+
+    #[test]
+    fn buf_read_until_stop_at_ctrl_z_across_chunks() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar\x1a\n" as &[u8]));
+
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 6);
+        assert_eq!(output, b"foobar");
+    }
+
+    #[test]
+    fn read_skip_leading_markers() {
+        let mut output = String::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"\x1a\x1ahello\x1aworld" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 5);
+        assert_eq!(output, "hello");
+        assert!(reader.is_terminated());
+        assert_eq!(reader.bytes_delivered(), 5);
+    }
+
+    #[test]
+    fn read_skip_leading_markers_disabled() {
+        let mut output = String::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"\x1a\x1ahello\x1aworld" as &[u8]).skip_leading_markers(false);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn read_skip_leading_markers_single_bytes() {
+        let bytes = assert_ok!(ReadToCtrlZ::new(b"\x1a\x1ahello\x1aworld" as &[u8])
+            .skip_leading_markers(true)
+            .bytes()
+            .collect::<Result<Vec<_>>>());
+
+        assert_eq!(bytes, b"hello");
+    }
+
+    #[test]
+    fn read_skip_leading_markers_only_markers() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1a\x1a" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn buf_read_skip_leading_markers() {
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(
+            1,
+            b"\x1a\x1ahello\x1aworld" as &[u8],
+        ))
+        .skip_leading_markers(true);
+
+        assert_ok_eq!(reader.fill_buf(), b"h");
+        assert_eq!(reader.inner_bytes_read(), 2);
+        reader.consume(1);
+        let mut output = Vec::new();
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 4);
+        assert_eq!(output, b"ello");
+        assert_eq!(reader.bytes_delivered(), 5);
+    }
+
+    #[test]
+    fn find_ctrl_z_skip_leading_markers() {
+        let mut reader =
+            ReadToCtrlZ::new(b"\x1a\x1ahello\x1aworld" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.find_ctrl_z(), Some(5));
+    }
+
+    #[test]
+    fn rewind_skip_leading_markers() {
+        let mut output = String::new();
+        let mut reader =
+            ReadToCtrlZ::new(Cursor::new(b"\x1afoo\x1a".to_vec())).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_ok!(reader.rewind());
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foofoo");
+    }
+
+    #[test]
+    fn read_error_on_leading_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]).error_on_leading_ctrl_z(true);
+
+        assert_eq!(
+            assert_err!(reader.read_to_string(&mut output)).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(reader.is_terminated());
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn read_error_on_leading_ctrl_z_only() {
+        let mut reader = ReadToCtrlZ::new(b"\x1a" as &[u8]).error_on_leading_ctrl_z(true);
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn read_error_on_leading_ctrl_z_not_leading() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a" as &[u8]).error_on_leading_ctrl_z(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_error_on_leading_ctrl_z_empty() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"" as &[u8]).error_on_leading_ctrl_z(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+    }
+
+    #[test]
+    fn read_error_on_leading_ctrl_z_disabled() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 0);
+    }
+
+    #[test]
+    fn buf_read_error_on_leading_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]).error_on_leading_ctrl_z(true);
+
+        assert_eq!(
+            assert_err!(reader.fill_buf()).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn error_on_leading_ctrl_z_after_skipped_markers() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1a\x1afoo\x1a" as &[u8])
+            .skip_leading_markers(true)
+            .error_on_leading_ctrl_z(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn buf_read_c0_controls() {
+        let mut reader = ReadToCtrlZ::new(b"foo\tbar\r\n\x07baz" as &[u8])
+            .terminate_on(TerminatorSet::c0_controls());
+
+        assert_ok_eq!(reader.fill_buf(), b"foo\tbar\r\n");
+        reader.consume(9);
+        assert_none!(reader.terminator());
+
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_some_eq!(reader.terminator(), b'\x07');
+    }
+
+    #[test]
+    fn buf_read_lenient_ctrl_z_followed_by_text() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\r\nbar" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.read_until(b'\xff', &mut output), 9);
+        assert_eq!(output, b"foo\x1a\r\nbar");
+        assert_none!(reader.terminator());
+    }
+
+    #[test]
+    fn buf_read_lenient_ctrl_z_followed_by_whitespace() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\r\n\r\n \x1a\0" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.read_until(b'\xff', &mut output), 3);
+        assert_eq!(output, b"foo");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn buf_read_lenient_across_chunks() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(
+            2,
+            b"foo\x1a\n\n\nbar\x1a\n\n\n" as &[u8],
+        ))
+        .lenient(true);
+
+        assert_ok_eq!(reader.read_until(b'\xff', &mut output), 10);
+        assert_eq!(output, b"foo\x1a\n\n\nbar");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn buf_read_ctrl_z_at_limit() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).require_marker_within(3);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn buf_read_ctrl_z_after_limit() {
+        let mut reader = ReadToCtrlZ::new(b"foob\x1a" as &[u8]).require_marker_within(3);
+
+        // Bytes up to the limit are still delivered.
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+
+        let error = assert_err!(reader.fill_buf());
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "no 0x1A byte found within 3 bytes (4 bytes read)"
+        );
+
+        // The error is only returned once.
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn read_fields() {
+        let mut reader = ReadToCtrlZ::new(b"foo\tbar\t\nbaz\n\nqux\x1a\tquux" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_fields(b'\t'),
+            vec![b"foo".to_vec(), b"bar".to_vec(), Vec::new()]
+        );
+        assert_ok_eq!(reader.read_fields(b'\t'), vec![b"baz".to_vec()]);
+        assert_ok_eq!(reader.read_fields(b'\t'), vec![Vec::<u8>::new()]);
+        assert_ok_eq!(reader.read_fields(b'\t'), vec![b"qux".to_vec()]);
+        assert_ok_eq!(reader.read_fields(b'\t'), Vec::<Vec<u8>>::new());
+    }
+
+    #[test]
+    fn read_fields_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foo\tbar\n\x1abaz"));
+
+        assert_ok_eq!(
+            reader.read_fields(b'\t'),
+            vec![b"foo".to_vec(), b"bar".to_vec()]
+        );
+    }
+
+    #[test]
+    fn read_fields_no_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foo,bar" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_fields(b','),
+            vec![b"foo".to_vec(), b"bar".to_vec()]
+        );
+        assert_ok_eq!(reader.read_fields(b','), Vec::<Vec<u8>>::new());
+    }
+
+    /// A reader that fails with [`ErrorKind::Interrupted`] once, before its first read.
+    struct InterruptingReader<'a> {
+        bytes: &'a [u8],
+        interrupted: bool,
+    }
+
+    impl<'a> InterruptingReader<'a> {
+        fn new(bytes: &'a [u8]) -> Self {
+            InterruptingReader {
+                bytes: bytes,
+                interrupted: false,
+            }
+        }
+
+        fn interrupt(&mut self) -> Result<()> {
+            if self.interrupted {
+                Ok(())
+            } else {
+                self.interrupted = true;
+                Err(Error::new(ErrorKind::Interrupted, "interrupted"))
+            }
+        }
+    }
+
+    impl<'a> Read for InterruptingReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            try!(self.interrupt());
+            self.bytes.read(buf)
+        }
+    }
+
+    impl<'a> BufRead for InterruptingReader<'a> {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            try!(self.interrupt());
+            Ok(self.bytes)
+        }
+
+        fn consume(&mut self, amount: usize) {
+            self.bytes.consume(amount)
+        }
+    }
+
+    #[test]
+    fn skip_n_bytes() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.skip_n_bytes(4), 4);
+        assert_ok_eq!(reader.read_to_string(&mut output), 2);
+        assert_eq!(output, "ar");
+    }
+
+    #[test]
+    fn skip_n_bytes_stop_at_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.skip_n_bytes(5000), 6);
+        assert_ok_eq!(reader.skip_n_bytes(1), 0);
+    }
+
+    #[test]
+    fn skip_n_bytes_buffered() {
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar\x1abaz" as &[u8]));
+
+        assert_ok_eq!(reader.skip_n_bytes_buffered(5), 5);
+        assert_ok_eq!(reader.fill_buf(), b"r");
+    }
+
+    #[test]
+    fn skip_n_bytes_buffered_stop_at_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.skip_n_bytes_buffered(5000), 6);
+        assert_ok_eq!(reader.skip_n_bytes_buffered(1), 0);
+    }
+
+    #[test]
+    fn skip_n_bytes_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.skip_n_bytes(5000), 6);
+    }
+
+    #[test]
+    fn skip_n_bytes_buffered_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.skip_n_bytes_buffered(5000), 6);
+    }
+
+    #[test]
+    fn len_hint() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.len_hint(), Some(6));
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.len_hint(), Some(4));
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.len_hint(), Some(0));
+        assert_ok_eq!(reader.read(&mut output), 0);
+        assert_ok_eq!(reader.len_hint(), Some(0));
+    }
+
+    #[test]
+    fn len_hint_no_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar".to_vec()));
+
+        assert_ok_eq!(reader.len_hint(), Some(6));
+        assert_ok_eq!(reader.len_hint(), Some(6));
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_ok_eq!(reader.len_hint(), Some(0));
+    }
+
+    #[test]
+    fn len_hint_after_consume() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz".to_vec()));
+
+        assert_ok_eq!(reader.fill_buf(), b"foobar");
+        reader.consume(1);
+        assert_ok_eq!(reader.len_hint(), Some(5));
+    }
+
+    #[test]
+    fn len_hint_skip_leading_markers() {
+        let mut output = String::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"\x1a\x1ahello\x1abaz" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.len_hint(), Some(5));
+        assert_ok_eq!(reader.read_to_string(&mut output), 5);
+        assert_eq!(output, "hello");
+        assert_eq!(reader.bytes_delivered(), 5);
+    }
+
+    #[test]
+    fn len_hint_require_marker_within() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1a" as &[u8]).require_marker_within(4);
+
+        assert_ok_eq!(reader.len_hint(), Some(4));
+        assert_err!(reader.read_to_end(&mut output));
+        assert_eq!(output, b"foob");
+        assert_ok_eq!(reader.len_hint(), Some(0));
+    }
+
+    #[test]
+    fn len_hint_require_marker_within_after_read() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1a" as &[u8]).require_marker_within(4);
+
+        assert_ok_eq!(reader.len_hint(), Some(4));
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.len_hint(), Some(2));
+    }
+
+    #[test]
+    fn len_hint_ensure_trailing_newline() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.len_hint(), None);
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"foo\n");
+        assert_ok_eq!(reader.len_hint(), Some(0));
+    }
+
+    #[test]
+    fn len_hint_ensure_trailing_newline_present() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\n\x1a" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.len_hint(), None);
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_ok_eq!(reader.len_hint(), Some(0));
+    }
+
+    #[test]
+    fn len_hint_lenient() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.len_hint(), None);
+    }
+
+    #[test]
+    fn counters_read_ctrl_z_mid_chunk() {
+        let mut output = [0; 16];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abarbaz" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(reader.bytes_delivered(), 3);
+        assert_eq!(reader.inner_bytes_read(), 10);
+        assert_eq!(reader.excess(), 7);
+
+        // Nothing more is read after termination.
+        assert_ok_eq!(reader.read(&mut output), 0);
+        assert_eq!(reader.inner_bytes_read(), 10);
+    }
+
+    #[test]
+    fn counters_read_small_buffer() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.read(&mut output), 1);
+        assert_eq!(reader.bytes_delivered(), 3);
+        assert_eq!(reader.inner_bytes_read(), 4);
+        assert_eq!(reader.excess(), 1);
+    }
+
+    #[test]
+    fn counters_buf_read() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(2);
+        assert_eq!(reader.bytes_delivered(), 2);
+        assert_eq!(reader.inner_bytes_read(), 2);
+
+        reader.consume(1);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_eq!(reader.bytes_delivered(), 3);
+        assert_eq!(reader.inner_bytes_read(), 4);
+        assert_eq!(reader.excess(), 1);
+    }
+
+    #[test]
+    fn counters_lenient_lookahead() {
+        let mut output = [0; 4];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\n\nbar" as &[u8]).lenient(true);
+
+        // The lookahead reads the rest of the input to decide the `0x1A` byte is data.
+        assert_ok_eq!(reader.read(&mut output), 4);
+        assert_eq!(reader.bytes_delivered(), 4);
+        assert_eq!(reader.inner_bytes_read(), 9);
+        assert_eq!(reader.excess(), 5);
+    }
+
+    #[test]
+    fn counters_buf_read_lenient_lookahead() {
+        let mut reader =
+            ReadToCtrlZ::new(BufReader::with_capacity(2, b"\x1a\n\n\n\x1a" as &[u8])).lenient(true);
+
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_eq!(reader.bytes_delivered(), 0);
+        assert_eq!(reader.inner_bytes_read(), 5);
+        assert_eq!(reader.excess(), 5);
+    }
+
+    #[test]
+    fn collect_until_ctrl_z() {
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).collect_until_ctrl_z(3),
+            b"foo"
+        );
+    }
+
+    #[test]
+    fn collect_until_ctrl_z_end_at_limit() {
+        assert_ok_eq!(
+            ReadToCtrlZ::new(b"foo" as &[u8]).collect_until_ctrl_z(3),
+            b"foo"
+        );
+    }
+
+    #[test]
+    fn collect_until_ctrl_z_exceed_limit() {
+        let error = assert_err!(ReadToCtrlZ::new(b"foob\x1a" as &[u8]).collect_until_ctrl_z(3));
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "no 0x1A byte found within 3 bytes (4 bytes read)"
+        );
+    }
+
+    #[test]
+    fn read_to_end_limited_under_limit() {
+        let mut output = b"baz".to_vec();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end_limited(&mut output, 4), 3);
+        assert_eq!(output, b"bazfoo");
+    }
+
+    #[test]
+    fn read_to_end_limited_at_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end_limited(&mut output, 3), 3);
+        assert_eq!(output, b"foo");
+    }
+
+    #[test]
+    fn read_to_end_limited_at_limit_without_ctrl_z() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end_limited(&mut output, 3), 3);
+        assert_eq!(output, b"foo");
+    }
+
+    #[test]
+    fn read_to_end_limited_over_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        let error = assert_err!(reader.read_to_end_limited(&mut output, 4));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "no 0x1A byte found within 4 bytes (5 bytes read)"
+        );
+        assert_eq!(output, b"foob");
+
+        // The remaining bytes are still available.
+        assert_ok_eq!(reader.read_to_end(&mut output), 2);
+        assert_eq!(output, b"foobar");
+    }
+
+    #[test]
+    fn read_to_string_limited_under_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string_limited(&mut output, 4), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_to_string_limited_at_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string_limited(&mut output, 3), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_to_string_limited_over_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        let error = assert_err!(reader.read_to_string_limited(&mut output, 4));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(output, "foob");
+    }
+
+    #[test]
+    fn read_to_string_limited_over_limit_splits_char() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new("caf\u{e9}\x1a".as_bytes());
+
+        assert_err!(reader.read_to_string_limited(&mut output, 4));
+        assert_eq!(output, "caf");
+    }
+
+    #[test]
+    fn read_to_string_limited_invalid_utf8() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"fo\xff\x1a" as &[u8]);
+
+        let error = assert_err!(reader.read_to_string_limited(&mut output, 4));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn rewind() {
+        let mut first = String::new();
+        let mut second = String::new();
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar".to_vec()));
+
+        assert_ok_eq!(reader.read_to_string(&mut first), 3);
+        assert_ok!(reader.rewind());
+        assert_none!(reader.terminator());
+        assert_eq!(reader.bytes_delivered(), 0);
+        assert_eq!(reader.inner_bytes_read(), 0);
+        assert_ok_eq!(reader.read_to_string(&mut second), 3);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn rewind_partial() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar".to_vec()));
+
+        assert_ok_eq!(reader.read(&mut [0; 2]), 2);
+        assert_ok!(reader.rewind());
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn rewind_buf_reader() {
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(
+            2,
+            Cursor::new(b"foo\x1a \nbar\x1a\n".to_vec()),
+        ))
+        .lenient(true);
+
+        assert_ok_eq!(reader.read_until(b'\xff', &mut first), 9);
+        assert_ok!(reader.rewind());
+        assert_ok_eq!(reader.read_until(b'\xff', &mut second), 9);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn peek_n() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.peek_n(3), b"foo");
+        assert_ok_eq!(reader.peek_n(10), b"foobar");
+        assert_eq!(reader.bytes_delivered(), 0);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn peek_n_across_chunks() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar\x1abaz" as &[u8]));
+
+        assert_ok_eq!(reader.peek_n(5), b"fooba");
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn peek_n_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.peek_n(5), b"fooba");
+    }
+
+    #[test]
+    fn peek_n_after_partial_read() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_ok_eq!(reader.peek_n(8), b"obar");
+        assert_ok_eq!(reader.read(&mut output), 2);
+        assert_eq!(&output, b"ob");
+        assert_ok_eq!(reader.peek_n(8), b"ar");
+    }
+
+    #[test]
+    fn peek_n_then_buf_read() {
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar\x1abaz" as &[u8]));
+
+        assert_ok_eq!(reader.peek_n(8), b"foobar");
+        assert_ok_eq!(reader.fill_buf(), b"foobar");
+        reader.consume(6);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn peek_n_skip_leading_markers() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1a\x1ahello" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(reader.peek_n(5), b"hello");
+        assert_ok_eq!(reader.read_to_string(&mut output), 5);
+        assert_eq!(output, "hello");
+        assert_eq!(reader.bytes_delivered(), 5);
+    }
+
+    #[test]
+    fn peek_n_lenient() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar\x1a\n" as &[u8]).lenient(true);
+
+        assert_ok_eq!(reader.peek_n(5), b"foo\x1ab");
+        assert_ok_eq!(reader.peek_n(10), b"foo\x1abar");
+    }
+
+    #[test]
+    fn read_until_or_marker_delimiter() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foo\xffbar" as &[u8]));
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foo\xff");
+    }
+
+    #[test]
+    fn read_until_or_marker_marker() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foo\x1a\xffbar" as &[u8]));
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"foo");
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"foo");
+    }
+
+    #[test]
+    fn read_until_or_marker_eof() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Eof
+        );
+        assert_eq!(output, b"foobar");
+    }
+
+    #[test]
+    fn read_until_or_marker_delimiter_before_marker() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\xff\x1abar" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foo\xff");
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"foo\xff");
+    }
+
+    #[test]
+    fn read_until_or_marker_lenient() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar\xffbaz\x1a" as &[u8]).lenient(true);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\xff', &mut output),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foo\x1abar\xff");
+    }
+
+    #[test]
+    fn read_until_or_marker_skip_leading_markers_without_leading() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"ab\x1acd" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\n', &mut output),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"ab");
+    }
+
+    #[test]
+    fn read_until_or_marker_skip_leading_markers() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"\x1a\x1aab\ncd\x1aef" as &[u8]).skip_leading_markers(true);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\n', &mut output),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"ab\n");
+        output.clear();
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\n', &mut output),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"cd");
+    }
+
+    #[test]
+    fn read_until_or_marker_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\xff\x1a" as &[u8]).require_marker_within(4);
+
+        assert_eq!(
+            assert_err!(reader.read_until_or_marker(b'\xff', &mut output)).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(output, b"foob");
+    }
+
+    #[test]
+    fn discard_to_marker() {
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.discard_to_marker(), 6);
+        assert_eq!(reader.bytes_delivered(), 6);
+        assert_some_eq!(reader.terminator(), b'\x1a');
+        assert_ok_eq!(reader.discard_to_marker(), 0);
+    }
+
+    #[test]
+    fn discard_to_marker_no_ctrl_z() {
+        let mut reader = ReadToCtrlZ::new(b"foobar" as &[u8]);
+
+        assert_ok_eq!(reader.discard_to_marker(), 6);
+        assert_none!(reader.terminator());
+    }
+
+    #[test]
+    fn discard_to_marker_buffered() {
+        let mut inner = BufReader::with_capacity(2, b"foobar\x1abaz\x1a" as &[u8]);
+
+        assert_ok_eq!(ReadToCtrlZ::new(&mut inner).discard_to_marker_buffered(), 6);
+        // The next segment starts following the `0x1A` byte.
+        let mut output = String::new();
+        assert_ok_eq!(ReadToCtrlZ::new(&mut inner).read_to_string(&mut output), 3);
+        assert_eq!(output, "baz");
+    }
+
+    #[test]
+    fn discard_to_marker_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.discard_to_marker(), 6);
+    }
+
+    #[test]
+    fn discard_to_marker_buffered_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.discard_to_marker_buffered(), 6);
+    }
+
+    #[test]
+    fn with_buffer_clears() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_buffer(b"foo\x1abar" as &[u8], b"baz".to_vec());
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn with_buffer_reused() {
+        let mut pool = vec![Vec::with_capacity(4096)];
+        let ptr = pool[0].as_ptr();
+
+        for input in [b"foo\x1abar" as &[u8], b"baz\x1aqux"].iter() {
+            let mut reader = ReadToCtrlZ::with_buffer(*input, assert_some!(pool.pop()));
+            // Reading ahead uses the internal buffer.
+            assert_ok_eq!(reader.peek_n(3), &input[..3]);
+            let buffer = reader.into_buffer();
+
+            assert_eq!(buffer.as_ptr(), ptr);
+            assert_eq!(buffer.capacity(), 4096);
+            assert!(buffer.is_empty());
+            pool.push(buffer);
+        }
+    }
+
+    #[test]
+    fn into_parts() {
+        let mut reader = ReadToCtrlZ::with_buffer(b"foo\x1abar" as &[u8], Vec::with_capacity(16));
+        assert_ok_eq!(reader.peek_n(2), b"fo");
+
+        let (inner, buffer) = reader.into_parts();
+        assert_eq!(inner, b"o\x1abar");
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), 16);
+    }
+
+    #[test]
+    fn by_ref_consecutive_segments() {
+        let mut inner = BufReader::with_capacity(4, b"foo\nbar\x1abaz\nqux\x1aquux" as &[u8]);
+
+        let first = ReadToCtrlZ::by_ref(&mut inner)
+            .lines()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(first, ["foo", "bar"]);
+
+        let second = ReadToCtrlZ::by_ref(&mut inner)
+            .lines()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(second, ["baz", "qux"]);
+
+        // The underlying reader is positioned after the second `0x1A` byte.
+        let mut output = String::new();
+        assert_ok_eq!(inner.read_to_string(&mut output), 4);
+        assert_eq!(output, "quux");
+    }
+
+    #[test]
+    fn by_ref_len_hint() {
+        let mut inner = Cursor::new(b"foo\x1abar".to_vec());
+
+        assert_ok_eq!(ReadToCtrlZ::by_ref(&mut inner).len_hint(), Some(3));
+    }
+
+    #[test]
+    fn by_ref_rewind() {
+        let mut inner = Cursor::new(b"foo\x1abar".to_vec());
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::by_ref(&mut inner);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_ok!(reader.rewind());
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foofoo");
+    }
+
+    #[test]
+    fn quote_aware() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"a,\"b\x1a\"\nc,d\x1ae" as &[u8]).quote_aware(b'"');
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 10);
+        assert_eq!(output, "a,\"b\x1a\"\nc,d");
+    }
+
+    #[test]
+    fn quote_aware_escaped_quote() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"a,\"b\"\"\x1a\"\"c\"\x1ad" as &[u8]).quote_aware(b'"');
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 11);
+        assert_eq!(output, "a,\"b\"\"\x1a\"\"c\"");
+    }
+
+    #[test]
+    fn quote_aware_across_reads() {
+        let mut input = b"\"a\x1ab\"\x1ac" as &[u8];
+        let mut output = String::new();
+        // The inner reader returns a single byte per read.
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            let len = cmp::min(buf.len(), 1);
+            input.read(&mut buf[..len])
+        }))
+        .quote_aware(b'"');
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 5);
+        assert_eq!(output, "\"a\x1ab\"");
+    }
+
+    #[test]
+    fn quote_aware_buf_read() {
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"\"a\x1ab\"\x1ac" as &[u8]))
+            .quote_aware(b'"');
+
+        assert_ok_eq!(reader.fill_buf(), b"\"a");
+        reader.consume(2);
+        assert_ok_eq!(reader.fill_buf(), b"\x1ab");
+        reader.consume(2);
+        assert_ok_eq!(reader.fill_buf(), b"\"");
+        reader.consume(1);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn quote_aware_buf_read_partial_consume() {
+        let mut reader = ReadToCtrlZ::new(b"\"a\"\x1ab" as &[u8]).quote_aware(b'"');
+
+        assert_ok_eq!(reader.fill_buf(), b"\"a\"");
+        // Only the opening quote is consumed, so the region is still quoted.
+        reader.consume(1);
+        assert_ok_eq!(reader.fill_buf(), b"a\"");
+        reader.consume(2);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn quote_aware_peek_n() {
+        let mut reader = ReadToCtrlZ::new(b"\"a\x1a\"\x1ab" as &[u8]).quote_aware(b'"');
+
+        assert_ok_eq!(reader.peek_n(10), b"\"a\x1a\"");
+    }
+
+    #[test]
+    fn quote_aware_rewind() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"\"a\x1ab".to_vec())).quote_aware(b'"');
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 4);
+        assert_ok!(reader.rewind());
+        assert_ok_eq!(reader.read_to_string(&mut output), 4);
+        assert_eq!(output, "\"a\x1ab\"a\x1ab");
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut reader = ReadToCtrlZ::with_capacity(2, b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"fo");
+        reader.consume(2);
+        assert_ok_eq!(reader.fill_buf(), b"ob");
+    }
+
+    #[test]
+    fn from_boxed_bytes() {
+        let mut reader = ReadToCtrlZ::from_boxed_bytes(b"foo\x1abar".to_vec().into_boxed_slice());
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+        assert_eq!(reader.into_parts().0.position(), 4);
+    }
+
+    assert_impl_all!(ReadToCtrlZ<Cursor<Arc<[u8]>>>: Read, BufRead, Send, Sync);
+
+    #[test]
+    fn arc_cursor() {
+        let buffer: Arc<[u8]> = Arc::from(b"foo\x1abar".to_vec());
+        let cursor = Cursor::new(buffer.clone());
+        let mut reader = ReadToCtrlZ::new(cursor.clone());
+
+        assert_eq!(Arc::strong_count(&buffer), 3);
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+        assert_eq!(reader.into_parts().0.position(), 4);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(Arc::strong_count(&buffer), 2);
+    }
+
+    #[test]
+    fn string_cursor() {
+        // `0x1A` is an ASCII control character, so a `String` can contain it as valid UTF-8.
+        let string = String::from("foo\x1abar");
+        assert_eq!(string.as_bytes(), b"foo\x1abar");
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(Cursor::new(string));
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn string_cursor_buf_read() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(String::from("foo\nbar\x1a\u{e9}")));
+
+        let lines = (&mut reader)
+            .lines()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["foo", "bar"]);
+        // The bytes following the `0x1A` byte remain in the cursor.
+        let cursor = reader.into_parts().0;
+        assert_eq!(&cursor.get_ref()[cursor.position() as usize..], "\u{e9}");
+    }
+
+    #[test]
+    fn arc_cursor_across_threads() {
+        let cursor = Cursor::new(Arc::<[u8]>::from(b"foo\x1abar".to_vec()));
+
+        let handles = (0..4)
+            .map(|_| {
+                let mut reader = ReadToCtrlZ::new(cursor.clone());
+                thread::spawn(move || {
+                    let mut output = String::new();
+                    reader.read_to_string(&mut output).unwrap();
+                    output
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_ok_eq!(handle.join(), "foo");
+        }
+    }
+
+    #[test]
+    fn from_boxed_bytes_read() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::from_boxed_bytes(b"foobar".to_vec().into_boxed_slice());
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+        assert_none!(reader.terminator());
+    }
+
+    #[test]
+    fn find_ctrl_z_across_chunks() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar\x1abaz" as &[u8]));
+
+        assert_ok_eq!(reader.find_ctrl_z(), Some(6));
+        // Scanning again answers from the read-ahead buffer.
+        assert_ok_eq!(reader.find_ctrl_z(), Some(6));
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+        assert_ok_eq!(reader.find_ctrl_z(), None);
+    }
+
+    #[test]
+    fn find_ctrl_z_after_consume() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(4, b"foobar\x1a" as &[u8]));
+
+        assert_ok_eq!(reader.fill_buf(), b"foob");
+        reader.consume(2);
+        assert_ok_eq!(reader.find_ctrl_z(), Some(4));
+        assert_ok_eq!(reader.read_to_string(&mut output), 4);
+        assert_eq!(output, "obar");
+    }
+
+    #[test]
+    fn find_ctrl_z_retries_interrupted() {
+        let mut reader = ReadToCtrlZ::new(InterruptingReader::new(b"foobar\x1abaz"));
+
+        assert_ok_eq!(reader.find_ctrl_z(), Some(6));
+    }
+
+    #[test]
+    fn find_ctrl_z_no_ctrl_z() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::with_capacity(2, b"foobar" as &[u8]));
+
+        assert_ok_eq!(reader.find_ctrl_z(), None);
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 6);
+        assert_eq!(output, b"foobar");
+    }
+
+    #[test]
+    fn find_ctrl_z_quote_aware() {
+        let mut reader = ReadToCtrlZ::new(b"\"a\x1a\"b\x1a" as &[u8]).quote_aware(b'"');
+
+        assert_ok_eq!(reader.find_ctrl_z(), Some(5));
+    }
+
+    #[test]
+    fn is_terminated_natural_eof() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"abc" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+        assert!(!reader.is_terminated());
+    }
+
     #[test]
-    fn read_with_bad_inner() {
-        let error = assert_err!(ReadToCtrlZ::new(BadReader).read(&mut []));
+    fn is_terminated_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"abc\x1a" as &[u8]);
+
+        assert!(!reader.is_terminated());
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn take_inner() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new((b"foo\x1abar" as &[u8]).take(2));
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 2);
+        assert_eq!(output, "fo");
+        assert!(!reader.is_terminated());
+
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new((b"foo\x1abar" as &[u8]).take(5));
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn take_outer() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).take(2);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 2);
+        assert_eq!(output, "fo");
+        assert!(!reader.get_ref().is_terminated());
+
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).take(5);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.get_ref().is_terminated());
+    }
+
+    #[test]
+    fn read_to_end_buffered() {
+        let mut output = b"foo".to_vec();
+        let mut inner = BufReader::with_capacity(4, b"barbaz\x1aqux" as &[u8]);
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(&mut inner).read_to_end_buffered(&mut output),
+            6
+        );
+        assert_eq!(output, b"foobarbaz");
+        let mut rest = String::new();
+        assert_ok_eq!(inner.read_to_string(&mut rest), 3);
+        assert_eq!(rest, "qux");
+    }
+
+    #[test]
+    fn read_to_end_buffered_matches_read_to_end() {
+        let input = b"foo\x1abar" as &[u8];
+        let mut expected = Vec::new();
+        let mut output = Vec::new();
+
+        assert_ok!(ReadToCtrlZ::new(input).read_to_end(&mut expected));
+        assert_ok!(ReadToCtrlZ::new(input).read_to_end_buffered(&mut output));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn read_to_writer() {
+        let mut output = b"foo".to_vec();
+        let mut reader = ReadToCtrlZ::new(b"barbaz\x1aqux" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_writer(&mut output), 6);
+        assert_eq!(output, b"foobarbaz");
+        assert_some_eq!(reader.terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn read_to_writer_matches_copy() {
+        let input = vec![b'a'; 20 * 1024];
+        let mut expected = Vec::new();
+        let mut output = Vec::new();
+
+        assert_ok_eq!(
+            std::io::copy(&mut ReadToCtrlZ::new(&input[..]), &mut expected),
+            input.len() as u64
+        );
+        assert_ok_eq!(
+            ReadToCtrlZ::new(&input[..]).read_to_writer(&mut output),
+            input.len() as u64
+        );
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn read_to_writer_buffered() {
+        let mut output = Vec::new();
+        let mut inner = BufReader::with_capacity(4, b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(&mut inner).read_to_writer_buffered(&mut output),
+            6
+        );
+        assert_eq!(output, b"foobar");
+        let mut rest = String::new();
+        assert_ok_eq!(inner.read_to_string(&mut rest), 3);
+        assert_eq!(rest, "baz");
+    }
+
+    #[test]
+    fn read_to_writer_buffered_write_error() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a" as &[u8]);
 
-        assert_eq!(error.kind(), ErrorKind::Other);
         assert_eq!(
-            error.to_string(),
-            "buffer smaller than amount of bytes read"
-        )
+            assert_err!(reader.read_to_writer_buffered(&mut &mut output[..])).kind(),
+            ErrorKind::WriteZero
+        );
+        // The bytes accepted by the writer were consumed, while the rest were not.
+        assert_eq!(&output, b"fo");
+        assert_eq!(reader.bytes_delivered(), 2);
+        assert_ok_eq!(reader.fill_buf(), b"o");
     }
 
     #[test]
-    fn buf_read_exclude_ctrl_z() {
-        assert_ok_eq!(ReadToCtrlZ::new(b"foo\x1a" as &[u8]).fill_buf(), b"foo");
+    fn read_to_writer_buffered_write_error_resume() {
+        let mut output = [0; 2];
+        let mut rest = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_err!(reader.read_to_writer_buffered(&mut &mut output[..]));
+        assert_ok_eq!(reader.read_to_writer_buffered(&mut rest), 4);
+
+        // No bytes were duplicated or lost.
+        assert_eq!(&output, b"fo");
+        assert_eq!(rest, b"obar");
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn buf_read_no_ctrl_z() {
-        assert_ok_eq!(ReadToCtrlZ::new(b"foo" as &[u8]).fill_buf(), b"foo");
+    fn until_outcome_serde_round_trip() {
+        for &(outcome, expected) in [
+            (UntilOutcome::Delimiter, r#""Delimiter""#),
+            (UntilOutcome::Marker, r#""Marker""#),
+            (UntilOutcome::Eof, r#""Eof""#),
+            (UntilOutcome::Limit, r#""Limit""#),
+        ]
+        .iter()
+        {
+            let json = assert_ok!(serde_json::to_string(&outcome));
+            assert_eq!(json, expected);
+            assert_ok_eq!(serde_json::from_str::<UntilOutcome>(&json), outcome);
+        }
     }
 
     #[test]
-    fn buf_read_stop_at_ctrl_z() {
-        assert_ok_eq!(ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).fill_buf(), b"foo");
+    fn read_unexpected_eof_does_not_terminate() {
+        let mut results = vec![
+            Ok(&b"foo"[..]),
+            Err(Error::new(ErrorKind::UnexpectedEof, "truncated")),
+            Ok(&b"bar\x1abaz"[..]),
+        ]
+        .into_iter();
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| match results.next() {
+            Some(Ok(chunk)) => (&*chunk).read(buf),
+            Some(Err(error)) => Err(error),
+            None => Ok(0),
+        }));
+        let mut output = [0; 16];
+
+        assert_ok_eq!(reader.read(&mut output), 3);
+        let error = assert_err!(reader.read(&mut output));
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        // The error does not indicate a `0x1A` byte, so reading is not terminated.
+        assert!(!reader.is_terminated());
+        assert_none!(reader.terminator());
+
+        // Once the error is handled, reading continues.
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(&output[..3], b"bar");
+        assert!(reader.is_terminated());
+        assert_eq!(reader.bytes_delivered(), 6);
     }
 
     #[test]
-    fn buf_read_after_ctrl_z() {
-        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]);
+    fn read_ensure_trailing_newline_added() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"foo\nbar\x1abaz" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 8);
+        assert_eq!(output, b"foo\nbar\n");
+        assert_eq!(reader.bytes_delivered(), 7);
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_present() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\n\x1a" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"foo\n");
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_at_eof() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"foo\n");
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_empty() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 0);
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_ctrl_z_at_start() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]).ensure_trailing_newline(true);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 0);
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_single_bytes() {
+        let mut reader = ReadToCtrlZ::new(b"ab\x1a" as &[u8]).ensure_trailing_newline(true);
+        let mut output = Vec::new();
+        let mut byte = [0];
+
+        while assert_ok!(reader.read(&mut byte)) == 1 {
+            output.push(byte[0]);
+        }
+
+        assert_eq!(output, b"ab\n");
+    }
+
+    #[test]
+    fn read_ensure_trailing_newline_exceeded() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar" as &[u8])
+            .require_marker_within(3)
+            .ensure_trailing_newline(true);
+
+        assert_err!(reader.read_to_end(&mut output));
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+
+    #[test]
+    fn buf_read_ensure_trailing_newline_added() {
+        let lines = ReadToCtrlZ::new(b"foo\nbar\x1abaz" as &[u8])
+            .ensure_trailing_newline(true)
+            .split(b'\n')
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn buf_read_ensure_trailing_newline() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).ensure_trailing_newline(true);
 
         assert_ok_eq!(reader.fill_buf(), b"foo");
         reader.consume(3);
-
-        // The reader should return nothing else, since the EOF `0x1A` was reached.
+        assert_ok_eq!(reader.fill_buf(), b"\n");
+        assert_ok_eq!(reader.fill_buf(), b"\n");
+        reader.consume(1);
         assert_ok_eq!(reader.fill_buf(), b"");
+        assert_ok_eq!(reader.read_to_end(&mut output), 0);
     }
 
     #[test]
-    fn buf_read_after_starting_ctrl_z() {
-        let mut reader = ReadToCtrlZ::new(b"\x1abar" as &[u8]);
+    fn buf_read_ensure_trailing_newline_present() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\n\x1abar" as &[u8]).ensure_trailing_newline(true);
 
-        // Should stop before "bar".
-        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_ok_eq!(reader.read_until(b'\0', &mut output), 4);
+        assert_eq!(output, b"foo\n");
+    }
+
+    #[test]
+    fn buf_read_ensure_trailing_newline_empty() {
+        let mut reader = ReadToCtrlZ::new(b"\x1afoo" as &[u8]).ensure_trailing_newline(true);
 
-        // The reader should return nothing else, since the EOF `0x1A` was reached.
         assert_ok_eq!(reader.fill_buf(), b"");
     }
+
+    #[test]
+    fn rewind_ensure_trailing_newline() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(Cursor::new(b"foo\x1a".to_vec())).ensure_trailing_newline(true);
+        assert_ok!(reader.read_to_end(&mut output));
+
+        assert_ok!(reader.rewind());
+        assert_ok!(reader.read_to_end(&mut output));
+        assert_eq!(output, b"foo\nfoo\n");
+    }
+
+    #[test]
+    fn read_until_limited_under_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\nbar\x1a" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 16),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foo\n");
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 16),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"foo\nbar");
+    }
+
+    #[test]
+    fn read_until_limited_delimiter_at_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\nbar" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 4),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foo\n");
+    }
+
+    #[test]
+    fn read_until_limited_marker_at_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 3),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, b"foo");
+    }
+
+    #[test]
+    fn read_until_limited_eof_at_limit() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 3),
+            UntilOutcome::Eof
+        );
+    }
+
+    #[test]
+    fn read_until_limited_resume() {
+        let mut output = Vec::new();
+        let inner = BufReader::with_capacity(2, b"foobar\nbaz\x1a" as &[u8]);
+        let mut reader = ReadToCtrlZ::new(inner);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 3),
+            UntilOutcome::Limit
+        );
+        assert_eq!(output, b"foo");
+        assert_eq!(reader.bytes_delivered(), 3);
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 3),
+            UntilOutcome::Limit
+        );
+        assert_eq!(output, b"foobar");
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 3),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"foobar\n");
+    }
+
+    #[test]
+    fn read_until_limited_zero() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_until_limited(b'\n', &mut output, 0),
+            UntilOutcome::Limit
+        );
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn read_line_limited_at_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\nbar\n\x1a" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 4),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, "foo\n");
+    }
+
+    #[test]
+    fn read_line_limited_resume() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foobar\x1abaz" as &[u8]);
+
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 4),
+            UntilOutcome::Limit
+        );
+        assert_eq!(output, "foob");
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 4),
+            UntilOutcome::Marker
+        );
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn read_line_limited_split_character() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new("abé\n".as_bytes());
+
+        // The limit splits the two bytes of "é", so it is left to be read next.
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 3),
+            UntilOutcome::Limit
+        );
+        assert_eq!(output, "ab");
+        assert_eq!(reader.bytes_delivered(), 2);
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 3),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, "abé\n");
+    }
+
+    #[test]
+    fn read_line_limited_split_character_keeps_buffer() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_buffer("abé\n".as_bytes(), Vec::with_capacity(1 << 20));
+
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 3),
+            UntilOutcome::Limit
+        );
+        assert_ok_eq!(
+            reader.read_line_limited(&mut output, 3),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, "abé\n");
+        assert_eq!(reader.into_buffer().capacity(), 1 << 20);
+    }
+
+    #[test]
+    fn read_line_limited_invalid_utf8() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\xff\xfe\nfoo" as &[u8]);
+
+        assert_eq!(
+            assert_err!(reader.read_line_limited(&mut output, 16)).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(output, "");
+    }
+
+    /// A decrypting reader that verifies its authentication tag on the read following its data.
+    struct AuthenticatedReader {
+        /// The decrypted chunks returned by successive reads. An empty chunk is a read returning no
+        /// bytes before the end of the data.
+        chunks: Vec<&'static [u8]>,
+        /// Whether the authentication tag is valid.
+        authentic: bool,
+        /// Whether the authentication tag has been verified.
+        finalized: bool,
+    }
+
+    impl AuthenticatedReader {
+        fn new(chunks: Vec<&'static [u8]>, authentic: bool) -> Self {
+            AuthenticatedReader {
+                chunks: chunks.into_iter().rev().collect(),
+                authentic: authentic,
+                finalized: false,
+            }
+        }
+    }
+
+    impl Read for AuthenticatedReader {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            if let Some(chunk) = self.chunks.pop() {
+                return (&*chunk).read(buf);
+            }
+            if !self.finalized {
+                self.finalized = true;
+                if !self.authentic {
+                    return Err(Error::new(ErrorKind::InvalidInput, "authentication failed"));
+                }
+            }
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn read_authentication_error() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(AuthenticatedReader::new(vec![b"foo"], false));
+
+        let error = assert_err!(reader.read_to_end(&mut output));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "authentication failed");
+        assert_eq!(output, b"foo");
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn read_authenticated_finalization() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(AuthenticatedReader::new(vec![b"foo"], true));
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        assert_eq!(output, b"foo");
+        assert!(reader.into_parts().0.finalized);
+    }
+
+    #[test]
+    fn read_zero_length_intermediate_read() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(AuthenticatedReader::new(
+            vec![b"foo", b"", b"bar\x1abaz"],
+            true,
+        ));
+
+        // The empty read ends the first `read_to_end()`, but does not terminate reading.
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        assert!(!reader.is_terminated());
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        assert_eq!(output, b"foobar");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn read_authentication_error_after_ctrl_z() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(AuthenticatedReader::new(vec![b"foo\x1a"], false));
+
+        // The authentication tag is not verified by stopping at the `0x1A` byte.
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        let (mut inner, _) = reader.into_parts();
+        assert!(!inner.finalized);
+        assert_eq!(
+            assert_err!(io::copy(&mut inner, &mut io::sink())).kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn buffered_capacity() {
+        let input = vec![b'a'; 2 * BUFFERED_CAPACITY];
+        let mut reader = ReadToCtrlZ::buffered(&input[..]);
+
+        assert_eq!(assert_ok!(reader.fill_buf()).len(), BUFFERED_CAPACITY);
+    }
+
+    #[test]
+    // `BufReader::capacity()` requires a newer Rust version, but is only used in this test.
+    #[allow(clippy::incompatible_msrv)]
+    fn buffered_uses_capacity() {
+        let reader = ReadToCtrlZ::buffered(&b"foo\x1a"[..]);
+
+        assert_eq!(reader.inner.capacity(), BUFFERED_CAPACITY);
+    }
+
+    #[test]
+    fn with_capacity_honored() {
+        let mut reader = ReadToCtrlZ::with_capacity(128, &[b'a'; 1024][..]);
+
+        assert_eq!(assert_ok!(reader.fill_buf()).len(), 128);
+    }
+
+    #[test]
+    fn into_innermost() {
+        let mut output = [0; 2];
+        let mut reader = ReadToCtrlZ::with_capacity(4, Cursor::new(b"foobar\x1a".to_vec()));
+        assert_ok!(reader.read_exact(&mut output));
+
+        // The bytes buffered by the `BufReader` are discarded.
+        assert_eq!(reader.into_innermost().position(), 4);
+    }
 }
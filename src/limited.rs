@@ -0,0 +1,187 @@
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+use std::io::Take;
+use ReadToCtrlZ;
+
+/// A reader to read until a `0x1A` byte or a limit on the bytes read from the inner reader,
+/// whichever comes first.
+///
+/// No more than the limit is ever read from the inner reader, including bytes read ahead past the
+/// `0x1A` byte. Once reading has finished, [`bytes_before_ctrl_z()`] and
+/// [`bytes_after_ctrl_z_within_limit()`] report how the limit was used: by data preceding the
+/// `0x1A` byte, or by bytes following it that were read from the inner reader but discarded.
+///
+/// This type is created using [`ReadToCtrlZ::new_with_limit()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut reader = ReadToCtrlZ::new_with_limit(&b"foo\x1abarbaz"[..], 7);
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// assert_eq!(reader.bytes_before_ctrl_z(), 3);
+/// assert_eq!(reader.bytes_after_ctrl_z_within_limit(), 3);
+/// ```
+///
+/// [`bytes_before_ctrl_z()`]: LimitedReadToCtrlZ::bytes_before_ctrl_z()
+/// [`bytes_after_ctrl_z_within_limit()`]: LimitedReadToCtrlZ::bytes_after_ctrl_z_within_limit()
+/// [`ReadToCtrlZ::new_with_limit()`]: crate::ReadToCtrlZ::new_with_limit()
+pub struct LimitedReadToCtrlZ<R> {
+    /// The internal reader, limited to the configured number of bytes.
+    inner: ReadToCtrlZ<Take<R>>,
+    /// The limit on the number of bytes read from the inner reader.
+    limit: u64,
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Creates a new `LimitedReadToCtrlZ`, reading no more than `limit` bytes from the provided
+    /// reader.
+    pub fn new_with_limit(inner: R, limit: u64) -> LimitedReadToCtrlZ<R> {
+        LimitedReadToCtrlZ {
+            inner: ReadToCtrlZ::new(inner.take(limit)),
+            limit: limit,
+        }
+    }
+}
+
+impl<R> LimitedReadToCtrlZ<R> {
+    /// Returns the limit on the number of bytes read from the inner reader.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns whether the limit has been reached.
+    ///
+    /// If the limit is reached before the `0x1A` byte, reading ends as if the inner reader had
+    /// ended.
+    pub fn is_limit_reached(&self) -> bool {
+        self.inner.inner_bytes_read() >= self.limit
+    }
+
+    /// Returns the number of bytes of the limit used by data preceding the `0x1A` byte.
+    ///
+    /// This is the number of bytes delivered.
+    pub fn bytes_before_ctrl_z(&self) -> u64 {
+        self.inner.bytes_delivered()
+    }
+
+    /// Returns the number of bytes of the limit used by bytes following the `0x1A` byte.
+    ///
+    /// These are bytes read from the inner reader along with the `0x1A` byte, which are never
+    /// delivered. The `0x1A` byte itself is not counted. This is `0` if the `0x1A` byte has not
+    /// been reached, and is only accurate once all bytes preceding it have been delivered. When
+    /// reading through [`BufRead`], bytes following the `0x1A` byte are never consumed, so this is
+    /// always `0`.
+    pub fn bytes_after_ctrl_z_within_limit(&self) -> u64 {
+        if self.inner.is_terminated() {
+            self.inner.excess().saturating_sub(1)
+        } else {
+            0
+        }
+    }
+
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<Take<R>> {
+        &self.inner
+    }
+
+    /// Unwraps this `LimitedReadToCtrlZ`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<Take<R>> {
+        self.inner
+    }
+}
+
+impl<R> Read for LimitedReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R> BufRead for LimitedReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn ctrl_z_before_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new_with_limit(b"foo\x1abarbaz" as &[u8], 7);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.get_ref().is_terminated());
+        assert!(reader.is_limit_reached());
+        assert_eq!(reader.bytes_before_ctrl_z(), 3);
+        assert_eq!(reader.bytes_after_ctrl_z_within_limit(), 3);
+    }
+
+    #[test]
+    fn limit_before_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new_with_limit(b"foobar\x1abaz" as &[u8], 4);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 4);
+        assert_eq!(output, "foob");
+        assert!(!reader.get_ref().is_terminated());
+        assert!(reader.is_limit_reached());
+        assert_eq!(reader.bytes_before_ctrl_z(), 4);
+        assert_eq!(reader.bytes_after_ctrl_z_within_limit(), 0);
+    }
+
+    #[test]
+    fn limit_at_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new_with_limit(b"foo\x1abar" as &[u8], 4);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.get_ref().is_terminated());
+        assert_eq!(reader.bytes_after_ctrl_z_within_limit(), 0);
+    }
+
+    #[test]
+    fn inner_shorter_than_limit() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new_with_limit(b"foo" as &[u8], 16);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert!(!reader.is_limit_reached());
+        assert_eq!(reader.limit(), 16);
+    }
+
+    #[test]
+    fn buf_read() {
+        let mut reader = ReadToCtrlZ::new_with_limit(b"foo\x1abar" as &[u8], 6);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+        assert_eq!(reader.bytes_before_ctrl_z(), 3);
+        assert_eq!(reader.bytes_after_ctrl_z_within_limit(), 0);
+    }
+}
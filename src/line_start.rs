@@ -0,0 +1,158 @@
+use std::io::Read;
+use std::io::Result;
+
+/// A reader to read until a `0x1A` byte at the start of a line.
+///
+/// Some CP/M-derived formats only treat `0x1A` as the end of the file when it begins a line, that
+/// is, when it is the first byte of the input or directly follows a `\n` byte. A `0x1A` byte
+/// anywhere else in a line is read as a regular byte. Whether the previous byte was `\n` is
+/// tracked across reads, so a line start split across reads of the inner reader is recognized.
+///
+/// As with [`ReadToCtrlZ`], bytes following the terminating `0x1A` byte within the same read of
+/// the inner reader are discarded.
+///
+/// # Example
+/// ```
+/// use ctrl_z::LineStartCtrlZReader;
+/// use std::io::Read;
+///
+/// let mut reader = LineStartCtrlZReader::new(&b"foo\x1abar\n\x1abaz"[..]);
+/// let mut output = String::new();
+///
+/// // Only the `0x1A` byte at the start of the second line terminates.
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo\x1abar\n");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct LineStartCtrlZReader<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// Whether the last byte delivered was `\n`, or no byte has been delivered yet.
+    last_was_newline: bool,
+    /// Whether or not a `0x1A` byte at the start of a line has been reached.
+    terminated: bool,
+}
+
+impl<R> LineStartCtrlZReader<R> {
+    /// Creates a new `LineStartCtrlZReader`, wrapping the provided reader.
+    pub fn new(inner: R) -> Self {
+        LineStartCtrlZReader {
+            inner: inner,
+            last_was_newline: true,
+            terminated: false,
+        }
+    }
+
+    /// Returns whether reading has been terminated by a `0x1A` byte at the start of a line.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader does not update whether the next byte starts a
+    /// line.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `LineStartCtrlZReader`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for LineStartCtrlZReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.terminated || buf.is_empty() {
+            return Ok(0);
+        }
+        let n = try!(self.inner.read(buf));
+        for (i, &byte) in buf[..n].iter().enumerate() {
+            if byte == b'\x1a' && self.last_was_newline {
+                self.terminated = true;
+                return Ok(i);
+            }
+            self.last_was_newline = byte == b'\n';
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineStartCtrlZReader;
+    use std::cmp;
+    use std::io::Read;
+    use ClosureReader;
+
+    #[test]
+    fn mid_line_ctrl_z_is_data() {
+        let mut output = Vec::new();
+        let mut reader = LineStartCtrlZReader::new(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 7);
+        assert_eq!(output, b"foo\x1abar");
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn line_start_ctrl_z_terminates() {
+        let mut output = Vec::new();
+        let mut reader = LineStartCtrlZReader::new(b"foo\r\n\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 5);
+        assert_eq!(output, b"foo\r\n");
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn first_byte_ctrl_z_terminates() {
+        let mut output = Vec::new();
+        let mut reader = LineStartCtrlZReader::new(b"\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 0);
+        assert!(reader.is_terminated());
+    }
+
+    #[test]
+    fn consecutive_ctrl_z_after_data() {
+        let mut output = Vec::new();
+        let mut reader = LineStartCtrlZReader::new(b"foo\x1a\x1a\n\x1a" as &[u8]);
+
+        // The second `0x1A` byte follows another `0x1A` byte, not a newline.
+        assert_ok_eq!(reader.read_to_end(&mut output), 6);
+        assert_eq!(output, b"foo\x1a\x1a\n");
+    }
+
+    #[test]
+    fn newline_across_reads() {
+        let mut input = b"foo\n\x1abar" as &[u8];
+        let mut output = Vec::new();
+        // The inner reader returns a single byte per read.
+        let mut reader = LineStartCtrlZReader::new(ClosureReader(|buf: &mut [u8]| {
+            let len = cmp::min(buf.len(), 1);
+            input.read(&mut buf[..len])
+        }));
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"foo\n");
+    }
+
+    #[test]
+    fn read_after_termination() {
+        let mut reader = LineStartCtrlZReader::new(b"\x1afoo" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+}
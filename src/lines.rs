@@ -0,0 +1,201 @@
+use std::io::BufRead;
+use std::io::Result;
+use ReadToCtrlZ;
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Returns an iterator over the lines of a CP/M-style text file, ending at the `0x1A` byte.
+    ///
+    /// This is like [`lines()`](BufRead::lines()), except that a trailing `\r` is also removed
+    /// from the final line. CP/M text files end their lines with `\r\n`, but the final line is
+    /// often followed directly by the `0x1A` byte, possibly after a lone `\r`. Since `lines()`
+    /// only removes a `\r` preceding a `\n`, it would return such a final line with the `\r`
+    /// intact.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let lines = ReadToCtrlZ::new(&b"foo\r\nbar\r\x1a\x1a"[..])
+    ///     .ctrl_z_lines_cpm()
+    ///     .map(|line| line.unwrap())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lines, ["foo", "bar"]);
+    /// ```
+    pub fn ctrl_z_lines_cpm(self) -> CpmLines<R> {
+        CpmLines {
+            inner: self,
+            cr_stripped: 0,
+            lf_seen: 0,
+        }
+    }
+}
+
+/// An iterator over the lines of a CP/M-style text file, ending at the `0x1A` byte.
+///
+/// This is created using [`ReadToCtrlZ::ctrl_z_lines_cpm()`].
+///
+/// [`ReadToCtrlZ::ctrl_z_lines_cpm()`]: crate::ReadToCtrlZ::ctrl_z_lines_cpm()
+pub struct CpmLines<R> {
+    /// The reader the lines are read from.
+    inner: ReadToCtrlZ<R>,
+    /// The number of `\r` bytes removed from the ends of lines.
+    cr_stripped: u64,
+    /// The number of `\n` bytes ending lines.
+    lf_seen: u64,
+}
+
+impl<R> CpmLines<R> {
+    /// Returns the number of `\r` bytes removed from the ends of the lines returned so far.
+    ///
+    /// Together with [`lf_bytes_seen()`](CpmLines::lf_bytes_seen()), this can be used to check
+    /// that the input consistently used `\r\n` line endings. However, a bare `\r` byte ending the
+    /// final line, directly before the `0x1A` byte or the end of the input, is also counted, as it
+    /// is removed from that line. Equal counts therefore do not prove that every `\n` byte was
+    /// preceded by a `\r` byte.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut lines = ReadToCtrlZ::new(&b"foo\r\nbar\nbaz\r\x1a"[..]).ctrl_z_lines_cpm();
+    /// for line in lines.by_ref() {
+    ///     line.unwrap();
+    /// }
+    ///
+    /// // One `\r` byte preceded a `\n` byte, and the other ended the final line.
+    /// assert_eq!(lines.cr_bytes_stripped(), 2);
+    /// assert_eq!(lines.lf_bytes_seen(), 2);
+    /// ```
+    pub fn cr_bytes_stripped(&self) -> u64 {
+        self.cr_stripped
+    }
+
+    /// Returns the number of `\n` bytes ending the lines returned so far.
+    pub fn lf_bytes_seen(&self) -> u64 {
+        self.lf_seen
+    }
+
+    /// Unwraps this `CpmLines`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> Iterator for CpmLines<R>
+where
+    R: BufRead,
+{
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        let mut line = String::new();
+        match self.inner.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    self.lf_seen += 1;
+                }
+                if line.ends_with('\r') {
+                    line.pop();
+                    self.cr_stripped += 1;
+                }
+                Some(Ok(line))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn lines_final_line_before_ctrl_z() {
+        let lines = ReadToCtrlZ::new(b"foo\r\nbar\x1abaz" as &[u8])
+            .lines()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        // The final line ends at the `0x1A` byte rather than a newline, and is returned whole.
+        assert_eq!(lines, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn lines_keeps_lone_carriage_return_before_ctrl_z() {
+        let lines = ReadToCtrlZ::new(b"foo\r\nbar\r\x1a" as &[u8])
+            .lines()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "bar\r"]);
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm() {
+        let lines = ReadToCtrlZ::new(b"foo\r\n\r\nbar\r\nbaz\r\x1aqux\r\n" as &[u8])
+            .ctrl_z_lines_cpm()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "", "bar", "baz"]);
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm_newline_before_ctrl_z() {
+        let lines = ReadToCtrlZ::new(b"foo\r\nbar\r\n\x1a\x1a\x1a" as &[u8])
+            .ctrl_z_lines_cpm()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm_across_buffers() {
+        let inner = BufReader::with_capacity(3, b"foo\r\nbar\r\x1abaz" as &[u8]);
+        let lines = ReadToCtrlZ::new(inner)
+            .ctrl_z_lines_cpm()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm_invalid_utf8() {
+        let mut lines = ReadToCtrlZ::new(b"\xff\r\n\x1a" as &[u8]).ctrl_z_lines_cpm();
+
+        assert_err!(assert_some!(lines.next()));
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm_counts_line_endings() {
+        let mut lines =
+            ReadToCtrlZ::new(b"foo\r\nbar\r\n\r\nbaz\x1a\r\n" as &[u8]).ctrl_z_lines_cpm();
+
+        assert_ok_eq!(assert_some!(lines.next()), "foo");
+        assert_eq!(lines.cr_bytes_stripped(), 1);
+        assert_eq!(lines.lf_bytes_seen(), 1);
+
+        assert_eq!(lines.by_ref().count(), 3);
+        assert_eq!(lines.cr_bytes_stripped(), 3);
+        assert_eq!(lines.lf_bytes_seen(), 3);
+    }
+
+    #[test]
+    fn ctrl_z_lines_cpm_counts_unix_line_endings() {
+        let mut lines = ReadToCtrlZ::new(b"foo\nbar\n" as &[u8]).ctrl_z_lines_cpm();
+
+        assert_eq!(lines.by_ref().count(), 2);
+        assert_eq!(lines.cr_bytes_stripped(), 0);
+        assert_eq!(lines.lf_bytes_seen(), 2);
+    }
+}
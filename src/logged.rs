@@ -0,0 +1,307 @@
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// A reader to read until a `0x1A` byte, logging every read operation through the [`log`] crate.
+///
+/// A `TRACE`-level record is logged before and after each call to [`read()`](Read::read()) and
+/// [`fill_buf()`](BufRead::fill_buf()). The record before the call includes the number of bytes
+/// requested, while the record after it includes the number of bytes returned and whether the
+/// `0x1A` byte has been detected, or the error if one occurred.
+///
+/// This type is only available with the `log` feature enabled. It is created using
+/// [`ReadToCtrlZ::with_logging()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut reader = ReadToCtrlZ::with_logging(&b"foo\x1abar"[..]);
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// ```
+///
+/// [`log`]: https://docs.rs/log
+/// [`ReadToCtrlZ::with_logging()`]: crate::ReadToCtrlZ::with_logging()
+pub struct LoggingReadToCtrlZ<R> {
+    /// The internal reader being logged.
+    inner: ReadToCtrlZ<R>,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `LoggingReadToCtrlZ`, wrapping the provided reader.
+    ///
+    /// This method is only available with the `log` feature enabled.
+    pub fn with_logging(inner: R) -> LoggingReadToCtrlZ<R> {
+        LoggingReadToCtrlZ {
+            inner: ReadToCtrlZ::new(inner),
+        }
+    }
+}
+
+impl<R> LoggingReadToCtrlZ<R> {
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<R> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying `ReadToCtrlZ`.
+    pub fn get_mut(&mut self) -> &mut ReadToCtrlZ<R> {
+        &mut self.inner
+    }
+
+    /// Unwraps this `LoggingReadToCtrlZ`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> Read for LoggingReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        ::log::trace!("read: requested {} bytes", buf.len());
+        match self.inner.read(buf) {
+            Ok(n) => {
+                ::log::trace!(
+                    "read: returned {} bytes, 0x1A detected: {}",
+                    n,
+                    self.inner.is_terminated()
+                );
+                Ok(n)
+            }
+            Err(error) => {
+                ::log::trace!("read: error: {}", error);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<R> BufRead for LoggingReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        ::log::trace!("fill_buf: requested");
+        // The length is logged before returning, so the borrow of the buffer must end first.
+        let len = match self.inner.fill_buf() {
+            Ok(buf) => buf.len(),
+            Err(error) => {
+                ::log::trace!("fill_buf: error: {}", error);
+                return Err(error);
+            }
+        };
+        let terminators = self.inner.terminators;
+        let terminated = self.inner.is_terminated();
+        if len == 0 || self.inner.finished {
+            // At most the trailing newline remains. The raw bytes are not examined, since that
+            // could read past the `0x1A` byte.
+            ::log::trace!(
+                "fill_buf: returned {} bytes, 0x1A detected: {}",
+                len,
+                terminated
+            );
+            return if len == 0 {
+                Ok(&[])
+            } else {
+                self.inner.fill_buf()
+            };
+        }
+        // The returned bytes are a prefix of the raw bytes, which were already buffered above, so
+        // this neither reads from the inner reader nor scans the bytes again.
+        let raw = try!(self.inner.fill_raw());
+        let detected = raw
+            .get(len)
+            .map_or(false, |&byte| terminators.contains(byte));
+        ::log::trace!(
+            "fill_buf: returned {} bytes, 0x1A detected: {}",
+            len,
+            detected
+        );
+        Ok(&raw[..len])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+    use log::LevelFilter;
+    use log::Log;
+    use log::Metadata;
+    use log::Record;
+    use std::cell::RefCell;
+    use std::io::BufRead;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::sync::Once;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    thread_local! {
+        /// The messages logged on the current thread, so tests running in parallel do not see each
+        /// other's records.
+        static RECORDS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    }
+
+    /// A logger capturing every `TRACE`-level message into `RECORDS`.
+    struct CapturingLogger;
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() == Level::Trace
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                RECORDS.with(|records| records.borrow_mut().push(record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger;
+    static INIT: Once = Once::new();
+
+    /// Installs the capturing logger, and clears the messages captured on the current thread.
+    fn capture() {
+        INIT.call_once(|| {
+            assert_ok!(log::set_logger(&LOGGER));
+            log::set_max_level(LevelFilter::Trace);
+        });
+        RECORDS.with(|records| records.borrow_mut().clear());
+    }
+
+    /// Returns the messages captured on the current thread.
+    fn records() -> Vec<String> {
+        RECORDS.with(|records| records.borrow().clone())
+    }
+
+    #[test]
+    fn read_to_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.get_ref().is_terminated());
+    }
+
+    #[test]
+    fn read_to_eof() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(!reader.get_ref().is_terminated());
+    }
+
+    #[test]
+    fn fill_buf() {
+        let mut reader = ReadToCtrlZ::with_logging(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn fill_buf_logs_marker_mid_buffer() {
+        capture();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+
+        assert_eq!(
+            records(),
+            [
+                "fill_buf: requested",
+                "fill_buf: returned 3 bytes, 0x1A detected: true",
+                "fill_buf: requested",
+                "fill_buf: returned 0 bytes, 0x1A detected: true",
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_buf_logs_no_marker() {
+        capture();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo" as &[u8]);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+
+        assert_eq!(
+            records(),
+            [
+                "fill_buf: requested",
+                "fill_buf: returned 3 bytes, 0x1A detected: false",
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_buf_trailing_newline() {
+        capture();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo\x1a" as &[u8]);
+        *reader.get_mut() = ReadToCtrlZ::new(b"foo\x1a" as &[u8]).ensure_trailing_newline(true);
+        let mut output = Vec::new();
+
+        assert_ok_eq!(reader.read_until(b'\0', &mut output), 4);
+        assert_eq!(output, b"foo\n");
+        assert!(records().contains(&"fill_buf: returned 1 bytes, 0x1A detected: true".to_owned()));
+    }
+
+    #[test]
+    fn read_logs() {
+        capture();
+        let mut reader = ReadToCtrlZ::with_logging(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read(&mut [0; 8]), 3);
+
+        assert_eq!(
+            records(),
+            [
+                "read: requested 8 bytes",
+                "read: returned 3 bytes, 0x1A detected: true",
+            ]
+        );
+    }
+
+    #[test]
+    fn read_error_logs() {
+        capture();
+        let mut reader = ReadToCtrlZ::with_logging(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+
+        assert_err!(reader.read(&mut [0; 4]));
+
+        assert_eq!(records(), ["read: requested 4 bytes", "read: error: foo"]);
+    }
+
+    #[test]
+    fn read_error() {
+        let mut reader = ReadToCtrlZ::with_logging(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::Other
+        );
+    }
+}
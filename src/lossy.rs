@@ -0,0 +1,171 @@
+use std::io::BufRead;
+use std::io::Result;
+use ReadToCtrlZ;
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Reads bytes until a newline (the `0xA` byte), the `0x1A` byte, or the end of the inner
+    /// reader, appending them to `buf` with invalid UTF-8 replaced.
+    ///
+    /// Unlike [`read_line()`](BufRead::read_line()), this does not fail on invalid UTF-8: each
+    /// invalid sequence is replaced by `U+FFFD REPLACEMENT CHARACTER`, as by
+    /// [`String::from_utf8_lossy()`]. This allows processing legacy text in encodings such as
+    /// CP437 line by line. The newline is appended to `buf` if found.
+    ///
+    /// Returns the number of raw bytes read, which may differ from the number of bytes appended to
+    /// `buf`. Bytes following the `0x1A` byte are never read, so they do not affect the result.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"caf\xe9\nbar\x1a\xff"[..]);
+    /// let mut line = String::new();
+    ///
+    /// assert_eq!(reader.read_line_lossy(&mut line).unwrap(), 5);
+    /// assert_eq!(line, "caf\u{fffd}\n");
+    /// ```
+    pub fn read_line_lossy(&mut self, buf: &mut String) -> Result<usize> {
+        let mut bytes = Vec::new();
+        let n = try!(self.read_until(b'\n', &mut bytes));
+        buf.push_str(&String::from_utf8_lossy(&bytes));
+        Ok(n)
+    }
+
+    /// Returns an iterator over the lines of this reader, with invalid UTF-8 replaced.
+    ///
+    /// Each line is read using [`read_line_lossy()`](ReadToCtrlZ::read_line_lossy()). As with
+    /// [`lines()`](BufRead::lines()), the trailing newline, or `\r\n`, is removed from each line.
+    /// Iteration ends at the `0x1A` byte or the end of the inner reader.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let lines = ReadToCtrlZ::new(&b"foo\r\n\xffbar\x1abaz"[..])
+    ///     .lines_lossy()
+    ///     .map(|line| line.unwrap())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lines, ["foo", "\u{fffd}bar"]);
+    /// ```
+    pub fn lines_lossy(self) -> LinesLossy<R> {
+        LinesLossy { inner: self }
+    }
+}
+
+/// An iterator over the lines of a [`ReadToCtrlZ`], with invalid UTF-8 replaced.
+///
+/// This is created using [`ReadToCtrlZ::lines_lossy()`].
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+/// [`ReadToCtrlZ::lines_lossy()`]: crate::ReadToCtrlZ::lines_lossy()
+pub struct LinesLossy<R> {
+    /// The reader the lines are read from.
+    inner: ReadToCtrlZ<R>,
+}
+
+impl<R> LinesLossy<R> {
+    /// Unwraps this `LinesLossy`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> Iterator for LinesLossy<R>
+where
+    R: BufRead,
+{
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        let mut line = String::new();
+        match self.inner.read_line_lossy(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Some(Ok(line))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read_line_lossy_lone_ff() {
+        let mut line = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\xffbar\nbaz" as &[u8]);
+
+        assert_ok_eq!(reader.read_line_lossy(&mut line), 8);
+        assert_eq!(line, "foo\u{fffd}bar\n");
+    }
+
+    #[test]
+    fn read_line_lossy_appends() {
+        let mut line = String::from("foo");
+        let mut reader = ReadToCtrlZ::new(b"bar" as &[u8]);
+
+        assert_ok_eq!(reader.read_line_lossy(&mut line), 3);
+        assert_eq!(line, "foobar");
+    }
+
+    #[test]
+    fn read_line_lossy_stops_at_ctrl_z() {
+        let mut line = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\xff\xfe\n" as &[u8]);
+
+        assert_ok_eq!(reader.read_line_lossy(&mut line), 3);
+        assert_eq!(line, "foo");
+        assert_ok_eq!(reader.read_line_lossy(&mut line), 0);
+        assert_eq!(line, "foo");
+    }
+
+    #[test]
+    fn read_line_lossy_across_buffers() {
+        let mut line = String::new();
+        // The two-byte sequence for "é" is split across buffers.
+        let inner = BufReader::with_capacity(4, b"caf\xc3\xa9\n" as &[u8]);
+        let mut reader = ReadToCtrlZ::new(inner);
+
+        assert_ok_eq!(reader.read_line_lossy(&mut line), 6);
+        assert_eq!(line, "caf\u{e9}\n");
+    }
+
+    #[test]
+    fn lines_lossy() {
+        let lines = ReadToCtrlZ::new(b"foo\r\n\xff\nbar\n\nbaz\x1aqux\xff" as &[u8])
+            .lines_lossy()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "\u{fffd}", "bar", "", "baz"]);
+    }
+
+    #[test]
+    fn lines_lossy_matches_lines_for_valid_utf8() {
+        let input = b"foo\r\nbar\n\nbaz\x1aqux" as &[u8];
+
+        let lossy = ReadToCtrlZ::new(input)
+            .lines_lossy()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let lines = ReadToCtrlZ::new(input)
+            .lines()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(lossy, lines);
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::io::Write;
+
+/// A writer to write multiple documents, each terminated by a `0x1A` byte.
+///
+/// Each document is opened using [`start_file()`](MultiCtrlZWriter::start_file()) and closed using
+/// [`finish_file()`](MultiCtrlZWriter::finish_file()), which writes its `0x1A` byte. Writing while
+/// no document is open fails, as does finishing the writer using
+/// [`finish()`](MultiCtrlZWriter::finish()) while a document is still open, so a missing `0x1A`
+/// byte cannot go unnoticed. The resulting output can be read back using
+/// [`ReadToCtrlZ::segments()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::MultiCtrlZWriter;
+/// use std::io::Write;
+///
+/// let mut writer = MultiCtrlZWriter::new(Vec::new());
+///
+/// assert!(writer.start_file().is_ok());
+/// assert!(writer.write_all(b"foo").is_ok());
+/// assert!(writer.finish_file().is_ok());
+/// assert!(writer.start_file().is_ok());
+/// assert!(writer.write_all(b"bar").is_ok());
+/// assert!(writer.finish_file().is_ok());
+///
+/// assert_eq!(writer.finish().unwrap(), b"foo\x1abar\x1a");
+/// ```
+///
+/// [`ReadToCtrlZ::segments()`]: crate::ReadToCtrlZ::segments()
+pub struct MultiCtrlZWriter<W> {
+    /// The internal writer being written to.
+    inner: W,
+    /// Whether or not a document is currently open.
+    open: bool,
+    /// The number of documents finished so far.
+    files: u64,
+}
+
+impl<W> MultiCtrlZWriter<W> {
+    /// Creates a new `MultiCtrlZWriter`, wrapping the provided writer.
+    ///
+    /// No document is open initially.
+    pub fn new(inner: W) -> Self {
+        MultiCtrlZWriter {
+            inner: inner,
+            open: false,
+            files: 0,
+        }
+    }
+
+    /// Opens a new document.
+    ///
+    /// Nothing is written to the inner writer.
+    ///
+    /// # Errors
+    /// Returns an error of kind [`InvalidInput`](ErrorKind::InvalidInput) if a document is already
+    /// open.
+    pub fn start_file(&mut self) -> Result<()> {
+        if self.open {
+            return Err(Error::new(ErrorKind::InvalidInput, "file already open"));
+        }
+        self.open = true;
+        Ok(())
+    }
+
+    /// Returns whether a document is currently open.
+    pub fn is_file_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns the number of documents finished so far.
+    pub fn files(&self) -> u64 {
+        self.files
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W> MultiCtrlZWriter<W>
+where
+    W: Write,
+{
+    /// Closes the open document by writing its `0x1A` byte.
+    ///
+    /// # Errors
+    /// Returns an error of kind [`InvalidInput`](ErrorKind::InvalidInput) if no document is open.
+    /// If writing the `0x1A` byte fails, the document remains open, so this can be retried.
+    pub fn finish_file(&mut self) -> Result<()> {
+        if !self.open {
+            return Err(Error::new(ErrorKind::InvalidInput, "no file open"));
+        }
+        try!(self.inner.write_all(b"\x1a"));
+        self.open = false;
+        self.files += 1;
+        Ok(())
+    }
+
+    /// Flushes the inner writer, returning it.
+    ///
+    /// # Errors
+    /// Returns an error of kind [`InvalidInput`](ErrorKind::InvalidInput) if a document is still
+    /// open, or any error that occurs while flushing.
+    pub fn finish(mut self) -> Result<W> {
+        if self.open {
+            return Err(Error::new(ErrorKind::InvalidInput, "file still open"));
+        }
+        try!(self.inner.flush());
+        Ok(self.inner)
+    }
+}
+
+impl<W> Write for MultiCtrlZWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if !self.open {
+            return Err(Error::new(ErrorKind::InvalidInput, "no file open"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiCtrlZWriter;
+    use std::io::ErrorKind;
+    use std::io::Write;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn three_documents() {
+        let mut writer = MultiCtrlZWriter::new(Vec::new());
+        for document in [&b"foo"[..], b"", b"bar\r\nbaz"].iter() {
+            assert_ok!(writer.start_file());
+            assert_ok!(writer.write_all(document));
+            assert_ok!(writer.finish_file());
+        }
+        assert_eq!(writer.files(), 3);
+        let archive = assert_ok!(writer.finish());
+
+        assert_eq!(archive, b"foo\x1a\x1abar\r\nbaz\x1a");
+        let segments = ReadToCtrlZ::new(&archive[..])
+            .segments()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(segments, [&b"foo"[..], b"", b"bar\r\nbaz"]);
+    }
+
+    #[test]
+    fn write_without_open_file() {
+        let mut writer = MultiCtrlZWriter::new(Vec::new());
+
+        assert_eq!(
+            assert_err!(writer.write(b"foo")).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(writer.get_ref(), b"");
+    }
+
+    #[test]
+    fn write_after_finish_file() {
+        let mut writer = MultiCtrlZWriter::new(Vec::new());
+        assert_ok!(writer.start_file());
+        assert_ok!(writer.finish_file());
+
+        assert_eq!(
+            assert_err!(writer.write(b"foo")).kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn start_file_twice() {
+        let mut writer = MultiCtrlZWriter::new(Vec::<u8>::new());
+        assert_ok!(writer.start_file());
+
+        assert_eq!(
+            assert_err!(writer.start_file()).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(writer.is_file_open());
+    }
+
+    #[test]
+    fn finish_file_without_open_file() {
+        let mut writer = MultiCtrlZWriter::new(Vec::new());
+
+        assert_eq!(
+            assert_err!(writer.finish_file()).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(writer.files(), 0);
+    }
+
+    #[test]
+    fn finish_with_open_file() {
+        let mut writer = MultiCtrlZWriter::new(Vec::new());
+        assert_ok!(writer.start_file());
+        assert_ok!(writer.write_all(b"foo"));
+
+        assert_eq!(assert_err!(writer.finish()).kind(), ErrorKind::InvalidInput);
+    }
+}
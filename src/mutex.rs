@@ -0,0 +1,111 @@
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A reader that reads from a reader shared through an `Arc<Mutex<R>>`.
+///
+/// [`Read`] cannot be implemented for a [`ReadToCtrlZ`] wrapping an `Arc<Mutex<R>>` directly, as
+/// it would conflict with the existing implementation for all readers. Wrapping the shared reader
+/// in this adapter first allows it to be read by a `ReadToCtrlZ`, while other threads keep access
+/// to it between reads. For [`BufRead`](std::io::BufRead), wrap it in a
+/// [`BufReadToCtrlZ`](crate::BufReadToCtrlZ) instead.
+///
+/// Each read locks the mutex for the duration of the read. If the mutex is poisoned, an error of
+/// kind [`Other`](ErrorKind::Other) is returned rather than panicking.
+///
+/// Note that only the inner reader is shared: the state of the `ReadToCtrlZ`, such as whether the
+/// `0x1A` byte has been reached, belongs to that `ReadToCtrlZ` alone. Since reading requires a
+/// mutable reference to it, that state can never be observed mid-update by another thread.
+///
+/// # Example
+/// ```
+/// use ctrl_z::MutexReader;
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+/// use std::sync::Arc;
+/// use std::sync::Mutex;
+///
+/// let shared = Arc::new(Mutex::new(&b"foo\x1abar"[..]));
+/// let mut reader = ReadToCtrlZ::new(MutexReader(shared.clone()));
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct MutexReader<R>(pub Arc<Mutex<R>>);
+
+impl<R> Read for MutexReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.0.lock() {
+            Ok(mut inner) => inner.read(buf),
+            Err(_) => Err(Error::new(ErrorKind::Other, "reader mutex poisoned")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MutexReader;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::thread;
+    use BufReadToCtrlZ;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read() {
+        let shared = Arc::new(Mutex::new(b"foo\x1abar" as &[u8]));
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(MutexReader(shared.clone())).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+        assert_eq!(*assert_ok!(shared.lock()), b"");
+    }
+
+    #[test]
+    fn read_from_other_thread() {
+        let shared = Arc::new(Mutex::new(b"foo\x1abar" as &[u8]));
+        let reader = MutexReader(shared.clone());
+
+        let output = assert_ok!(thread::spawn(move || {
+            let mut output = String::new();
+            BufReadToCtrlZ::new(reader)
+                .read_to_string(&mut output)
+                .unwrap();
+            output
+        })
+        .join());
+
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn poisoned() {
+        let shared = Arc::new(Mutex::new(b"foo" as &[u8]));
+        let poisoner = shared.clone();
+        assert_err!(thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poisoning the mutex");
+        })
+        .join());
+        let mut reader = ReadToCtrlZ::new(MutexReader(shared));
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::Other
+        );
+    }
+}
@@ -0,0 +1,332 @@
+// The `nom` crate requires a newer Rust version, so the MSRV does not apply here.
+#![allow(clippy::incompatible_msrv)]
+
+use nom::error::ErrorKind;
+use nom::error::ParseError;
+use nom::AsBytes;
+use nom::Compare;
+use nom::CompareResult;
+use nom::FindSubstring;
+use nom::IResult;
+use nom::Input;
+use nom::Needed;
+use nom::Offset;
+use std::io::Cursor;
+use std::iter::Copied;
+use std::iter::Enumerate;
+use std::slice::Iter;
+use ReadToCtrlZ;
+
+/// A [`nom`] input over bytes preceding a `0x1A` byte.
+///
+/// This wraps a byte slice truncated at its first terminating byte, so every `nom` operation on
+/// it, such as taking, splitting, and comparing, is bounded by the `0x1A` byte. A parser that
+/// would need to consume beyond it fails as if the input ended there. It implements [`Input`],
+/// [`Compare`], [`FindSubstring`], [`Offset`], and [`AsBytes`], which is enough to drive the
+/// parsers in `nom::bytes`, `nom::character`, and `nom::number`.
+///
+/// This type is only available with the `nom` feature enabled, which requires Rust 1.65 or later.
+/// It is created using [`CtrlZInput::new()`] or [`ReadToCtrlZ::nom_input()`].
+///
+/// # Example
+/// ```
+/// # extern crate ctrl_z;
+/// # extern crate nom;
+/// use ctrl_z::CtrlZInput;
+/// use nom::bytes::complete::tag;
+/// use nom::bytes::complete::take_while;
+/// use nom::IResult;
+///
+/// fn word(input: CtrlZInput) -> IResult<CtrlZInput, CtrlZInput> {
+///     take_while(|byte: u8| byte.is_ascii_alphabetic())(input)
+/// }
+///
+/// fn space(input: CtrlZInput) -> IResult<CtrlZInput, CtrlZInput> {
+///     tag(" ")(input)
+/// }
+///
+/// # fn main() {
+/// let input = CtrlZInput::new(b"foo bar\x1abaz");
+///
+/// let (input, first) = word(input).unwrap();
+/// let (input, _) = space(input).unwrap();
+/// let (input, second) = word(input).unwrap();
+///
+/// assert_eq!(first.as_bytes(), b"foo");
+/// assert_eq!(second.as_bytes(), b"bar");
+/// // "baz" follows the `0x1A` byte, so it is not part of the input.
+/// assert!(input.as_bytes().is_empty());
+/// # }
+/// ```
+///
+/// [`nom`]: https://docs.rs/nom
+/// [`Input`]: nom::Input
+/// [`Compare`]: nom::Compare
+/// [`FindSubstring`]: nom::FindSubstring
+/// [`Offset`]: nom::Offset
+/// [`AsBytes`]: nom::AsBytes
+/// [`ReadToCtrlZ::nom_input()`]: crate::ReadToCtrlZ::nom_input()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CtrlZInput<'a> {
+    /// The bytes preceding the `0x1A` byte.
+    bytes: &'a [u8],
+}
+
+impl<'a> CtrlZInput<'a> {
+    /// Creates a new `CtrlZInput` over the bytes of `bytes` preceding its first `0x1A` byte.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let len = bytes
+            .iter()
+            .position(|&byte| byte == b'\x1a')
+            .unwrap_or(bytes.len());
+        CtrlZInput {
+            bytes: &bytes[..len],
+        }
+    }
+
+    /// Returns the bytes of this input.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a> ReadToCtrlZ<Cursor<&'a [u8]>> {
+    /// Returns a [`CtrlZInput`] over the bytes that remain to be delivered by this reader.
+    ///
+    /// The input starts at the first byte not yet delivered and ends at the next terminating
+    /// byte, so `nom` parsers can be run directly on the in-memory bytes without copying them.
+    /// This reader is left unchanged. If reading has already been terminated, the input is empty.
+    ///
+    /// Only the configured [terminating bytes](ReadToCtrlZ::terminate_on()) are considered:
+    /// [lenient](ReadToCtrlZ::lenient()) and [quote-aware](ReadToCtrlZ::quote_aware()) modes are
+    /// not.
+    ///
+    /// This method is only available with the `nom` feature enabled.
+    ///
+    /// [`CtrlZInput`]: crate::CtrlZInput
+    pub fn nom_input(&self) -> CtrlZInput<'a> {
+        if self.terminated || self.exceeded {
+            return CtrlZInput { bytes: &[] };
+        }
+        let cursor = &self.inner;
+        let bytes: &'a [u8] = cursor.get_ref();
+        // Bytes read ahead precede the cursor's position, but have not been delivered.
+        let pending = self.buffer.len() - self.pos;
+        let position = cursor.position().min(bytes.len() as u64) as usize;
+        let rest = &bytes[position - pending..];
+        let terminators = self.terminators;
+        let len = rest
+            .iter()
+            .position(|&byte| terminators.contains(byte))
+            .unwrap_or(rest.len());
+        CtrlZInput {
+            bytes: &rest[..len],
+        }
+    }
+}
+
+impl<'a> Input for CtrlZInput<'a> {
+    type Item = u8;
+    type Iter = Copied<Iter<'a, u8>>;
+    type IterIndices = Enumerate<Copied<Iter<'a, u8>>>;
+
+    fn input_len(&self) -> usize {
+        self.bytes.input_len()
+    }
+
+    fn take(&self, index: usize) -> Self {
+        CtrlZInput {
+            bytes: self.bytes.take(index),
+        }
+    }
+
+    fn take_from(&self, index: usize) -> Self {
+        CtrlZInput {
+            bytes: self.bytes.take_from(index),
+        }
+    }
+
+    fn take_split(&self, index: usize) -> (Self, Self) {
+        let (suffix, prefix) = self.bytes.take_split(index);
+        (CtrlZInput { bytes: suffix }, CtrlZInput { bytes: prefix })
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(u8) -> bool,
+    {
+        self.bytes.position(predicate)
+    }
+
+    fn iter_elements(&self) -> Self::Iter {
+        self.bytes.iter_elements()
+    }
+
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.bytes.iter_indices()
+    }
+
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        self.bytes.slice_index(count)
+    }
+
+    fn split_at_position_complete<P, E>(&self, predicate: P) -> IResult<Self, Self, E>
+    where
+        P: Fn(u8) -> bool,
+        E: ParseError<Self>,
+    {
+        let index = self.position(predicate).unwrap_or(self.bytes.len());
+        Ok(self.take_split(index))
+    }
+
+    fn split_at_position1_complete<P, E>(
+        &self,
+        predicate: P,
+        kind: ErrorKind,
+    ) -> IResult<Self, Self, E>
+    where
+        P: Fn(u8) -> bool,
+        E: ParseError<Self>,
+    {
+        match self.position(predicate).unwrap_or(self.bytes.len()) {
+            0 => Err(nom::Err::Error(E::from_error_kind(*self, kind))),
+            index => Ok(self.take_split(index)),
+        }
+    }
+}
+
+impl<'a, 'b> Compare<&'b [u8]> for CtrlZInput<'a> {
+    fn compare(&self, other: &'b [u8]) -> CompareResult {
+        self.bytes.compare(other)
+    }
+
+    fn compare_no_case(&self, other: &'b [u8]) -> CompareResult {
+        self.bytes.compare_no_case(other)
+    }
+}
+
+impl<'a, 'b> Compare<&'b str> for CtrlZInput<'a> {
+    fn compare(&self, other: &'b str) -> CompareResult {
+        self.bytes.compare(other)
+    }
+
+    fn compare_no_case(&self, other: &'b str) -> CompareResult {
+        self.bytes.compare_no_case(other)
+    }
+}
+
+impl<'a, 'b> FindSubstring<&'b [u8]> for CtrlZInput<'a> {
+    fn find_substring(&self, substr: &'b [u8]) -> Option<usize> {
+        self.bytes.find_substring(substr)
+    }
+}
+
+impl<'a, 'b> FindSubstring<&'b str> for CtrlZInput<'a> {
+    fn find_substring(&self, substr: &'b str) -> Option<usize> {
+        self.bytes.find_substring(substr)
+    }
+}
+
+impl<'a> Offset for CtrlZInput<'a> {
+    fn offset(&self, second: &Self) -> usize {
+        self.bytes.offset(second.bytes)
+    }
+}
+
+impl<'a> AsBytes for CtrlZInput<'a> {
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CtrlZInput;
+    use nom::bytes::complete::tag;
+    use nom::bytes::complete::take;
+    use nom::bytes::complete::take_until;
+    use nom::character::complete::alpha1;
+    use nom::character::complete::digit1;
+    use nom::error::Error;
+    use nom::IResult;
+    use std::io::Cursor;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn new_truncates() {
+        assert_eq!(CtrlZInput::new(b"foo\x1abar").as_bytes(), b"foo");
+        assert_eq!(CtrlZInput::new(b"foo").as_bytes(), b"foo");
+        assert_eq!(CtrlZInput::new(b"\x1afoo").as_bytes(), b"");
+    }
+
+    #[test]
+    fn tag_bounded_by_ctrl_z() {
+        let input = CtrlZInput::new(b"foo\x1abar");
+
+        let result: IResult<CtrlZInput, CtrlZInput> = tag("foo")(input);
+        let (rest, matched) = assert_ok!(result);
+        assert_eq!(matched.as_bytes(), b"foo");
+        assert_eq!(rest.as_bytes(), b"");
+
+        // The bytes following the `0x1A` byte can never be matched.
+        let result: IResult<CtrlZInput, CtrlZInput> = tag(&b"foo\x1abar"[..])(input);
+        assert_err!(result);
+    }
+
+    #[test]
+    fn take_bounded_by_ctrl_z() {
+        let input = CtrlZInput::new(b"foo\x1abar");
+
+        let result: IResult<CtrlZInput, CtrlZInput> = take(3usize)(input);
+        assert_ok!(result);
+        let result: IResult<CtrlZInput, CtrlZInput> = take(4usize)(input);
+        assert_err!(result);
+    }
+
+    #[test]
+    fn character_parsers() {
+        let input = CtrlZInput::new(b"abc123\x1a456");
+
+        let (input, letters) = assert_ok!(alpha1::<_, Error<_>>(input));
+        let (input, digits) = assert_ok!(digit1::<_, Error<_>>(input));
+
+        assert_eq!(letters.as_bytes(), b"abc");
+        // The digits following the `0x1A` byte are not included.
+        assert_eq!(digits.as_bytes(), b"123");
+        assert_eq!(input.as_bytes(), b"");
+    }
+
+    #[test]
+    fn take_until_missing() {
+        let input = CtrlZInput::new(b"foo\x1a;");
+
+        let result: IResult<CtrlZInput, CtrlZInput> = take_until(";")(input);
+        assert_err!(result);
+    }
+
+    #[test]
+    fn nom_input_from_reader() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz" as &[u8]));
+        assert_ok_eq!(reader.read(&mut [0; 3]), 3);
+
+        assert_eq!(reader.nom_input().as_bytes(), b"bar");
+    }
+
+    #[test]
+    fn nom_input_with_read_ahead() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foobar\x1abaz" as &[u8]));
+        assert_ok_eq!(reader.peek_n(4), b"foob");
+
+        assert_eq!(reader.nom_input().as_bytes(), b"foobar");
+    }
+
+    #[test]
+    fn nom_input_terminated() {
+        let mut reader = ReadToCtrlZ::new(Cursor::new(b"foo\x1abar" as &[u8]));
+        let mut output = Vec::new();
+        assert_ok!(reader.read_to_end(&mut output));
+
+        assert_eq!(reader.nom_input().as_bytes(), b"");
+    }
+}
@@ -0,0 +1,180 @@
+use std::io::Read;
+use std::io::Result;
+
+/// A reader to read until a `0x1A` byte at one of a set of offsets.
+///
+/// Some binary formats only treat `0x1A` as the end of the file at specific offsets, such as
+/// directly after a fixed-size header. A `0x1A` byte at any other offset is read as a regular
+/// byte. Offsets are counted from the first byte read from the inner reader, starting at `0`.
+///
+/// As with [`ReadToCtrlZ`], bytes following the terminating `0x1A` byte within the same read of
+/// the inner reader are discarded.
+///
+/// # Example
+/// ```
+/// use ctrl_z::OffsetBoundReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut reader = OffsetBoundReadToCtrlZ::new(&b"\x1afoo\x1abar"[..], vec![4]);
+/// let mut output = Vec::new();
+///
+/// // Only the `0x1A` byte at offset 4 terminates.
+/// assert!(reader.read_to_end(&mut output).is_ok());
+/// assert_eq!(output, b"\x1afoo");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct OffsetBoundReadToCtrlZ<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// The offsets at which a `0x1A` byte terminates reading, sorted and without duplicates.
+    positions: Vec<u64>,
+    /// The offset of the next byte to be read.
+    offset: u64,
+    /// Whether or not a `0x1A` byte at one of the offsets has been reached.
+    terminated: bool,
+}
+
+impl<R> OffsetBoundReadToCtrlZ<R> {
+    /// Creates a new `OffsetBoundReadToCtrlZ`, wrapping the provided reader and terminating only
+    /// on a `0x1A` byte at one of `positions`.
+    ///
+    /// `positions` may be in any order and may contain duplicates.
+    pub fn new(inner: R, mut positions: Vec<u64>) -> Self {
+        positions.sort();
+        positions.dedup();
+        OffsetBoundReadToCtrlZ {
+            inner: inner,
+            positions: positions,
+            offset: 0,
+            terminated: false,
+        }
+    }
+
+    /// Returns the offsets at which a `0x1A` byte terminates reading, in ascending order.
+    pub fn positions(&self) -> &[u64] {
+        &self.positions
+    }
+
+    /// Returns the offset of the next byte to be read.
+    ///
+    /// Once reading has been terminated, this is the offset of the terminating `0x1A` byte.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns whether reading has been terminated by a `0x1A` byte at one of the offsets.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader does not advance the offset.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `OffsetBoundReadToCtrlZ`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for OffsetBoundReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.terminated || buf.is_empty() {
+            return Ok(0);
+        }
+        let n = try!(self.inner.read(buf));
+        for (i, &byte) in buf[..n].iter().enumerate() {
+            let offset = self.offset + i as u64;
+            if byte == b'\x1a' && self.positions.binary_search(&offset).is_ok() {
+                self.terminated = true;
+                self.offset = offset;
+                return Ok(i);
+            }
+        }
+        self.offset += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OffsetBoundReadToCtrlZ;
+    use std::cmp;
+    use std::io::Read;
+    use ClosureReader;
+
+    #[test]
+    fn ctrl_z_at_position() {
+        let mut output = Vec::new();
+        let mut reader = OffsetBoundReadToCtrlZ::new(b"foo\x1abar" as &[u8], vec![3]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 3);
+        assert_eq!(output, b"foo");
+        assert!(reader.is_terminated());
+        assert_eq!(reader.offset(), 3);
+    }
+
+    #[test]
+    fn ctrl_z_elsewhere_is_data() {
+        let mut output = Vec::new();
+        let mut reader = OffsetBoundReadToCtrlZ::new(b"foo\x1abar" as &[u8], vec![2, 4]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 7);
+        assert_eq!(output, b"foo\x1abar");
+        assert!(!reader.is_terminated());
+        assert_eq!(reader.offset(), 7);
+    }
+
+    #[test]
+    fn no_positions() {
+        let mut output = Vec::new();
+        let mut reader = OffsetBoundReadToCtrlZ::new(b"\x1afoo\x1a" as &[u8], Vec::new());
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 5);
+        assert!(!reader.is_terminated());
+    }
+
+    #[test]
+    fn positions_sorted() {
+        let reader = OffsetBoundReadToCtrlZ::new(b"" as &[u8], vec![8, 2, 8, 4]);
+
+        assert_eq!(reader.positions(), [2, 4, 8]);
+    }
+
+    #[test]
+    fn offset_across_reads() {
+        let mut input = b"\x1aa\x1ab\x1ac" as &[u8];
+        let mut output = Vec::new();
+        // The inner reader returns a single byte per read.
+        let mut reader = OffsetBoundReadToCtrlZ::new(
+            ClosureReader(|buf: &mut [u8]| {
+                let len = cmp::min(buf.len(), 1);
+                input.read(&mut buf[..len])
+            }),
+            vec![1, 4],
+        );
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert_eq!(output, b"\x1aa\x1ab");
+    }
+
+    #[test]
+    fn read_after_termination() {
+        let mut reader = OffsetBoundReadToCtrlZ::new(b"\x1afoo" as &[u8], vec![0]);
+
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+        assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+    }
+}
@@ -0,0 +1,140 @@
+//! Re-exports of all public types, traits, and functions.
+//!
+//! Glob-importing this module brings the whole crate into scope with a single `use` statement:
+//!
+//! ```
+//! use ctrl_z::prelude::*;
+//! use std::io::Read;
+//!
+//! let mut reader = ReadToCtrlZ::new(&b"foo\x1abar"[..]);
+//! let mut output = String::new();
+//!
+//! assert!(reader.read_to_string(&mut output).is_ok());
+//! assert_eq!(output, "foo");
+//! assert_eq!(find_ctrl_z(b"foo\x1abar"), Some(3));
+//! ```
+//!
+//! This includes the types returned by methods of [`ReadToCtrlZ`], such as [`UntilOutcome`] and
+//! [`MarkerNotFound`]:
+//!
+//! ```
+//! use ctrl_z::prelude::*;
+//!
+//! let mut reader = ReadToCtrlZ::new(&b"foo,bar\x1abaz"[..]);
+//! let mut record = Vec::new();
+//!
+//! let outcome = reader.read_until_or_marker(b',', &mut record).unwrap();
+//! assert_eq!(outcome, UntilOutcome::Delimiter);
+//!
+//! let error = reader.collect_until_ctrl_z(2).unwrap_err();
+//! let not_found = error.get_ref().unwrap().downcast_ref::<MarkerNotFound>().unwrap();
+//! assert_eq!(not_found.limit(), 2);
+//! ```
+//!
+//! [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+//! [`UntilOutcome`]: crate::UntilOutcome
+//! [`MarkerNotFound`]: crate::MarkerNotFound
+
+pub use classify;
+pub use classify_reader;
+pub use classify_reader_cancellable;
+pub use count_ctrl_z;
+pub use ensure_terminated;
+pub use ensure_terminated_with_options;
+pub use eq_ignoring_ctrl_z;
+pub use eq_ignoring_ctrl_z_cancellable;
+pub use files_eq_ignoring_ctrl_z;
+pub use files_eq_ignoring_ctrl_z_cancellable;
+pub use find_ctrl_z;
+#[cfg(feature = "json")]
+pub use json_from_ctrl_z_reader;
+#[cfg(feature = "rayon")]
+pub use par_find_ctrl_z;
+#[cfg(feature = "rayon")]
+pub use par_scan;
+pub use read_ctrl_z_file;
+pub use read_ctrl_z_file_to_string;
+pub use read_ctrl_z_from_stdin;
+pub use scan;
+pub use scan_resumable;
+#[cfg(feature = "strip")]
+pub use strip_files;
+#[cfg(feature = "strip")]
+pub use strip_files_with_progress;
+#[cfg(feature = "strip")]
+pub use strip_in_place;
+pub use AsInMemory;
+#[cfg(feature = "strip")]
+pub use BatchEntry;
+#[cfg(feature = "strip")]
+pub use BatchReport;
+pub use BufReadToCtrlZ;
+pub use CancelToken;
+pub use Cancelled;
+#[cfg(feature = "crossbeam")]
+pub use ChannelReader;
+pub use CharsError;
+pub use Checkpoint;
+pub use Classification;
+pub use ClosureReader;
+pub use CountingCtrlZSink;
+pub use CpmLines;
+pub use CpmRecords;
+#[cfg(feature = "stream")]
+pub use CtrlZByteStream;
+pub use CtrlZChars;
+pub use CtrlZChunks;
+pub use CtrlZCursor;
+#[cfg(feature = "nom")]
+pub use CtrlZInput;
+pub use CtrlZReadAt;
+pub use DbaseMemoReader;
+pub use EnsureOptions;
+pub use EnsureOutcome;
+pub use IntoInnerError;
+#[cfg(feature = "json")]
+pub use JsonCtrlZError;
+pub use KnownLen;
+pub use LimitedReadToCtrlZ;
+pub use LineStartCtrlZReader;
+pub use LinesLossy;
+#[cfg(feature = "log")]
+pub use LoggingReadToCtrlZ;
+pub use LogicalBytes;
+pub use MarkerNotFound;
+pub use MultiCtrlZWriter;
+pub use MutexReader;
+pub use OffsetBoundReadToCtrlZ;
+pub use Progress;
+pub use ProgressReadToCtrlZ;
+#[cfg(feature = "rc")]
+pub use RcReader;
+pub use ReadAt;
+pub use ReadError;
+pub use ReadToCtrlZ;
+pub use Record;
+pub use SafeBufReader;
+pub use ScanReport;
+pub use ScanState;
+pub use SegmentOffsets;
+pub use SegmentRef;
+pub use SegmentingWriter;
+pub use Segments;
+pub use SharedReadToCtrlZ;
+pub use SplitReadWriteToCtrlZ;
+pub use StrictReadToCtrlZ;
+#[cfg(feature = "strip")]
+pub use StripOptions;
+#[cfg(feature = "strip")]
+pub use StripOutcome;
+pub use TerminatorSet;
+pub use TextProfile;
+#[cfg(all(unix, feature = "timeout"))]
+pub use TimeoutReadToCtrlZ;
+#[cfg(feature = "tracing")]
+pub use TracedReadToCtrlZ;
+pub use Transparent;
+pub use UntilOutcome;
+pub use Verdict;
+pub use Violation;
+pub use WriteToCtrlZ;
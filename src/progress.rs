@@ -0,0 +1,331 @@
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// A snapshot of the progress of a [`ProgressReadToCtrlZ`].
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the fields
+/// `bytes_delivered`, `inner_bytes_read`, and `done`.
+///
+/// [`ProgressReadToCtrlZ`]: crate::ProgressReadToCtrlZ
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Progress {
+    /// The number of bytes delivered so far.
+    bytes_delivered: u64,
+    /// The number of bytes read from the inner reader so far.
+    inner_bytes_read: u64,
+    /// Whether or not reading has finished.
+    done: bool,
+}
+
+impl Progress {
+    /// Returns the cumulative number of bytes delivered, excluding the terminating byte.
+    pub fn bytes_delivered(&self) -> u64 {
+        self.bytes_delivered
+    }
+
+    /// Returns the cumulative number of bytes read from the inner reader.
+    ///
+    /// See [`ReadToCtrlZ::inner_bytes_read()`] for exactly which bytes are counted.
+    ///
+    /// [`ReadToCtrlZ::inner_bytes_read()`]: crate::ReadToCtrlZ::inner_bytes_read()
+    pub fn inner_bytes_read(&self) -> u64 {
+        self.inner_bytes_read
+    }
+
+    /// Returns whether reading has finished, by either the `0x1A` byte or the end of the inner
+    /// reader.
+    ///
+    /// This is only ever `true` for the final notification.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+/// A reader to read until a `0x1A` byte, reporting its [`Progress`] to a callback.
+///
+/// The callback is called at most once for each chunk delivered: after each [`read()`] returning
+/// bytes, and after each [`consume()`] of a nonzero amount. Once reading has finished, the
+/// callback is called one final time with [`is_done()`] returning `true`, and is never called
+/// again. When reading through [`Read`], the final notification may accompany the last chunk.
+///
+/// This type is created using [`ReadToCtrlZ::on_progress()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut delivered = 0;
+/// let mut output = String::new();
+/// {
+///     let mut reader = ReadToCtrlZ::new(&b"foo\x1abar"[..])
+///         .on_progress(|progress| delivered = progress.bytes_delivered());
+///
+///     assert!(reader.read_to_string(&mut output).is_ok());
+/// }
+///
+/// assert_eq!(delivered, 3);
+/// ```
+///
+/// [`Progress`]: crate::Progress
+/// [`read()`]: Read::read()
+/// [`consume()`]: BufRead::consume()
+/// [`is_done()`]: crate::Progress::is_done()
+/// [`ReadToCtrlZ::on_progress()`]: crate::ReadToCtrlZ::on_progress()
+pub struct ProgressReadToCtrlZ<R, F> {
+    /// The internal reader being observed.
+    inner: ReadToCtrlZ<R>,
+    /// The callback receiving progress notifications.
+    callback: F,
+    /// Whether or not the final notification has been sent.
+    finished: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `ProgressReadToCtrlZ`, calling `callback` with the reader's [`Progress`] as
+    /// chunks are delivered.
+    ///
+    /// See [`ProgressReadToCtrlZ`] for when the callback is called.
+    ///
+    /// [`Progress`]: crate::Progress
+    /// [`ProgressReadToCtrlZ`]: crate::ProgressReadToCtrlZ
+    pub fn on_progress<F>(self, callback: F) -> ProgressReadToCtrlZ<R, F>
+    where
+        F: FnMut(Progress),
+    {
+        ProgressReadToCtrlZ {
+            inner: self,
+            callback: callback,
+            finished: false,
+        }
+    }
+}
+
+impl<R, F> ProgressReadToCtrlZ<R, F> {
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<R> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying `ReadToCtrlZ`.
+    pub fn get_mut(&mut self) -> &mut ReadToCtrlZ<R> {
+        &mut self.inner
+    }
+
+    /// Unwraps this `ProgressReadToCtrlZ`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R, F> ProgressReadToCtrlZ<R, F>
+where
+    F: FnMut(Progress),
+{
+    /// Calls the callback with the current progress, unless the final notification has been sent.
+    fn notify(&mut self, done: bool) {
+        if self.finished {
+            return;
+        }
+        self.finished = done;
+        (self.callback)(Progress {
+            bytes_delivered: self.inner.bytes_delivered(),
+            inner_bytes_read: self.inner.inner_bytes_read(),
+            done: done,
+        });
+    }
+}
+
+impl<R, F> Read for ProgressReadToCtrlZ<R, F>
+where
+    R: Read,
+    F: FnMut(Progress),
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = try!(self.inner.read(buf));
+        if n > 0 {
+            let done = self.inner.terminator().is_some();
+            self.notify(done);
+        } else if !buf.is_empty() {
+            self.notify(true);
+        }
+        Ok(n)
+    }
+}
+
+impl<R, F> BufRead for ProgressReadToCtrlZ<R, F>
+where
+    R: BufRead,
+    F: FnMut(Progress),
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if try!(self.inner.fill_buf()).is_empty() {
+            self.notify(true);
+        }
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        if amount > 0 {
+            self.notify(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Result;
+    use std::slice;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    /// A reader delivering each of its chunks in turn, followed by the end of the input.
+    ///
+    /// A single read never spans more than one chunk.
+    struct Scripted {
+        chunks: slice::Iter<'static, &'static [u8]>,
+        chunk: &'static [u8],
+    }
+
+    impl Read for Scripted {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            if self.chunk.is_empty() {
+                match self.chunks.next() {
+                    Some(chunk) => self.chunk = chunk,
+                    None => return Ok(0),
+                }
+            }
+            self.chunk.read(buf)
+        }
+    }
+
+    fn scripted(chunks: &'static [&'static [u8]]) -> Scripted {
+        Scripted {
+            chunks: chunks.iter(),
+            chunk: &[],
+        }
+    }
+
+    /// Asserts that the notifications never decrease and that only the last one is done.
+    fn assert_monotonic(notifications: &[Progress]) {
+        for pair in notifications.windows(2) {
+            assert!(pair[0].bytes_delivered() <= pair[1].bytes_delivered());
+            assert!(pair[0].inner_bytes_read() <= pair[1].inner_bytes_read());
+            assert!(!pair[0].is_done());
+        }
+        assert!(assert_some!(notifications.last()).is_done());
+    }
+
+    #[test]
+    fn read_to_ctrl_z() {
+        let mut notifications = Vec::new();
+        {
+            let mut reader = ReadToCtrlZ::new(scripted(&[b"foo", b"bar", b"baz\x1aqux", b"quux"]))
+                .on_progress(|progress| notifications.push(progress));
+            let mut buf = [0; 16];
+
+            assert_ok_eq!(reader.read(&mut buf), 3);
+            assert_ok_eq!(reader.read(&mut buf), 3);
+            assert_ok_eq!(reader.read(&mut buf), 3);
+            assert_ok_eq!(reader.read(&mut buf), 0);
+        }
+
+        assert_monotonic(&notifications);
+        assert_eq!(
+            notifications
+                .iter()
+                .map(|progress| (progress.bytes_delivered(), progress.inner_bytes_read()))
+                .collect::<Vec<_>>(),
+            [(3, 3), (6, 6), (9, 13)]
+        );
+    }
+
+    #[test]
+    fn read_to_eof() {
+        let mut notifications = Vec::new();
+        let mut output = String::new();
+        {
+            let mut reader = ReadToCtrlZ::new(scripted(&[b"foo", b"bar"]))
+                .on_progress(|progress| notifications.push(progress));
+
+            assert_ok_eq!(reader.read_to_string(&mut output), 6);
+            // Reading after the end does not notify again.
+            assert_ok_eq!(reader.read(&mut [0; 4]), 0);
+        }
+
+        assert_monotonic(&notifications);
+        assert_eq!(notifications.len(), 3);
+        assert_eq!(notifications[2].bytes_delivered(), 6);
+    }
+
+    #[test]
+    fn buf_read_to_ctrl_z() {
+        let mut notifications = Vec::new();
+        let mut output = Vec::new();
+        {
+            let inner = BufReader::with_capacity(4, scripted(&[b"foo", b"bar", b"b\x1aqux"]));
+            let mut reader =
+                ReadToCtrlZ::new(inner).on_progress(|progress| notifications.push(progress));
+
+            assert_ok_eq!(reader.read_until(b'\n', &mut output), 7);
+            // Filling the buffer without consuming does not notify.
+            assert_ok_eq!(reader.fill_buf(), b"");
+            assert_ok_eq!(reader.fill_buf(), b"");
+        }
+
+        assert_eq!(output, b"foobarb");
+        assert_monotonic(&notifications);
+        assert_eq!(
+            notifications
+                .iter()
+                .map(|progress| progress.bytes_delivered())
+                .collect::<Vec<_>>(),
+            [3, 6, 7, 7]
+        );
+    }
+
+    #[test]
+    fn error() {
+        let mut notifications = Vec::new();
+        {
+            let mut reader = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+                Err(Error::new(ErrorKind::Other, "foo"))
+            }))
+            .on_progress(|progress| notifications.push(progress));
+
+            assert_eq!(
+                assert_err!(reader.read(&mut [0; 4])).kind(),
+                ErrorKind::Other
+            );
+        }
+
+        assert!(notifications.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let progress = Progress {
+            bytes_delivered: 3,
+            inner_bytes_read: 7,
+            done: true,
+        };
+        let json = assert_ok!(serde_json::to_string(&progress));
+
+        assert_eq!(
+            json,
+            r#"{"bytes_delivered":3,"inner_bytes_read":7,"done":true}"#
+        );
+        assert_ok_eq!(serde_json::from_str::<Progress>(&json), progress);
+    }
+}
@@ -0,0 +1,113 @@
+use std::cell::RefCell;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::rc::Rc;
+
+/// A reader that reads from a reader shared through an `Rc<RefCell<R>>`.
+///
+/// [`Read`] cannot be implemented for a [`ReadToCtrlZ`] wrapping an `Rc<RefCell<R>>` directly, as
+/// it would conflict with the existing implementation for all readers. Wrapping the shared reader
+/// in this adapter first allows it to be read by a `ReadToCtrlZ`, while other owners keep access
+/// to it between reads. For [`BufRead`](std::io::BufRead), wrap it in a
+/// [`BufReadToCtrlZ`](crate::BufReadToCtrlZ) instead.
+///
+/// Each read mutably borrows the inner reader for the duration of the read. If it is already
+/// borrowed, an error of kind [`Other`](ErrorKind::Other) is returned rather than panicking.
+///
+/// This type is only available with the `rc` feature enabled, which requires Rust 1.13 or later.
+///
+/// # Example
+/// ```
+/// use ctrl_z::RcReader;
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::cell::RefCell;
+/// use std::io::Read;
+/// use std::rc::Rc;
+///
+/// let shared = Rc::new(RefCell::new(&b"foo\x1abar"[..]));
+/// let mut reader = ReadToCtrlZ::new(RcReader(shared.clone()));
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct RcReader<R>(pub Rc<RefCell<R>>);
+
+// The `rc` feature requires a newer Rust version, so the MSRV does not apply here.
+#[allow(clippy::incompatible_msrv)]
+impl<R> Read for RcReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.0.try_borrow_mut() {
+            Ok(mut inner) => inner.read(buf),
+            Err(error) => Err(Error::new(ErrorKind::Other, error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RcReader;
+    use std::cell::RefCell;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::rc::Rc;
+    use BufReadToCtrlZ;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read() {
+        let shared = Rc::new(RefCell::new(b"foo\x1abar" as &[u8]));
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            ReadToCtrlZ::new(RcReader(shared.clone())).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+        assert_eq!(*shared.borrow(), b"");
+    }
+
+    #[test]
+    fn read_shared_between_owners() {
+        let shared = Rc::new(RefCell::new(b"foo\x1abar" as &[u8]));
+        let mut first = RcReader(shared.clone());
+        let mut second = RcReader(shared.clone());
+        let mut output = [0; 2];
+
+        assert_ok_eq!(first.read(&mut output), 2);
+        assert_eq!(&output, b"fo");
+        assert_ok_eq!(second.read(&mut output), 2);
+        assert_eq!(&output, b"o\x1a");
+    }
+
+    #[test]
+    fn buffered() {
+        let shared = Rc::new(RefCell::new(b"foo\x1abar" as &[u8]));
+        let mut output = String::new();
+
+        assert_ok_eq!(
+            BufReadToCtrlZ::new(RcReader(shared)).read_to_string(&mut output),
+            3
+        );
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn already_borrowed() {
+        let shared = Rc::new(RefCell::new(b"foo" as &[u8]));
+        let _borrow = shared.borrow();
+        let mut reader = ReadToCtrlZ::new(RcReader(shared.clone()));
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::Other
+        );
+    }
+}
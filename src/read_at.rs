@@ -0,0 +1,262 @@
+use find_ctrl_z;
+#[cfg(feature = "read_at")]
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+#[cfg(all(unix, feature = "read_at"))]
+use std::os::unix::fs::FileExt;
+#[cfg(all(windows, feature = "read_at"))]
+use std::os::windows::fs::FileExt;
+
+/// A source of bytes that can be read at arbitrary offsets through a shared reference.
+///
+/// This is implemented for byte slices and `Vec<u8>`. With the `read_at` feature enabled, which
+/// requires Rust 1.15 or later, it is also implemented for [`File`](std::fs::File) on Unix and
+/// Windows using the platform's `FileExt`.
+pub trait ReadAt {
+    /// Reads bytes starting at `offset` into `buf`, returning the number of bytes read.
+    ///
+    /// Returning `Ok(0)` for a nonempty `buf` indicates that `offset` is at or beyond the end.
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize>;
+}
+
+impl ReadAt for [u8] {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        if offset >= self.len() as u64 {
+            return Ok(0);
+        }
+        (&self[offset as usize..]).read(buf)
+    }
+}
+
+impl ReadAt for Vec<u8> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        self[..].read_at(buf, offset)
+    }
+}
+
+impl<'a, T> ReadAt for &'a T
+where
+    T: ReadAt + ?Sized,
+{
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        let inner: &&'a T = self;
+        (**inner).read_at(buf, offset)
+    }
+}
+
+#[cfg(all(unix, feature = "read_at"))]
+// The `read_at` feature requires a newer Rust version, so the MSRV does not apply here.
+#[allow(clippy::incompatible_msrv)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        FileExt::read_at(self, buf, offset)
+    }
+}
+
+#[cfg(all(windows, feature = "read_at"))]
+// The `read_at` feature requires a newer Rust version, so the MSRV does not apply here.
+#[allow(clippy::incompatible_msrv)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        // This moves the file's cursor, but does not depend on it.
+        self.seek_read(buf, offset)
+    }
+}
+
+/// A positioned reader treating the next `0x1A` byte at or after each offset as the end.
+///
+/// Each read is independent, and no cursor is kept, so a single `CtrlZReadAt` can be shared and
+/// read at many offsets concurrently. This suits containers holding many `0x1A`-terminated
+/// regions.
+///
+/// # Example
+/// ```
+/// use ctrl_z::CtrlZReadAt;
+///
+/// let reader = CtrlZReadAt::new(b"foo\x1abarbaz\x1a".to_vec());
+/// let mut buf = [0; 16];
+///
+/// assert_eq!(reader.read_logical_at(4, &mut buf).unwrap(), 6);
+/// assert_eq!(&buf[..6], b"barbaz");
+/// assert_eq!(reader.content_len_at(1).unwrap(), 2);
+/// ```
+pub struct CtrlZReadAt<F> {
+    /// The source being read.
+    inner: F,
+}
+
+impl<F> CtrlZReadAt<F> {
+    /// Creates a new `CtrlZReadAt`, wrapping the provided source.
+    pub fn new(inner: F) -> Self {
+        CtrlZReadAt { inner: inner }
+    }
+
+    /// Returns a reference to the underlying source.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+
+    /// Unwraps this `CtrlZReadAt`, returning the underlying source.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F> CtrlZReadAt<F>
+where
+    F: ReadAt,
+{
+    /// Reads bytes starting at `offset` into `buf`, stopping before the first `0x1A` byte.
+    ///
+    /// Returns the number of bytes read, which is `0` if the byte at `offset` is a `0x1A` byte or
+    /// `offset` is at the end of the source. Only a single read is made, so fewer bytes than are
+    /// available may be returned; reading again at the following offset continues the region.
+    pub fn read_logical_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let n = try!(self.read_raw_at(offset, buf));
+        Ok(find_ctrl_z(&buf[..n]).unwrap_or(n))
+    }
+
+    /// Returns the number of bytes from `offset` to the next `0x1A` byte, or to the end of the
+    /// source if there is none.
+    pub fn content_len_at(&self, offset: u64) -> Result<u64> {
+        let mut chunk = [0; 8 * 1024];
+        let mut len = 0;
+        loop {
+            let n = try!(self.read_raw_at(offset + len, &mut chunk));
+            if n == 0 {
+                return Ok(len);
+            }
+            if let Some(i) = find_ctrl_z(&chunk[..n]) {
+                return Ok(len + i as u64);
+            }
+            len += n as u64;
+        }
+    }
+
+    /// Reads bytes starting at `offset` into `buf`, retrying if interrupted.
+    fn read_raw_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            match self.inner.read_at(buf, offset) {
+                Ok(n) => return Ok(n),
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CtrlZReadAt;
+    use super::ReadAt;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn read_logical_at() {
+        let reader = CtrlZReadAt::new(b"foo\x1abar" as &[u8]);
+        let mut buf = [0; 16];
+
+        assert_ok_eq!(reader.read_logical_at(0, &mut buf), 3);
+        assert_eq!(&buf[..3], b"foo");
+        assert_ok_eq!(reader.read_logical_at(1, &mut buf), 2);
+        assert_ok_eq!(reader.read_logical_at(3, &mut buf), 0);
+        assert_ok_eq!(reader.read_logical_at(4, &mut buf), 3);
+        assert_eq!(&buf[..3], b"bar");
+        assert_ok_eq!(reader.read_logical_at(7, &mut buf), 0);
+        assert_ok_eq!(reader.read_logical_at(100, &mut buf), 0);
+    }
+
+    #[test]
+    fn read_logical_at_small_buffer() {
+        let reader = CtrlZReadAt::new(b"foobar\x1a".to_vec());
+        let mut buf = [0; 4];
+
+        assert_ok_eq!(reader.read_logical_at(0, &mut buf), 4);
+        assert_ok_eq!(reader.read_logical_at(4, &mut buf), 2);
+    }
+
+    #[test]
+    fn content_len_at() {
+        let mut data = vec![b'a'; 20000];
+        data.push(b'\x1a');
+        data.extend(b"foo".iter().cloned());
+        let reader = CtrlZReadAt::new(data);
+
+        assert_ok_eq!(reader.content_len_at(0), 20000);
+        assert_ok_eq!(reader.content_len_at(19999), 1);
+        assert_ok_eq!(reader.content_len_at(20000), 0);
+        assert_ok_eq!(reader.content_len_at(20001), 3);
+        assert_ok_eq!(reader.content_len_at(30000), 0);
+    }
+
+    #[test]
+    fn concurrent_overlapping_offsets() {
+        let mut data = Vec::new();
+        for i in 0..64 {
+            data.extend((0..i).map(|_| b'a'));
+            data.push(b'\x1a');
+        }
+        let expected = (0..data.len() as u64)
+            .map(|offset| {
+                let rest = &data[offset as usize..];
+                rest.iter().position(|&byte| byte == b'\x1a').unwrap() as u64
+            })
+            .collect::<Vec<_>>();
+        let reader = Arc::new(CtrlZReadAt::new(data));
+        let expected = Arc::new(expected);
+
+        let handles = (0..4)
+            .map(|thread| {
+                let reader = reader.clone();
+                let expected = expected.clone();
+                thread::spawn(move || {
+                    // Each thread visits every offset, starting at a different point.
+                    let len = expected.len();
+                    for i in 0..len {
+                        let offset = (i + thread * len / 4) % len;
+                        assert_ok_eq!(reader.content_len_at(offset as u64), expected[offset]);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_ok!(handle.join());
+        }
+    }
+
+    #[test]
+    fn read_at_slice_beyond_end() {
+        assert_ok_eq!((b"foo" as &[u8]).read_at(&mut [0; 4], 3), 0);
+    }
+
+    #[cfg(feature = "read_at")]
+    #[test]
+    fn read_at_file() {
+        use std::env;
+        use std::fs;
+        use std::fs::File;
+        use std::io::Write;
+
+        let path = env::temp_dir().join("ctrl_z_read_at_file");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1abarbaz\x1a"));
+        let reader = Arc::new(CtrlZReadAt::new(assert_ok!(File::open(&path))));
+
+        let handles = [0, 4, 5]
+            .iter()
+            .map(|&offset| {
+                let reader = reader.clone();
+                thread::spawn(move || reader.content_len_at(offset).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let lens = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        assert_ok!(fs::remove_file(&path));
+
+        assert_eq!(lens, [3, 6, 5]);
+    }
+}
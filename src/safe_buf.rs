@@ -0,0 +1,170 @@
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// A [`BufReader`] over a [`ReadToCtrlZ`], reporting whether bytes following the `0x1A` byte were
+/// read from the inner reader.
+///
+/// Wrapping a `ReadToCtrlZ` in a `BufReader`, as in `BufReader::new(ReadToCtrlZ::new(inner))`,
+/// never delivers bytes following the `0x1A` byte. However, the `ReadToCtrlZ` reads from `inner`
+/// in chunks as large as the `BufReader`'s buffer, so any bytes following the `0x1A` byte within
+/// the same chunk are consumed from `inner` and lost. This type makes that visible through
+/// [`overread()`](SafeBufReader::overread()).
+///
+/// When the bytes following the `0x1A` byte must remain available in `inner`, compose the types in
+/// the other order, as in `ReadToCtrlZ::new(BufReader::new(inner))`, and read through [`BufRead`]:
+/// the `BufReader` then keeps the bytes following the `0x1A` byte in its own buffer, where they
+/// can be reached by unwrapping it. For the full [`BufRead`] API over a reader that only
+/// implements [`Read`], see [`BufReadToCtrlZ`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::SafeBufReader;
+/// use std::io::BufRead;
+///
+/// let mut reader = SafeBufReader::new(&b"foo\nbar\x1abaz"[..]);
+///
+/// let lines = (&mut reader).lines().map(|line| line.unwrap()).collect::<Vec<_>>();
+/// assert_eq!(lines, ["foo", "bar"]);
+/// // "baz" was read from the inner reader along with the `0x1A` byte.
+/// assert_eq!(reader.overread(), 3);
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+/// [`BufReadToCtrlZ`]: crate::BufReadToCtrlZ
+pub struct SafeBufReader<R> {
+    /// The buffered reader being read.
+    inner: BufReader<ReadToCtrlZ<R>>,
+}
+
+impl<R> SafeBufReader<R>
+where
+    R: Read,
+{
+    /// Creates a new `SafeBufReader` with a default buffer capacity, wrapping the provided reader.
+    pub fn new(inner: R) -> Self {
+        SafeBufReader {
+            inner: BufReader::new(ReadToCtrlZ::new(inner)),
+        }
+    }
+
+    /// Creates a new `SafeBufReader` with the specified buffer capacity, wrapping the provided
+    /// reader.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        SafeBufReader {
+            inner: BufReader::with_capacity(capacity, ReadToCtrlZ::new(inner)),
+        }
+    }
+}
+
+impl<R> SafeBufReader<R> {
+    /// Returns the number of bytes following the `0x1A` byte that were read from the inner reader.
+    ///
+    /// These bytes were never delivered, and are no longer available from the inner reader. This is
+    /// `0` until the `0x1A` byte has been reached.
+    pub fn overread(&self) -> u64 {
+        let reader = self.inner.get_ref();
+        if reader.is_terminated() {
+            // The terminating byte itself is never counted as overread.
+            reader.excess().saturating_sub(1)
+        } else {
+            0
+        }
+    }
+
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<R> {
+        self.inner.get_ref()
+    }
+
+    /// Unwraps this `SafeBufReader`, returning the underlying `BufReader`.
+    pub fn into_inner(self) -> BufReader<ReadToCtrlZ<R>> {
+        self.inner
+    }
+}
+
+impl<R> Read for SafeBufReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R> BufRead for SafeBufReader<R>
+where
+    R: Read,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafeBufReader;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn lines() {
+        let lines = SafeBufReader::new(b"foo\nbar\x1abaz" as &[u8])
+            .lines()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn overread() {
+        let mut output = String::new();
+        let mut reader = SafeBufReader::new(b"foo\x1abar" as &[u8]);
+
+        assert_eq!(reader.overread(), 0);
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_eq!(reader.overread(), 3);
+    }
+
+    #[test]
+    fn no_overread_at_end() {
+        let mut output = String::new();
+        let mut reader = SafeBufReader::new(b"foo\x1a" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(reader.overread(), 0);
+    }
+
+    #[test]
+    fn no_overread_without_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = SafeBufReader::with_capacity(2, b"foobar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(reader.overread(), 0);
+    }
+
+    #[test]
+    fn buffer_inside_keeps_remainder() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(BufReader::new(b"foo\x1abar" as &[u8]));
+
+        // Reading through `BufRead` consumes only the bytes preceding the `0x1A` byte.
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 3);
+        assert_eq!(output, b"foo");
+        // The bytes following the `0x1A` byte remain in the `BufReader`.
+        let mut rest = String::new();
+        assert_ok_eq!(reader.into_parts().0.read_to_string(&mut rest), 3);
+        assert_eq!(rest, "bar");
+    }
+}
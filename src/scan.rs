@@ -0,0 +1,586 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+#[cfg(feature = "simd")]
+use std::simd::prelude::*;
+
+#[cfg(not(feature = "simd"))]
+use self::find_scalar as find;
+#[cfg(feature = "simd")]
+use self::find_simd as find;
+
+/// The number of bytes compared by each vector operation.
+#[cfg(feature = "simd")]
+const LANES: usize = 32;
+
+/// The size of the buffer read into by [`scan_resumable()`].
+///
+/// [`scan_resumable()`]: crate::scan_resumable()
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// The length of a [`ScanState`] serialized using [`ScanState::to_bytes()`].
+///
+/// [`ScanState`]: crate::ScanState
+/// [`ScanState::to_bytes()`]: crate::ScanState::to_bytes()
+const STATE_LEN: usize = 25;
+
+/// The number of bytes searched by each parallel task.
+#[cfg(feature = "rayon")]
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// A summary of the `0x1A` bytes found within a buffer.
+///
+/// This is returned by [`scan()`], and by `par_scan()` when the `rayon` feature is enabled.
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the fields `first` and
+/// `count`.
+///
+/// [`scan()`]: crate::scan()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScanReport {
+    /// The offset of the first `0x1A` byte, if any.
+    first: Option<usize>,
+    /// The total number of `0x1A` bytes.
+    count: usize,
+}
+
+impl ScanReport {
+    /// Returns the offset of the first `0x1A` byte, or `None` if there is none.
+    pub fn first(&self) -> Option<usize> {
+        self.first
+    }
+
+    /// Returns the total number of `0x1A` bytes.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Combines this report with the report of the bytes directly following it.
+    ///
+    /// `len` is the number of bytes covered by this report.
+    #[cfg(feature = "rayon")]
+    fn chain(self, len: usize, next: ScanReport) -> ScanReport {
+        ScanReport {
+            first: self.first.or(next.first.map(|first| first + len)),
+            count: self.count + next.count,
+        }
+    }
+}
+
+/// Searches `bytes` for the first `0x1A` byte one byte at a time.
+fn find_scalar(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|&byte| byte == b'\x1a')
+}
+
+/// Searches `bytes` for the first `0x1A` byte, comparing `LANES` bytes at a time.
+///
+/// Bytes following the last full vector are searched one byte at a time.
+#[cfg(feature = "simd")]
+// The `simd` feature requires a nightly compiler, so the MSRV does not apply here.
+#[allow(clippy::incompatible_msrv)]
+fn find_simd(bytes: &[u8]) -> Option<usize> {
+    let marker = Simd::<u8, LANES>::splat(b'\x1a');
+    let mut chunks = bytes.chunks_exact(LANES);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        if let Some(i) = Simd::from_slice(chunk).simd_eq(marker).first_set() {
+            return Some(offset + i);
+        }
+        offset += LANES;
+    }
+    find_scalar(chunks.remainder()).map(|i| offset + i)
+}
+
+/// Returns the offset of the first `0x1A` byte in `bytes`, or `None` if there is none.
+///
+/// With the `simd` feature enabled, which requires a nightly compiler, the search is explicitly
+/// vectorized using `std::simd`. The result is identical either way.
+///
+/// # Example
+/// ```
+/// assert_eq!(ctrl_z::find_ctrl_z(b"foo\x1abar"), Some(3));
+/// assert_eq!(ctrl_z::find_ctrl_z(b"foobar"), None);
+/// ```
+pub fn find_ctrl_z(bytes: &[u8]) -> Option<usize> {
+    find(bytes)
+}
+
+/// Returns the number of `0x1A` bytes in `bytes`.
+///
+/// # Example
+/// ```
+/// assert_eq!(ctrl_z::count_ctrl_z(b"foo\x1abar\x1a"), 2);
+/// ```
+pub fn count_ctrl_z(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\x1a').count()
+}
+
+/// Scans all of `bytes` for `0x1A` bytes.
+///
+/// # Example
+/// ```
+/// let report = ctrl_z::scan(b"foo\x1abar\x1a");
+///
+/// assert_eq!(report.first(), Some(3));
+/// assert_eq!(report.count(), 2);
+/// ```
+pub fn scan(bytes: &[u8]) -> ScanReport {
+    ScanReport {
+        first: find_ctrl_z(bytes),
+        count: count_ctrl_z(bytes),
+    }
+}
+
+/// The progress of a scan that may be resumed later, possibly by another process.
+///
+/// This is updated by [`scan_resumable()`]. It records how many bytes have been scanned, along with
+/// the `0x1A` bytes found within them, so a scan interrupted partway through can continue from
+/// [`offset()`](ScanState::offset()) rather than from the start. Offsets are `u64`, so inputs
+/// larger than the address space can be scanned.
+///
+/// A `ScanState` can be persisted between invocations using [`to_bytes()`](ScanState::to_bytes())
+/// and [`from_bytes()`](ScanState::from_bytes()), or using `serde` when the `serde` feature is
+/// enabled.
+///
+/// # Example
+/// ```
+/// use ctrl_z::ScanState;
+///
+/// let input = b"foo\x1abar\x1a";
+/// let mut state = ScanState::new();
+///
+/// ctrl_z::scan_resumable(&input[..5], &mut state).unwrap();
+/// let saved = state.to_bytes();
+///
+/// // Later, continue from where the scan left off.
+/// let mut state = ScanState::from_bytes(&saved).unwrap();
+/// let offset = state.offset() as usize;
+/// ctrl_z::scan_resumable(&input[offset..], &mut state).unwrap();
+///
+/// assert_eq!(state.first(), Some(3));
+/// assert_eq!(state.count(), 2);
+/// ```
+///
+/// [`scan_resumable()`]: crate::scan_resumable()
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScanState {
+    /// The number of bytes scanned so far.
+    offset: u64,
+    /// The offset of the first `0x1A` byte found, if any.
+    first: Option<u64>,
+    /// The number of `0x1A` bytes found so far.
+    count: u64,
+}
+
+impl ScanState {
+    /// Creates a new `ScanState` for a scan that has not yet started.
+    pub fn new() -> Self {
+        ScanState {
+            offset: 0,
+            first: None,
+            count: 0,
+        }
+    }
+
+    /// Returns the number of bytes scanned so far.
+    ///
+    /// This is the offset a resumed scan must continue reading from.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the offset of the first `0x1A` byte found, or `None` if none has been found yet.
+    pub fn first(&self) -> Option<u64> {
+        self.first
+    }
+
+    /// Returns the number of `0x1A` bytes found so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Serializes this `ScanState` into a fixed-length byte representation.
+    ///
+    /// The representation is the offset, a byte indicating whether a `0x1A` byte has been found,
+    /// the offset of the first `0x1A` byte (zero if none has been found), and the count, with each
+    /// integer encoded as 8 little-endian bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(STATE_LEN);
+        push_u64(&mut bytes, self.offset);
+        bytes.push(self.first.is_some() as u8);
+        push_u64(&mut bytes, self.first.unwrap_or(0));
+        push_u64(&mut bytes, self.count);
+        bytes
+    }
+
+    /// Deserializes a `ScanState` from the representation returned by
+    /// [`to_bytes()`](ScanState::to_bytes()).
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::InvalidInput`] error if `bytes` is not a valid representation.
+    ///
+    /// [`ErrorKind::InvalidInput`]: std::io::ErrorKind::InvalidInput
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != STATE_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "scan state has an invalid length",
+            ));
+        }
+        let offset = read_u64(&bytes[0..8]);
+        let first = match bytes[8] {
+            0 => None,
+            1 => Some(read_u64(&bytes[9..17])),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "scan state has an invalid flag",
+                ))
+            }
+        };
+        let count = read_u64(&bytes[17..25]);
+        if first.map_or(count != 0, |first| count == 0 || first >= offset) || count > offset {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "scan state is inconsistent",
+            ));
+        }
+        Ok(ScanState {
+            offset: offset,
+            first: first,
+            count: count,
+        })
+    }
+
+    /// Records the results of scanning `bytes`, which directly follow the bytes scanned so far.
+    fn update(&mut self, bytes: &[u8]) {
+        if self.first.is_none() {
+            self.first = find_ctrl_z(bytes).map(|i| self.offset + i as u64);
+        }
+        self.count += count_ctrl_z(bytes) as u64;
+        self.offset += bytes.len() as u64;
+    }
+}
+
+/// Appends `value` to `bytes` as 8 little-endian bytes.
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    for i in 0..8 {
+        bytes.push((value >> (8 * i)) as u8);
+    }
+}
+
+/// Reads a `u64` from 8 little-endian bytes.
+fn read_u64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+/// Scans `reader` for `0x1A` bytes until it is exhausted, recording the progress in `state`.
+///
+/// `reader` must be positioned at [`state.offset()`](ScanState::offset()) relative to the start of
+/// the input, such as by seeking there when resuming a scan of a file. `state` is updated after
+/// every read, so if an error occurs, it reflects every byte scanned before the error, and the scan
+/// can be resumed from there. However a scan is split, the final state is identical to that of an
+/// unsplit scan.
+///
+/// # Errors
+/// Returns any error other than [`ErrorKind::Interrupted`] that occurs while reading.
+///
+/// # Example
+/// ```
+/// use ctrl_z::ScanState;
+///
+/// let mut state = ScanState::new();
+/// ctrl_z::scan_resumable(&b"foo\x1abar"[..], &mut state).unwrap();
+///
+/// assert_eq!(state.offset(), 7);
+/// assert_eq!(state.first(), Some(3));
+/// ```
+///
+/// [`ErrorKind::Interrupted`]: std::io::ErrorKind::Interrupted
+pub fn scan_resumable<R>(mut reader: R, state: &mut ScanState) -> Result<()>
+where
+    R: Read,
+{
+    let mut buf = [0; BUFFER_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => state.update(&buf[..n]),
+            Err(ref error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Returns the offset of the first `0x1A` byte in `bytes`, searching chunks of `bytes` in
+/// parallel.
+///
+/// The result is always identical to that of [`find_ctrl_z()`]: if multiple chunks contain a
+/// `0x1A` byte, the lowest offset is returned.
+///
+/// This function is only available with the `rayon` feature enabled.
+///
+/// [`find_ctrl_z()`]: crate::find_ctrl_z()
+#[cfg(feature = "rayon")]
+pub fn par_find_ctrl_z(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .par_chunks(CHUNK_LEN)
+        .enumerate()
+        .find_map_first(|(i, chunk)| find_ctrl_z(chunk).map(|offset| i * CHUNK_LEN + offset))
+}
+
+/// Scans all of `bytes` for `0x1A` bytes, scanning chunks of `bytes` in parallel.
+///
+/// The result is always identical to that of [`scan()`].
+///
+/// This function is only available with the `rayon` feature enabled.
+///
+/// [`scan()`]: crate::scan()
+#[cfg(feature = "rayon")]
+pub fn par_scan(bytes: &[u8]) -> ScanReport {
+    // Each chunk's report is paired with its length, so offsets can be adjusted when combining.
+    bytes
+        .par_chunks(CHUNK_LEN)
+        .map(|chunk| (chunk.len(), scan(chunk)))
+        .reduce(
+            || (0, scan(&[])),
+            |(len, report), (next_len, next)| (len + next_len, report.chain(len, next)),
+        )
+        .1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A simple xorshift generator, so randomized inputs are reproducible.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    #[test]
+    fn find_ctrl_z_present() {
+        assert_some_eq!(find_ctrl_z(b"foo\x1abar\x1a"), 3);
+    }
+
+    #[test]
+    fn find_ctrl_z_absent() {
+        assert_none!(find_ctrl_z(b"foobar"));
+    }
+
+    #[test]
+    fn scan_present() {
+        let report = scan(b"\x1afoo\x1abar\x1a");
+
+        assert_some_eq!(report.first(), 0);
+        assert_eq!(report.count(), 3);
+    }
+
+    #[test]
+    fn scan_absent() {
+        let report = scan(b"foobar");
+
+        assert_none!(report.first());
+        assert_eq!(report.count(), 0);
+    }
+
+    #[test]
+    fn scan_resumable_matches_scan() {
+        let input = b"\x1afoo\x1abar\x1a";
+        let mut state = ScanState::new();
+
+        assert_ok!(scan_resumable(&input[..], &mut state));
+
+        let report = scan(input);
+        assert_eq!(state.offset(), input.len() as u64);
+        assert_eq!(state.first(), report.first().map(|first| first as u64));
+        assert_eq!(state.count(), report.count() as u64);
+    }
+
+    #[test]
+    fn scan_resumable_split_matches_unsplit() {
+        let mut rng = XorShift(0xd1b5_4a32_d192_ed03_u64);
+        for _ in 0..64 {
+            let len = (rng.next() % (3 * BUFFER_SIZE as u64)) as usize;
+            let mut bytes = vec![0; len];
+            for byte in bytes.iter_mut() {
+                // Markers are sparse, so inputs without any are also generated.
+                *byte = if rng.next() % 512 == 0 {
+                    b'\x1a'
+                } else {
+                    (rng.next() % 26) as u8
+                };
+            }
+            let mut unsplit = ScanState::new();
+            assert_ok!(scan_resumable(&bytes[..], &mut unsplit));
+
+            // Split the scan at several points, persisting the state between each part.
+            let mut splits = (0..(rng.next() % 4))
+                .map(|_| (rng.next() % (len as u64 + 1)) as usize)
+                .collect::<Vec<_>>();
+            splits.sort();
+            splits.push(len);
+            let mut saved = ScanState::new().to_bytes();
+            for &split in &splits {
+                let mut state = assert_ok!(ScanState::from_bytes(&saved));
+                let offset = state.offset() as usize;
+                assert_ok!(scan_resumable(&bytes[offset..split], &mut state));
+                saved = state.to_bytes();
+            }
+
+            assert_ok_eq!(ScanState::from_bytes(&saved), unsplit);
+        }
+    }
+
+    #[test]
+    fn scan_resumable_every_split_point() {
+        let input = b"\x1afoo\x1a\x1abar\x1a";
+        let mut unsplit = ScanState::new();
+        assert_ok!(scan_resumable(&input[..], &mut unsplit));
+
+        for split in 0..input.len() + 1 {
+            let mut state = ScanState::new();
+            assert_ok!(scan_resumable(&input[..split], &mut state));
+            assert_ok!(scan_resumable(&input[split..], &mut state));
+
+            assert_eq!(state, unsplit);
+        }
+    }
+
+    #[test]
+    fn scan_resumable_error_keeps_progress() {
+        let mut state = ScanState::new();
+        let reader = (&b"foo\x1a"[..]).chain(::ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "tape error"))
+        }));
+
+        assert_err!(scan_resumable(reader, &mut state));
+        assert_eq!(state.offset(), 4);
+        assert_some_eq!(state.first(), 3);
+    }
+
+    #[test]
+    fn scan_state_bytes_round_trip() {
+        let mut state = ScanState::new();
+        assert_ok!(scan_resumable(&b"foo\x1abar"[..], &mut state));
+
+        assert_ok_eq!(ScanState::from_bytes(&state.to_bytes()), state);
+        assert_ok_eq!(
+            ScanState::from_bytes(&ScanState::new().to_bytes()),
+            ScanState::new()
+        );
+    }
+
+    #[test]
+    fn scan_state_from_invalid_bytes() {
+        let mut bytes = ScanState::new().to_bytes();
+
+        assert_eq!(
+            assert_err!(ScanState::from_bytes(&bytes[1..])).kind(),
+            ErrorKind::InvalidInput
+        );
+        bytes[8] = 2;
+        assert_eq!(
+            assert_err!(ScanState::from_bytes(&bytes)).kind(),
+            ErrorKind::InvalidInput
+        );
+        // A count without a first `0x1A` byte is inconsistent.
+        bytes[8] = 0;
+        bytes[17] = 1;
+        assert_eq!(
+            assert_err!(ScanState::from_bytes(&bytes)).kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_matches_sequential() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d_u64);
+        for _ in 0..64 {
+            let len = (rng.next() % (4 * CHUNK_LEN as u64)) as usize;
+            let markers = rng.next() % 4;
+            let mut bytes = vec![0; len];
+            for byte in bytes.iter_mut() {
+                // Avoid generating `0x1A` bytes, so the number of markers is controlled.
+                *byte = (rng.next() % 26) as u8;
+            }
+            for _ in 0..markers {
+                if len > 0 {
+                    bytes[(rng.next() % len as u64) as usize] = b'\x1a';
+                }
+            }
+
+            assert_eq!(par_find_ctrl_z(&bytes), find_ctrl_z(&bytes));
+            assert_eq!(par_scan(&bytes), scan(&bytes));
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_matches_scalar() {
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15_u64);
+        let mut bytes = [0; 4 * LANES + 64];
+        for _ in 0..4096 {
+            for byte in bytes.iter_mut() {
+                // Avoid generating `0x1A` bytes, so the number of markers is controlled.
+                *byte = (rng.next() % 26) as u8;
+            }
+            // Offsetting the start varies the alignment, and the lengths include buffers shorter
+            // than a single vector.
+            let start = (rng.next() % 64) as usize;
+            let len = (rng.next() % (4 * LANES as u64)) as usize;
+            let slice = &mut bytes[start..start + len];
+            if len > 0 {
+                match rng.next() % 4 {
+                    0 => {}
+                    // A marker within the final partial vector.
+                    1 => {
+                        slice[len - 1 - (rng.next() % ((len - 1) % LANES + 1) as u64) as usize] =
+                            b'\x1a'
+                    }
+                    _ => slice[(rng.next() % len as u64) as usize] = b'\x1a',
+                }
+            }
+
+            assert_eq!(find_simd(slice), find_scalar(slice));
+            assert_eq!(find_ctrl_z(slice), find_scalar(slice));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let report = scan(b"foo\x1abar\x1a");
+        let json = assert_ok!(serde_json::to_string(&report));
+
+        assert_eq!(json, r#"{"first":3,"count":2}"#);
+        assert_ok_eq!(serde_json::from_str::<ScanReport>(&json), report);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn scan_state_serde_round_trip() {
+        let mut state = ScanState::new();
+        assert_ok!(scan_resumable(&b"foo\x1abar"[..], &mut state));
+        let json = assert_ok!(serde_json::to_string(&state));
+
+        assert_eq!(json, r#"{"offset":7,"first":3,"count":1}"#);
+        assert_ok_eq!(serde_json::from_str::<ScanState>(&json), state);
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp;
+use std::io::Result;
+use std::io::Write;
+
+/// A writer splitting its output into `0x1A`-terminated segments of bounded size.
+///
+/// Bytes are written to the inner writer unchanged, except that a `0x1A` byte is inserted whenever
+/// the current segment has reached `max_segment_len` bytes and more bytes are written. The final
+/// partial segment is terminated by [`finish()`](SegmentingWriter::finish()). Each segment
+/// therefore contains between `1` and `max_segment_len` bytes, followed by a `0x1A` byte that is
+/// not counted towards its length.
+///
+/// Writes are split across segment boundaries regardless of how the input is divided between
+/// calls to [`write()`](Write::write()). The `0x1A` byte is only ever written on its own, so a
+/// failed write never leaves a segment partially terminated.
+///
+/// # Example
+/// ```
+/// use ctrl_z::SegmentingWriter;
+/// use std::io::Write;
+///
+/// let mut writer = SegmentingWriter::new(Vec::new(), 3);
+///
+/// assert!(writer.write_all(b"foobarba").is_ok());
+/// assert_eq!(writer.finish().unwrap(), b"foo\x1abar\x1aba\x1a");
+/// ```
+pub struct SegmentingWriter<W> {
+    /// The internal writer being written to.
+    inner: W,
+    /// The maximum number of bytes in a segment, excluding the `0x1A` byte.
+    max_segment_len: usize,
+    /// The number of bytes written to the current segment.
+    current_len: usize,
+    /// The number of segments terminated so far.
+    segments: u64,
+}
+
+impl<W> SegmentingWriter<W> {
+    /// Creates a new `SegmentingWriter`, wrapping the provided writer and limiting each segment to
+    /// `max_segment_len` bytes.
+    ///
+    /// # Panics
+    /// Panics if `max_segment_len` is zero.
+    pub fn new(inner: W, max_segment_len: usize) -> Self {
+        assert!(
+            max_segment_len > 0,
+            "maximum segment length must be nonzero"
+        );
+        SegmentingWriter {
+            inner: inner,
+            max_segment_len: max_segment_len,
+            current_len: 0,
+            segments: 0,
+        }
+    }
+
+    /// Returns the number of segments emitted so far.
+    ///
+    /// A segment is counted once its `0x1A` byte has been written.
+    pub fn segments(&self) -> u64 {
+        self.segments
+    }
+
+    /// Returns the number of bytes written to the current segment.
+    pub fn current_segment_len(&self) -> usize {
+        self.current_len
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W> SegmentingWriter<W>
+where
+    W: Write,
+{
+    /// Terminates the final partial segment and flushes the inner writer, returning it.
+    ///
+    /// If nothing has been written since the last segment was terminated, no `0x1A` byte is
+    /// written, so no empty segment is ever emitted.
+    pub fn finish(mut self) -> Result<W> {
+        if self.current_len > 0 {
+            try!(self.terminate());
+        }
+        try!(self.inner.flush());
+        Ok(self.inner)
+    }
+
+    /// Terminates the current segment by writing the `0x1A` byte.
+    fn terminate(&mut self) -> Result<()> {
+        try!(self.inner.write_all(b"\x1a"));
+        self.current_len = 0;
+        self.segments += 1;
+        Ok(())
+    }
+}
+
+impl<W> Write for SegmentingWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.current_len == self.max_segment_len {
+            try!(self.terminate());
+        }
+        let len = cmp::min(buf.len(), self.max_segment_len - self.current_len);
+        let n = try!(self.inner.write(&buf[..len]));
+        self.current_len += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentingWriter;
+    use std::io::Write;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn off_boundary() {
+        let mut writer = SegmentingWriter::new(Vec::new(), 4);
+
+        assert_ok!(writer.write_all(b"foobarbaz"));
+        assert_eq!(writer.segments(), 2);
+        assert_eq!(writer.current_segment_len(), 1);
+        assert_ok_eq!(writer.finish(), b"foob\x1aarba\x1az\x1a");
+    }
+
+    #[test]
+    fn on_boundary() {
+        let mut writer = SegmentingWriter::new(Vec::new(), 3);
+
+        assert_ok!(writer.write_all(b"foobar"));
+        // The second segment is only terminated once it is finished.
+        assert_eq!(writer.segments(), 1);
+        assert_eq!(writer.current_segment_len(), 3);
+        assert_ok_eq!(writer.finish(), b"foo\x1abar\x1a");
+    }
+
+    #[test]
+    fn split_writes() {
+        let mut writer = SegmentingWriter::new(Vec::new(), 4);
+
+        for chunk in [&b"fo"[..], b"ob", b"", b"arb", b"a", b"z"].iter() {
+            assert_ok!(writer.write_all(chunk));
+        }
+
+        assert_ok_eq!(writer.finish(), b"foob\x1aarba\x1az\x1a");
+    }
+
+    #[test]
+    fn nothing_written() {
+        let writer = SegmentingWriter::new(Vec::new(), 4);
+
+        assert_eq!(writer.segments(), 0);
+        assert_ok_eq!(writer.finish(), b"");
+    }
+
+    #[test]
+    fn write_stops_at_boundary() {
+        let mut writer = SegmentingWriter::new(Vec::new(), 4);
+
+        assert_ok_eq!(writer.write(b"foobar"), 4);
+        assert_ok_eq!(writer.write(b"ar"), 2);
+        assert_eq!(writer.get_ref(), b"foob\x1aar");
+    }
+
+    #[test]
+    fn read_back() {
+        let mut writer = SegmentingWriter::new(Vec::new(), 32);
+        let input = (0..100).map(|i| b'a' + (i % 26) as u8).collect::<Vec<_>>();
+
+        assert_ok!(writer.write_all(&input));
+        assert_eq!(writer.current_segment_len(), 4);
+        let output = assert_ok!(writer.finish());
+
+        let segments = ReadToCtrlZ::new(&output[..])
+            .segments()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(segments.len(), 4);
+        assert!(segments.iter().all(|segment| segment.len() <= 32));
+        assert_eq!(segments.concat(), input);
+    }
+
+    #[test]
+    #[should_panic]
+    fn max_segment_len_zero() {
+        SegmentingWriter::new(Vec::<u8>::new(), 0);
+    }
+}
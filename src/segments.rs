@@ -0,0 +1,749 @@
+use find_ctrl_z;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::iter;
+#[cfg(feature = "fused")]
+use std::iter::FusedIterator;
+use std::ops::Deref;
+use ReadToCtrlZ;
+use TerminatorSet;
+
+/// The size of the buffer the inner reader is read into.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// An iterator over the segments of a reader separated by `0x1A` bytes.
+///
+/// Each segment yielded holds the bytes between two consecutive `0x1A` bytes, so consecutive
+/// `0x1A` bytes yield empty segments. The bytes following the final `0x1A` byte are yielded as the
+/// last segment, unless there are none. Segments are split on the reader's
+/// [terminating bytes](crate::ReadToCtrlZ::terminate_on()); lenient mode and quoting are not
+/// applied.
+///
+/// By default every segment is yielded. Empty segments can be suppressed using
+/// [`skip_empty()`](Segments::skip_empty()), the number of segments can be capped using
+/// [`max_segments()`](Segments::max_segments()), and the offset of each segment can be included
+/// using [`with_offsets()`](Segments::with_offsets()).
+///
+/// This type is created using [`ReadToCtrlZ::segments()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let segments = ReadToCtrlZ::new(&b"foo\x1a\x1abar\x1a"[..])
+///     .segments()
+///     .map(|segment| segment.unwrap())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(segments, [&b"foo"[..], b"", b"bar"]);
+/// ```
+///
+/// [`ReadToCtrlZ::segments()`]: crate::ReadToCtrlZ::segments()
+pub struct Segments<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// The bytes separating segments.
+    terminators: TerminatorSet,
+    /// Bytes read from the internal reader.
+    buffer: Vec<u8>,
+    /// The position of the first unexamined byte within `buffer`.
+    pos: usize,
+    /// The number of bytes within `buffer` that were read.
+    len: usize,
+    /// The offset of the first unexamined byte, relative to the start of the first segment.
+    offset: u64,
+    /// Whether or not empty segments are suppressed.
+    skip_empty: bool,
+    /// The number of segments that may still be yielded, if limited.
+    remaining: Option<usize>,
+    /// The maximum number of segments, as set by `max_segments()`.
+    max: usize,
+    /// Whether or not a segment beyond `max` results in an error.
+    error_on_excess: bool,
+    /// Whether or not iteration has finished.
+    finished: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `Segments`, iterating over the segments separated by `0x1A` bytes.
+    ///
+    /// The first segment starts at the first byte this reader has not yet delivered. If reading
+    /// was already terminated through [`BufRead`](std::io::BufRead), the inner reader is
+    /// positioned directly after the terminating byte, so the first segment is the one following
+    /// it.
+    pub fn segments(self) -> Segments<R> {
+        self.segments_with_capacity(BUFFER_SIZE)
+    }
+
+    /// Creates a new `Segments` over `inner`, returning an error once more than `max` segments are
+    /// found.
+    ///
+    /// This guards iteration over malformed input containing many `0x1A` bytes. It is shorthand
+    /// for calling [`segments()`](ReadToCtrlZ::segments()) with
+    /// [`max_segments(max)`](Segments::max_segments()) and
+    /// [`error_on_excess_segments(true)`](Segments::error_on_excess_segments()).
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use std::io::ErrorKind;
+    ///
+    /// let mut segments = ReadToCtrlZ::with_max_segments(&b"foo\x1abar"[..], 1);
+    ///
+    /// assert_eq!(segments.next().unwrap().unwrap(), b"foo");
+    /// let error = segments.next().unwrap().unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    /// assert_eq!(error.to_string(), "more than 1 segments seen");
+    /// ```
+    pub fn with_max_segments(inner: R, max: usize) -> Segments<R> {
+        ReadToCtrlZ::new(inner)
+            .segments()
+            .max_segments(max)
+            .error_on_excess_segments(true)
+    }
+
+    /// Creates a new `Segments` with the specified buffer capacity.
+    ///
+    /// Segments that fit within the buffer can be borrowed from it using
+    /// [`next_segment_borrowed()`](Segments::next_segment_borrowed()), so a larger capacity allows
+    /// larger segments to be yielded without allocating.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn segments_with_capacity(self, capacity: usize) -> Segments<R> {
+        assert!(capacity > 0, "buffer capacity must be nonzero");
+        let mut buffer = self.buffer;
+        let len = buffer.len();
+        if len < capacity {
+            buffer.extend(iter::repeat(0).take(capacity - len));
+        }
+        Segments {
+            inner: self.inner,
+            terminators: self.terminators,
+            buffer: buffer,
+            pos: self.pos,
+            len: len,
+            offset: 0,
+            skip_empty: false,
+            remaining: None,
+            max: 0,
+            error_on_excess: false,
+            finished: false,
+        }
+    }
+}
+
+impl<R> Segments<R> {
+    /// Sets whether segments of length zero are suppressed.
+    ///
+    /// Suppressed segments do not count toward [`max_segments()`](Segments::max_segments()).
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// Limits the number of segments yielded to `max`.
+    ///
+    /// Once `max` segments have been yielded, iteration ends without reading further.
+    pub fn max_segments(mut self, max: usize) -> Self {
+        self.remaining = Some(max);
+        self.max = max;
+        self
+    }
+
+    /// Sets whether a segment beyond [`max_segments()`](Segments::max_segments()) results in an
+    /// error, rather than silently ending iteration.
+    ///
+    /// This guards against malformed input containing far more `0x1A` bytes than expected. Once
+    /// the limit has been reached, the next segment is read, and if there is one, an
+    /// [`ErrorKind::InvalidInput`] error stating the number of segments seen is returned in its
+    /// place. Iteration then ends. If the input ends exactly at the limit, no error is returned.
+    ///
+    /// [`ReadToCtrlZ::with_max_segments()`] creates a `Segments` configured this way.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut segments = ReadToCtrlZ::new(&b"foo\x1abar\x1abaz"[..])
+    ///     .segments()
+    ///     .max_segments(2)
+    ///     .error_on_excess_segments(true);
+    ///
+    /// assert_eq!(segments.next().unwrap().unwrap(), b"foo");
+    /// assert_eq!(segments.next().unwrap().unwrap(), b"bar");
+    /// assert!(segments.next().unwrap().is_err());
+    /// assert!(segments.next().is_none());
+    /// ```
+    ///
+    /// [`ErrorKind::InvalidInput`]: std::io::ErrorKind::InvalidInput
+    /// [`ReadToCtrlZ::with_max_segments()`]: crate::ReadToCtrlZ::with_max_segments()
+    pub fn error_on_excess_segments(mut self, error_on_excess: bool) -> Self {
+        self.error_on_excess = error_on_excess;
+        self
+    }
+
+    /// Creates an iterator yielding each segment along with its offset.
+    pub fn with_offsets(self) -> SegmentOffsets<R> {
+        SegmentOffsets { inner: self }
+    }
+
+    /// Unwraps this `Segments`, returning the underlying reader.
+    ///
+    /// Any bytes that have been buffered but not yet yielded are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Segments<R>
+where
+    R: Read,
+{
+    /// Reads the next segment, borrowing it from the internal buffer if possible.
+    ///
+    /// If the whole segment, including the byte separating it from the next one, is already
+    /// within the internal buffer, it is borrowed as a [`SegmentRef::Borrowed`]. Otherwise, the
+    /// segment spans multiple reads and is collected into a [`SegmentRef::Owned`]. Returns
+    /// `Ok(None)` once iteration has finished. This respects
+    /// [`skip_empty()`](Segments::skip_empty()) and [`max_segments()`](Segments::max_segments()),
+    /// and can be interleaved with calls to [`next()`](Iterator::next()).
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    ///
+    /// let mut segments = ReadToCtrlZ::new(&b"foo\x1abar"[..]).segments();
+    ///
+    /// let first = segments.next_segment_borrowed().unwrap().unwrap();
+    /// assert!(first.is_borrowed());
+    /// assert_eq!(&*first, b"foo");
+    /// ```
+    ///
+    /// [`SegmentRef::Borrowed`]: crate::SegmentRef::Borrowed
+    /// [`SegmentRef::Owned`]: crate::SegmentRef::Owned
+    pub fn next_segment_borrowed<'a>(&'a mut self) -> Result<Option<SegmentRef<'a>>> {
+        loop {
+            if !self.finished && self.remaining == Some(0) && self.error_on_excess {
+                try!(self.check_excess());
+            }
+            if self.finished || self.remaining == Some(0) {
+                return Ok(None);
+            }
+            if self.pos == self.len && !try!(self.fill()) {
+                self.finished = true;
+                return Ok(None);
+            }
+
+            let start = self.pos;
+            let end = match find(self.terminators, &self.buffer[start..self.len]) {
+                Some(i) => start + i,
+                None => {
+                    // The segment spans multiple reads, so it must be collected.
+                    return match self.next_segment() {
+                        Some(Ok((_, segment))) => Ok(Some(SegmentRef::Owned(segment))),
+                        Some(Err(error)) => Err(error),
+                        None => Ok(None),
+                    };
+                }
+            };
+            self.pos = end + 1;
+            self.offset += (end + 1 - start) as u64;
+            if self.skip_empty && end == start {
+                continue;
+            }
+            if let Some(ref mut remaining) = self.remaining {
+                *remaining -= 1;
+            }
+            return Ok(Some(SegmentRef::Borrowed(&self.buffer[start..end])));
+        }
+    }
+
+    /// Reads from the internal reader into the buffer, returning whether any bytes were read.
+    fn fill(&mut self) -> Result<bool> {
+        loop {
+            match self.inner.read(&mut self.buffer) {
+                Ok(n) => {
+                    self.pos = 0;
+                    self.len = n;
+                    return Ok(n > 0);
+                }
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Reads past the limit set by `max_segments()`, returning an error if another segment follows.
+    ///
+    /// Iteration is finished afterward.
+    fn check_excess(&mut self) -> Result<()> {
+        self.remaining = None;
+        let next = self.next_segment();
+        self.remaining = Some(0);
+        self.finished = true;
+        match next {
+            // The segments past the limit are not counted, since malformed input may hold an
+            // unbounded number of them.
+            Some(Ok(_)) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("more than {} segments seen", self.max),
+            )),
+            Some(Err(error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the next segment, returning it along with its offset.
+    fn next_segment(&mut self) -> Option<Result<(u64, Vec<u8>)>> {
+        loop {
+            if !self.finished && self.remaining == Some(0) && self.error_on_excess {
+                if let Err(error) = self.check_excess() {
+                    return Some(Err(error));
+                }
+            }
+            if self.finished || self.remaining == Some(0) {
+                return None;
+            }
+
+            let start = self.offset;
+            let mut segment = Vec::new();
+            let mut separated = false;
+            while !separated {
+                if self.pos == self.len {
+                    match self.fill() {
+                        Ok(true) => {}
+                        Ok(false) => break,
+                        Err(error) => {
+                            self.finished = true;
+                            return Some(Err(error));
+                        }
+                    }
+                }
+
+                let rest = &self.buffer[self.pos..self.len];
+                let found = find(self.terminators, rest);
+                let end = found.unwrap_or(rest.len());
+                segment.extend(rest[..end].iter().cloned());
+                // The separating byte is consumed along with the segment.
+                let consumed = if found.is_some() { end + 1 } else { end };
+                self.pos += consumed;
+                self.offset += consumed as u64;
+                separated = found.is_some();
+            }
+
+            if !separated {
+                self.finished = true;
+                if segment.is_empty() {
+                    return None;
+                }
+            }
+            if self.skip_empty && segment.is_empty() {
+                continue;
+            }
+            if let Some(ref mut remaining) = self.remaining {
+                *remaining -= 1;
+            }
+            return Some(Ok((start, segment)));
+        }
+    }
+}
+
+impl<R> Iterator for Segments<R>
+where
+    R: Read,
+{
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_segment()
+            .map(|result| result.map(|(_, segment)| segment))
+    }
+}
+
+#[cfg(feature = "fused")]
+#[allow(clippy::incompatible_msrv)]
+impl<R> FusedIterator for Segments<R> where R: Read {}
+
+/// Returns the position of the first byte of `bytes` within `terminators`, if any.
+fn find(terminators: TerminatorSet, bytes: &[u8]) -> Option<usize> {
+    if terminators == TerminatorSet::ctrl_z() {
+        find_ctrl_z(bytes)
+    } else {
+        bytes.iter().position(|&byte| terminators.contains(byte))
+    }
+}
+
+/// A segment returned by [`Segments::next_segment_borrowed()`].
+///
+/// Dereferences to the bytes of the segment, regardless of whether they are borrowed.
+///
+/// [`Segments::next_segment_borrowed()`]: crate::Segments::next_segment_borrowed()
+#[derive(Debug, Eq, PartialEq)]
+pub enum SegmentRef<'a> {
+    /// The segment was borrowed from the internal buffer, without copying.
+    Borrowed(&'a [u8]),
+    /// The segment spanned multiple reads, so it was collected into an owned buffer.
+    Owned(Vec<u8>),
+}
+
+impl<'a> SegmentRef<'a> {
+    /// Returns whether the segment was borrowed from the internal buffer.
+    pub fn is_borrowed(&self) -> bool {
+        match *self {
+            SegmentRef::Borrowed(_) => true,
+            SegmentRef::Owned(_) => false,
+        }
+    }
+
+    /// Converts the segment into an owned buffer, copying it if it was borrowed.
+    pub fn into_owned(self) -> Vec<u8> {
+        match self {
+            SegmentRef::Borrowed(bytes) => bytes.to_vec(),
+            SegmentRef::Owned(bytes) => bytes,
+        }
+    }
+}
+
+impl<'a> Deref for SegmentRef<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match *self {
+            SegmentRef::Borrowed(bytes) => bytes,
+            SegmentRef::Owned(ref bytes) => bytes,
+        }
+    }
+}
+
+/// An iterator over the segments of a reader separated by `0x1A` bytes, along with their offsets.
+///
+/// Each item is the offset of the segment's first byte, relative to the start of the first
+/// segment, along with the segment itself.
+///
+/// This type is created using [`Segments::with_offsets()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let segments = ReadToCtrlZ::new(&b"foo\x1a\x1abar"[..])
+///     .segments()
+///     .skip_empty(true)
+///     .with_offsets()
+///     .map(|segment| segment.unwrap())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(segments, [(0, b"foo".to_vec()), (5, b"bar".to_vec())]);
+/// ```
+///
+/// [`Segments::with_offsets()`]: crate::Segments::with_offsets()
+pub struct SegmentOffsets<R> {
+    /// The segments being iterated over.
+    inner: Segments<R>,
+}
+
+impl<R> SegmentOffsets<R> {
+    /// Unwraps this `SegmentOffsets`, returning the underlying `Segments`.
+    pub fn into_inner(self) -> Segments<R> {
+        self.inner
+    }
+}
+
+impl<R> Iterator for SegmentOffsets<R>
+where
+    R: Read,
+{
+    type Item = Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_segment()
+    }
+}
+
+#[cfg(feature = "fused")]
+#[allow(clippy::incompatible_msrv)]
+impl<R> FusedIterator for SegmentOffsets<R> where R: Read {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use ClosureReader;
+    use ReadToCtrlZ;
+    use TerminatorSet;
+
+    #[cfg(feature = "fused")]
+    assert_impl_all!(super::Segments<&'static [u8]>: std::iter::FusedIterator);
+    #[cfg(feature = "fused")]
+    assert_impl_all!(super::SegmentOffsets<&'static [u8]>: std::iter::FusedIterator);
+
+    /// Collects the segments of `input`, suppressing empty segments if `skip_empty` is set.
+    fn segments(input: &[u8], skip_empty: bool) -> Vec<Vec<u8>> {
+        assert_ok!(ReadToCtrlZ::new(input)
+            .segments()
+            .skip_empty(skip_empty)
+            .collect::<Result<Vec<_>, _>>())
+    }
+
+    #[test]
+    fn separated() {
+        assert_eq!(
+            segments(b"foo\x1abar\x1abaz", false),
+            [b"foo", b"bar", b"baz"]
+        );
+    }
+
+    #[test]
+    fn trailing_ctrl_z() {
+        assert_eq!(segments(b"foo\x1abar\x1a", false), [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(segments(b"", false).is_empty());
+    }
+
+    #[test]
+    fn leading_run() {
+        assert_eq!(segments(b"\x1a\x1afoo", false), [&b""[..], b"", b"foo"]);
+        assert_eq!(segments(b"\x1a\x1afoo", true), [b"foo"]);
+    }
+
+    #[test]
+    fn trailing_run() {
+        assert_eq!(segments(b"foo\x1a\x1a\x1a", false), [&b"foo"[..], b"", b""]);
+        assert_eq!(segments(b"foo\x1a\x1a\x1a", true), [b"foo"]);
+    }
+
+    #[test]
+    fn interior_run() {
+        assert_eq!(
+            segments(b"foo\x1a\x1a\x1abar", false),
+            [&b"foo"[..], b"", b"", b"bar"]
+        );
+        assert_eq!(segments(b"foo\x1a\x1a\x1abar", true), [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn max_segments() {
+        let segments = assert_ok!(ReadToCtrlZ::new(b"\x1afoo\x1abar\x1abaz" as &[u8])
+            .segments()
+            .skip_empty(true)
+            .max_segments(2)
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn max_segments_does_not_read_further() {
+        let mut reads = 0;
+        let mut segments = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            reads += 1;
+            buf[..4].clone_from_slice(b"foo\x1a");
+            Ok(4)
+        }))
+        .segments()
+        .max_segments(1);
+
+        assert_ok_eq!(assert_some!(segments.next()), b"foo");
+        assert_none!(segments.next());
+        drop(segments);
+        assert_eq!(reads, 1);
+    }
+
+    #[test]
+    fn error_on_excess_segments() {
+        let mut segments = ReadToCtrlZ::new(b"foo\x1abar\x1abaz\x1aqux" as &[u8])
+            .segments()
+            .max_segments(2)
+            .error_on_excess_segments(true);
+
+        assert_ok_eq!(assert_some!(segments.next()), b"foo");
+        assert_ok_eq!(assert_some!(segments.next()), b"bar");
+        let error = assert_err!(assert_some!(segments.next()));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "more than 2 segments seen");
+        assert_none!(segments.next());
+    }
+
+    #[test]
+    fn with_max_segments() {
+        let mut segments = ReadToCtrlZ::with_max_segments(b"foo\x1a\x1a\x1a\x1a" as &[u8], 2);
+
+        assert_ok_eq!(assert_some!(segments.next()), b"foo");
+        assert_ok_eq!(assert_some!(segments.next()), b"");
+        let error = assert_err!(assert_some!(segments.next()));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "more than 2 segments seen");
+        assert_none!(segments.next());
+    }
+
+    #[test]
+    fn with_max_segments_at_limit() {
+        let segments = assert_ok!(ReadToCtrlZ::with_max_segments(b"foo\x1abar" as &[u8], 2)
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn error_on_excess_segments_at_limit() {
+        let segments = assert_ok!(ReadToCtrlZ::new(b"foo\x1abar\x1a" as &[u8])
+            .segments()
+            .max_segments(2)
+            .error_on_excess_segments(true)
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"foo", b"bar"]);
+    }
+
+    #[test]
+    fn error_on_excess_segments_ignores_skipped() {
+        let segments = assert_ok!(ReadToCtrlZ::new(b"foo\x1a\x1a\x1a" as &[u8])
+            .segments()
+            .skip_empty(true)
+            .max_segments(1)
+            .error_on_excess_segments(true)
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"foo"]);
+    }
+
+    #[test]
+    fn error_on_excess_segments_borrowed() {
+        let mut segments = ReadToCtrlZ::new(b"foo\x1a\x1a" as &[u8])
+            .segments()
+            .max_segments(1)
+            .error_on_excess_segments(true);
+
+        assert_eq!(
+            &*assert_some!(assert_ok!(segments.next_segment_borrowed())),
+            b"foo"
+        );
+        let error = assert_err!(segments.next_segment_borrowed());
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_none!(assert_ok!(segments.next_segment_borrowed()));
+    }
+
+    #[test]
+    fn with_offsets() {
+        let segments = assert_ok!(ReadToCtrlZ::new(b"foo\x1a\x1abar\x1a" as &[u8])
+            .segments()
+            .with_offsets()
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(
+            segments,
+            [(0, b"foo".to_vec()), (4, Vec::new()), (5, b"bar".to_vec())]
+        );
+    }
+
+    #[test]
+    fn longer_than_buffer() {
+        let mut input = vec![b'a'; 2 * super::BUFFER_SIZE + 1];
+        input.push(b'\x1a');
+        input.push(b'b');
+
+        let segments = segments(&input, false);
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].len(), 2 * super::BUFFER_SIZE + 1);
+        assert_eq!(segments[1], b"b");
+    }
+
+    #[test]
+    fn terminators() {
+        let mut terminators = TerminatorSet::ctrl_z();
+        terminators.insert(b'\x04');
+        let segments = assert_ok!(ReadToCtrlZ::new(b"foo\x04bar\x1abaz" as &[u8])
+            .terminate_on(terminators)
+            .segments()
+            .collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"foo", b"bar", b"baz"]);
+    }
+
+    #[test]
+    fn after_first_segment() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar\x1abaz" as &[u8]);
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+
+        let segments = assert_ok!(reader.segments().collect::<Result<Vec<_>, _>>());
+
+        assert_eq!(segments, [b"bar", b"baz"]);
+    }
+
+    #[test]
+    fn error() {
+        let mut segments = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }))
+        .segments();
+
+        assert_eq!(
+            assert_err!(assert_some!(segments.next())).kind(),
+            ErrorKind::Other
+        );
+        assert_none!(segments.next());
+    }
+
+    #[test]
+    fn borrowed_with_large_capacity() {
+        let mut segments =
+            ReadToCtrlZ::new(b"foo\x1a\x1abar\x1abaz" as &[u8]).segments_with_capacity(64);
+
+        let first = assert_some!(assert_ok!(segments.next_segment_borrowed()));
+        assert!(first.is_borrowed());
+        assert_eq!(&*first, b"foo");
+        let second = assert_some!(assert_ok!(segments.next_segment_borrowed()));
+        assert!(second.is_borrowed());
+        assert!(second.is_empty());
+        let third = assert_some!(assert_ok!(segments.next_segment_borrowed()));
+        assert!(third.is_borrowed());
+        assert_eq!(&*third, b"bar");
+        // The final segment is not followed by a separating byte, so the end of the input must be
+        // read to find its end.
+        let fourth = assert_some!(assert_ok!(segments.next_segment_borrowed()));
+        assert!(!fourth.is_borrowed());
+        assert_eq!(fourth.into_owned(), b"baz");
+        assert_none!(assert_ok!(segments.next_segment_borrowed()));
+    }
+
+    #[test]
+    fn owned_when_spanning_reads() {
+        let mut segments =
+            ReadToCtrlZ::new(b"foobar\x1abaz\x1a" as &[u8]).segments_with_capacity(4);
+
+        let first = assert_some!(assert_ok!(segments.next_segment_borrowed()));
+        assert!(!first.is_borrowed());
+        assert_eq!(&*first, b"foobar");
+    }
+
+    #[test]
+    fn borrowed_skip_empty_and_max_segments() {
+        let mut segments = ReadToCtrlZ::new(b"\x1afoo\x1a\x1abar\x1abaz\x1a" as &[u8])
+            .segments()
+            .skip_empty(true)
+            .max_segments(2);
+
+        assert_eq!(
+            assert_some!(assert_ok!(segments.next_segment_borrowed())).into_owned(),
+            b"foo"
+        );
+        assert_ok_eq!(assert_some!(segments.next()), b"bar");
+        assert_none!(assert_ok!(segments.next_segment_borrowed()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn capacity_zero() {
+        ReadToCtrlZ::new(b"" as &[u8]).segments_with_capacity(0);
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// A reader to read until a `0x1A` byte through a shared reference.
+///
+/// This is a variant of [`ReadToCtrlZ`] for inner readers that can themselves be read through a
+/// shared reference, such as [`File`](std::fs::File), for which `&File` implements [`Read`]. Its
+/// state is stored in atomics, so `&SharedReadToCtrlZ<R>` implements [`Read`] as well, allowing
+/// multiple threads to take turns reading.
+///
+/// Interleaved readers share a single logical cursor, just as they do when reading from a shared
+/// `&File` directly: each read continues where the previous read, on any thread, stopped. Once any
+/// read encounters the `0x1A` byte, all subsequent reads return `Ok(0)`. Note that a read
+/// performed concurrently with the read that encounters the `0x1A` byte may already have read
+/// bytes following it.
+///
+/// # Example
+/// ```no_run
+/// use ctrl_z::SharedReadToCtrlZ;
+/// use std::fs::File;
+/// use std::io::Read;
+///
+/// let reader = SharedReadToCtrlZ::new(File::open("foo.txt").unwrap());
+/// let mut output = String::new();
+///
+/// // Reading is done through a shared reference.
+/// (&reader).read_to_string(&mut output).unwrap();
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct SharedReadToCtrlZ<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// Whether or not the EOF `0x1A` byte has been reached.
+    terminated: AtomicBool,
+    /// The number of bytes delivered to callers.
+    delivered: AtomicUsize,
+}
+
+impl<R> SharedReadToCtrlZ<R> {
+    /// Creates a new `SharedReadToCtrlZ`, wrapping the provided reader.
+    pub fn new(inner: R) -> Self {
+        SharedReadToCtrlZ {
+            inner: inner,
+            terminated: AtomicBool::new(false),
+            delivered: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns whether the `0x1A` byte has been reached by any reader.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of bytes delivered to all readers combined.
+    pub fn bytes_delivered(&self) -> u64 {
+        self.delivered.load(Ordering::SeqCst) as u64
+    }
+
+    /// Unwraps this `SharedReadToCtrlZ`, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<'a, R> Read for &'a SharedReadToCtrlZ<R>
+where
+    &'a R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // A zero-length read is not an indication of EOF, so the inner reader is not consulted.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.terminated.load(Ordering::SeqCst) {
+            return Ok(0);
+        }
+
+        let n = try!((&self.inner).read(buf));
+        if n > buf.len() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "buffer smaller than amount of bytes read",
+            ));
+        }
+        let len = match buf[..n].iter().position(|&byte| byte == b'\x1a') {
+            Some(i) => {
+                self.terminated.store(true, Ordering::SeqCst);
+                i
+            }
+            None => n,
+        };
+        self.delivered.fetch_add(len, Ordering::SeqCst);
+        Ok(len)
+    }
+}
+
+impl<R> Read for SharedReadToCtrlZ<R>
+where
+    for<'a> &'a R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedReadToCtrlZ;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::Read;
+    use std::io::Write;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn read_file() {
+        let path = env::temp_dir().join("ctrl_z_shared_read_file");
+        assert_ok!(assert_ok!(File::create(&path)).write_all(b"foo\x1abar"));
+        let mut reader = SharedReadToCtrlZ::new(assert_ok!(File::open(&path)));
+        let mut output = String::new();
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.is_terminated());
+        assert_eq!(reader.bytes_delivered(), 3);
+
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn read_file_from_threads() {
+        let path = env::temp_dir().join("ctrl_z_shared_read_file_from_threads");
+        let mut contents = vec![b'a'; 10000];
+        contents.push(b'\x1a');
+        assert_ok!(assert_ok!(File::create(&path)).write_all(&contents));
+        let reader = Arc::new(SharedReadToCtrlZ::new(assert_ok!(File::open(&path))));
+
+        let threads = (0..2)
+            .map(|_| {
+                let reader = reader.clone();
+                thread::spawn(move || {
+                    let mut output = Vec::new();
+                    let mut buf = [0; 16];
+                    loop {
+                        let n = (&*reader).read(&mut buf).unwrap();
+                        if n == 0 {
+                            return output;
+                        }
+                        output.extend(buf[..n].iter().cloned());
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut total = 0;
+        for thread in threads {
+            let output = assert_ok!(thread.join());
+            assert!(output.iter().all(|&byte| byte == b'a'));
+            total += output.len();
+        }
+
+        // Both threads shared a single cursor.
+        assert_eq!(total, 10000);
+        assert_eq!(reader.bytes_delivered(), 10000);
+        assert!(reader.is_terminated());
+
+        assert_ok!(fs::remove_file(&path));
+    }
+}
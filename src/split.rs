@@ -0,0 +1,166 @@
+use std::io::Read;
+use std::io::Result;
+use std::io::Write;
+use ReadToCtrlZ;
+
+/// A reader to read until a `0x1A` byte, copying every byte read to a writer, which can also be
+/// written to directly.
+///
+/// Each call to [`read()`](Read::read()) reads from the inner reader until the `0x1A` byte, as
+/// with [`ReadToCtrlZ`], and writes the bytes returned to the caller to the tee writer before
+/// returning. The `0x1A` byte and anything following it are never written. Calls to
+/// [`write()`](Write::write()) are forwarded to the tee writer unchanged, so additional bytes,
+/// such as headers, can be interleaved with the copied bytes.
+///
+/// # Example
+/// ```
+/// use ctrl_z::SplitReadWriteToCtrlZ;
+/// use std::io::Read;
+/// use std::io::Write;
+///
+/// let mut split = SplitReadWriteToCtrlZ::new(&b"foo\x1abar"[..], Vec::new());
+/// let mut output = String::new();
+///
+/// assert!(split.write_all(b"> ").is_ok());
+/// assert!(split.read_to_string(&mut output).is_ok());
+///
+/// assert_eq!(output, "foo");
+/// assert_eq!(split.into_parts().1, b"> foo");
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub struct SplitReadWriteToCtrlZ<R, W> {
+    /// The reader being read until the `0x1A` byte.
+    reader: ReadToCtrlZ<R>,
+    /// The writer receiving every byte read, as well as every byte written.
+    tee: W,
+}
+
+impl<R, W> SplitReadWriteToCtrlZ<R, W> {
+    /// Creates a new `SplitReadWriteToCtrlZ`, reading from `inner` and copying to `tee`.
+    pub fn new(inner: R, tee: W) -> Self {
+        SplitReadWriteToCtrlZ {
+            reader: ReadToCtrlZ::new(inner),
+            tee: tee,
+        }
+    }
+
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn reader(&self) -> &ReadToCtrlZ<R> {
+        &self.reader
+    }
+
+    /// Returns a reference to the tee writer.
+    pub fn writer(&self) -> &W {
+        &self.tee
+    }
+
+    /// Returns a mutable reference to the tee writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.tee
+    }
+
+    /// Unwraps this `SplitReadWriteToCtrlZ`, returning the underlying `ReadToCtrlZ` and the tee
+    /// writer.
+    pub fn into_parts(self) -> (ReadToCtrlZ<R>, W) {
+        (self.reader, self.tee)
+    }
+}
+
+impl<R, W> Read for SplitReadWriteToCtrlZ<R, W>
+where
+    R: Read,
+    W: Write,
+{
+    /// Reads bytes preceding the `0x1A` byte into `buf`, writing them to the tee writer.
+    ///
+    /// If writing to the tee writer fails, the error is returned even though the bytes were
+    /// already read into `buf`.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = try!(self.reader.read(buf));
+        try!(self.tee.write_all(&buf[..n]));
+        Ok(n)
+    }
+}
+
+impl<R, W> Write for SplitReadWriteToCtrlZ<R, W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.tee.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.tee.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SplitReadWriteToCtrlZ;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Result;
+    use std::io::Write;
+
+    /// A writer that always fails.
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn read_tees() {
+        let mut split = SplitReadWriteToCtrlZ::new(b"foo\x1abar" as &[u8], Vec::new());
+        let mut output = Vec::new();
+
+        assert_ok_eq!(split.read_to_end(&mut output), 3);
+
+        assert_eq!(output, b"foo");
+        assert_eq!(split.writer(), b"foo");
+        assert!(split.reader().is_terminated());
+    }
+
+    #[test]
+    fn read_partial() {
+        let mut split = SplitReadWriteToCtrlZ::new(b"foobar\x1abaz" as &[u8], Vec::new());
+        let mut output = [0; 2];
+
+        assert_ok_eq!(split.read(&mut output), 2);
+
+        assert_eq!(split.writer(), b"fo");
+    }
+
+    #[test]
+    fn write_interleaved() {
+        let mut split = SplitReadWriteToCtrlZ::new(b"foo\x1abar" as &[u8], Vec::new());
+        let mut output = [0; 2];
+
+        assert_ok!(split.write_all(b"<"));
+        assert_ok_eq!(split.read(&mut output), 2);
+        assert_ok!(split.write_all(b"|"));
+        assert_ok_eq!(split.read(&mut output), 1);
+        assert_ok_eq!(split.read(&mut output), 0);
+        assert_ok!(split.write_all(b">"));
+        assert_ok!(split.flush());
+
+        assert_eq!(split.into_parts().1, b"<fo|o>");
+    }
+
+    #[test]
+    fn read_tee_error() {
+        let mut split = SplitReadWriteToCtrlZ::new(b"foo\x1abar" as &[u8], FailingWriter);
+
+        let error = assert_err!(split.read(&mut [0; 4]));
+        assert_eq!(error.kind(), ErrorKind::Other);
+    }
+}
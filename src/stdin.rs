@@ -0,0 +1,74 @@
+use std::io::Stdin;
+use std::io::StdinLock;
+use ReadToCtrlZ;
+
+/// Locks standard input, wrapping the lock in a [`ReadToCtrlZ`].
+///
+/// The returned reader implements both [`Read`](std::io::Read) and
+/// [`BufRead`](std::io::BufRead), reading directly from the lock's buffer. Standard input remains
+/// locked until the reader is dropped. The lock borrows the provided [`Stdin`] handle, which must
+/// therefore outlive the returned reader.
+///
+/// The returned reader is not [`Send`], since the lock must be released on the thread that
+/// acquired it. To read standard input on another thread, wrap an unlocked [`Stdin`] handle
+/// instead. A `ReadToCtrlZ<Stdin>` is both [`Send`] and [`Sync`], at the cost of locking standard
+/// input on every read:
+///
+/// ```no_run
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io;
+/// use std::io::Read;
+/// use std::thread;
+///
+/// let mut reader = ReadToCtrlZ::new(io::stdin());
+/// let handle = thread::spawn(move || {
+///     let mut output = String::new();
+///     reader.read_to_string(&mut output).unwrap();
+///     output
+/// });
+/// let output = handle.join().unwrap();
+/// ```
+///
+/// # Example
+/// ```no_run
+/// use std::io;
+/// use std::io::BufRead;
+///
+/// let stdin = io::stdin();
+/// for line in ctrl_z::read_ctrl_z_from_stdin(&stdin).lines() {
+///     println!("{}", line.unwrap());
+/// }
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+pub fn read_ctrl_z_from_stdin<'a>(stdin: &'a Stdin) -> ReadToCtrlZ<StdinLock<'a>> {
+    ReadToCtrlZ::new(stdin.lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_ctrl_z_from_stdin;
+    use std::io;
+    use std::io::BufRead;
+    use std::io::Stdin;
+    use std::io::StdinLock;
+    use ReadToCtrlZ;
+
+    assert_impl_all!(ReadToCtrlZ<Stdin>: Send, Sync);
+    assert_not_impl_any!(ReadToCtrlZ<StdinLock<'static>>: Send);
+
+    fn assert_buf_read<R>(_: &R)
+    where
+        R: BufRead,
+    {
+    }
+
+    #[test]
+    fn lock_stdin() {
+        let stdin = io::stdin();
+        let reader = read_ctrl_z_from_stdin(&stdin);
+
+        assert_buf_read(&reader);
+        assert_none!(reader.terminator());
+    }
+}
@@ -0,0 +1,216 @@
+// `Pin`, `Context`, and the `Stream` trait require a newer Rust version, as do the `bytes` and
+// `futures-core` crates themselves.
+#![allow(clippy::incompatible_msrv)]
+
+use bytes_crate::Bytes;
+use find_ctrl_z;
+use futures_core::task::Context;
+use futures_core::task::Poll;
+use futures_core::Stream;
+use std::pin::Pin;
+
+/// A stream of [`Bytes`] chunks, ending at the first `0x1A` byte.
+///
+/// This wraps any stream of `Result<Bytes, E>`, such as the body of an HTTP response, yielding
+/// each chunk unchanged until the chunk containing the `0x1A` byte. That chunk is truncated to the
+/// bytes preceding the `0x1A` byte, and the stream ends after it. If the `0x1A` byte is the first
+/// byte of its chunk, the stream ends without yielding an empty chunk. Errors from the inner
+/// stream are passed through unchanged.
+///
+/// The bytes of the final chunk following the `0x1A` byte are retained and can be retrieved using
+/// [`remainder()`](CtrlZByteStream::remainder()). Chunks following the final chunk are never
+/// polled from the inner stream.
+///
+/// This type is only available with the `stream` feature enabled, which requires Rust 1.39 or
+/// later.
+///
+/// # Example
+/// ```
+/// # extern crate bytes;
+/// # extern crate ctrl_z;
+/// # extern crate futures;
+/// use bytes::Bytes;
+/// use ctrl_z::CtrlZByteStream;
+/// use futures::executor::block_on_stream;
+/// use futures::stream;
+///
+/// # fn main() {
+/// let chunks = vec![
+///     Ok::<_, ()>(Bytes::from_static(b"foo")),
+///     Ok(Bytes::from_static(b"bar\x1abaz")),
+/// ];
+/// let mut stream = CtrlZByteStream::new(stream::iter(chunks));
+///
+/// let output = block_on_stream(&mut stream)
+///     .map(Result::unwrap)
+///     .collect::<Vec<_>>();
+/// assert_eq!(output, [&b"foo"[..], &b"bar"[..]]);
+/// assert!(stream.is_terminated());
+/// assert_eq!(stream.remainder().unwrap(), &b"baz"[..]);
+/// # }
+/// ```
+///
+/// [`Bytes`]: https://docs.rs/bytes/latest/bytes/struct.Bytes.html
+pub struct CtrlZByteStream<S> {
+    /// The stream of chunks being read.
+    inner: S,
+    /// The bytes of the final chunk following the `0x1A` byte, once it has been found.
+    remainder: Option<Bytes>,
+    /// Whether or not the stream has ended.
+    finished: bool,
+}
+
+impl<S> CtrlZByteStream<S> {
+    /// Creates a new `CtrlZByteStream`, wrapping the provided stream.
+    pub fn new(inner: S) -> Self {
+        CtrlZByteStream {
+            inner: inner,
+            remainder: None,
+            finished: false,
+        }
+    }
+
+    /// Returns whether the `0x1A` byte has been found.
+    pub fn is_terminated(&self) -> bool {
+        self.remainder.is_some()
+    }
+
+    /// Returns the bytes of the final chunk following the `0x1A` byte.
+    ///
+    /// Returns `None` if the `0x1A` byte has not been found.
+    pub fn remainder(&self) -> Option<&Bytes> {
+        self.remainder.as_ref()
+    }
+
+    /// Returns a reference to the inner stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwraps this `CtrlZByteStream`, returning the inner stream and the remainder of the final
+    /// chunk, if the `0x1A` byte was found.
+    pub fn into_parts(self) -> (S, Option<Bytes>) {
+        (self.inner, self.remainder)
+    }
+}
+
+impl<S, E> Stream for CtrlZByteStream<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => match find_ctrl_z(&chunk) {
+                Some(index) => {
+                    self.finished = true;
+                    self.remainder = Some(chunk.slice(index + 1..));
+                    if index == 0 {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Ready(Some(Ok(chunk.slice(..index))))
+                    }
+                }
+                None => Poll::Ready(Some(Ok(chunk))),
+            },
+            Poll::Ready(None) => {
+                self.finished = true;
+                Poll::Ready(None)
+            }
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CtrlZByteStream;
+    use bytes_crate::Bytes;
+    use futures::executor::block_on_stream;
+    use futures::stream;
+
+    /// Collects the chunks of a `CtrlZByteStream` over the given chunks.
+    fn collect(chunks: Vec<&'static [u8]>) -> (Vec<Bytes>, Option<Bytes>) {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| Ok::<_, ()>(Bytes::from_static(chunk)))
+            .collect::<Vec<_>>();
+        let mut stream = CtrlZByteStream::new(stream::iter(chunks));
+        let output = block_on_stream(&mut stream).map(Result::unwrap).collect();
+        (output, stream.into_parts().1)
+    }
+
+    #[test]
+    fn no_ctrl_z() {
+        let (output, remainder) = collect(vec![b"foo", b"bar"]);
+
+        assert_eq!(output, [&b"foo"[..], &b"bar"[..]]);
+        assert_none!(remainder);
+    }
+
+    #[test]
+    fn ctrl_z_mid_chunk() {
+        let (output, remainder) = collect(vec![b"foo", b"bar\x1abaz", b"qux"]);
+
+        assert_eq!(output, [&b"foo"[..], &b"bar"[..]]);
+        assert_some_eq!(remainder, &b"baz"[..]);
+    }
+
+    #[test]
+    fn ctrl_z_at_chunk_start() {
+        let (output, remainder) = collect(vec![b"foo", b"\x1abar"]);
+
+        assert_eq!(output, [&b"foo"[..]]);
+        assert_some_eq!(remainder, &b"bar"[..]);
+    }
+
+    #[test]
+    fn ctrl_z_at_chunk_end() {
+        let (output, remainder) = collect(vec![b"foo\x1a", b"bar"]);
+
+        assert_eq!(output, [&b"foo"[..]]);
+        assert_some_eq!(remainder, &b""[..]);
+    }
+
+    #[test]
+    fn every_chunking() {
+        let input: &'static [u8] = b"foobar\x1abaz\x1aqux";
+        for first in 0..input.len() + 1 {
+            for second in first..input.len() + 1 {
+                let (output, remainder) = collect(vec![
+                    &input[..first],
+                    &input[first..second],
+                    &input[second..],
+                ]);
+
+                let concatenated = output.iter().fold(Vec::new(), |mut acc, chunk| {
+                    acc.extend(chunk.iter().cloned());
+                    acc
+                });
+                assert_eq!(concatenated, b"foobar");
+                // The remainder is taken from whichever chunk contains the first `0x1A` byte.
+                let expected_remainder = if first > 6 {
+                    &input[7..first]
+                } else if second > 6 {
+                    &input[7..second]
+                } else {
+                    &input[7..]
+                };
+                assert_some_eq!(remainder, expected_remainder);
+            }
+        }
+    }
+
+    #[test]
+    fn error() {
+        let chunks = vec![Ok(Bytes::from_static(b"foo")), Err("error")];
+        let mut stream = block_on_stream(CtrlZByteStream::new(stream::iter(chunks)));
+
+        assert_ok_eq!(assert_some!(stream.next()), Bytes::from_static(b"foo"));
+        assert_eq!(assert_err!(assert_some!(stream.next())), "error");
+    }
+}
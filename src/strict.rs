@@ -0,0 +1,323 @@
+use is_padding;
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::result;
+use ReadToCtrlZ;
+
+/// An error returned by [`StrictReadToCtrlZ::read_strict()`].
+///
+/// [`StrictReadToCtrlZ::read_strict()`]: crate::StrictReadToCtrlZ::read_strict()
+#[derive(Debug)]
+pub enum ReadError {
+    /// An error occurred while reading from the inner reader.
+    Io(io::Error),
+    /// A `0x1A` byte is directly followed by more data, so it is embedded within the data rather
+    /// than marking its end.
+    UnexpectedCtrlZ {
+        /// The offset of the `0x1A` byte within the delivered bytes.
+        offset: u64,
+    },
+    /// The `0x1A` byte is followed by padding, but the padding is followed by more data.
+    DataAfterCtrlZ,
+}
+
+impl Display for ReadError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            ReadError::Io(ref error) => write!(formatter, "{}", error),
+            ReadError::UnexpectedCtrlZ { offset } => {
+                write!(formatter, "unexpected 0x1A byte at byte {}", offset)
+            }
+            ReadError::DataAfterCtrlZ => write!(formatter, "data after 0x1A byte"),
+        }
+    }
+}
+
+impl error::Error for ReadError {
+    fn description(&self) -> &str {
+        match *self {
+            ReadError::Io(_) => "I/O error",
+            ReadError::UnexpectedCtrlZ { .. } => "unexpected 0x1A byte",
+            ReadError::DataAfterCtrlZ => "data after 0x1A byte",
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+impl From<ReadError> for io::Error {
+    /// Converts a `ReadError` into an `io::Error`.
+    ///
+    /// [`ReadError::Io`] is unwrapped, while the other variants become errors of kind
+    /// [`InvalidInput`](ErrorKind::InvalidInput) wrapping the `ReadError`.
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Io(error) => error,
+            error => io::Error::new(ErrorKind::InvalidInput, error),
+        }
+    }
+}
+
+/// A reader to read until a `0x1A` byte, requiring that only padding follows it.
+///
+/// A `0x1A` byte only terminates reading if everything following it is whitespace or `\0`
+/// padding, as in [lenient](crate::ReadToCtrlZ::lenient()) mode. Any other `0x1A` byte is reported
+/// as an error, rather than being read as a regular byte: [`ReadError::UnexpectedCtrlZ`] if data
+/// directly follows it, and [`ReadError::DataAfterCtrlZ`] if data follows after some padding.
+/// When [quote-aware](crate::ReadToCtrlZ::quote_aware()), `0x1A` bytes within quoted regions are
+/// read as regular bytes.
+///
+/// Bytes preceding an offending `0x1A` byte are delivered first, and the error is returned by the
+/// following read. After the error has been returned, reading returns `Ok(0)`.
+///
+/// Errors are returned as [`ReadError`] by [`read_strict()`]. Through [`Read`], they are
+/// converted into an [`io::Error`] of kind [`InvalidInput`](ErrorKind::InvalidInput) wrapping the
+/// `ReadError`.
+///
+/// This type is created using [`ReadToCtrlZ::strict()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadError;
+/// use ctrl_z::ReadToCtrlZ;
+///
+/// let mut reader = ReadToCtrlZ::new(&b"foo\x1abar"[..]).strict();
+/// let mut buf = [0; 16];
+///
+/// assert_eq!(reader.read_strict(&mut buf).unwrap(), 3);
+/// match reader.read_strict(&mut buf) {
+///     Err(ReadError::UnexpectedCtrlZ { offset }) => assert_eq!(offset, 3),
+///     result => panic!("unexpected result: {:?}", result),
+/// }
+/// ```
+///
+/// [`ReadError`]: crate::ReadError
+/// [`ReadError::UnexpectedCtrlZ`]: crate::ReadError::UnexpectedCtrlZ
+/// [`ReadError::DataAfterCtrlZ`]: crate::ReadError::DataAfterCtrlZ
+/// [`read_strict()`]: crate::StrictReadToCtrlZ::read_strict()
+/// [`ReadToCtrlZ::strict()`]: crate::ReadToCtrlZ::strict()
+pub struct StrictReadToCtrlZ<R> {
+    /// The internal reader being read.
+    inner: ReadToCtrlZ<R>,
+    /// Whether or not the delivered bytes end within a quoted region.
+    quoted: bool,
+    /// An error to be returned by the next read.
+    pending: Option<ReadError>,
+    /// Whether or not an error has been returned.
+    failed: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `StrictReadToCtrlZ`, reporting `0x1A` bytes followed by data as errors.
+    ///
+    /// This enables [lenient](ReadToCtrlZ::lenient()) mode on this reader, since the bytes
+    /// following each `0x1A` byte must be read ahead to determine whether it is the end.
+    pub fn strict(mut self) -> StrictReadToCtrlZ<R> {
+        self.lenient = true;
+        StrictReadToCtrlZ {
+            inner: self,
+            quoted: false,
+            pending: None,
+            failed: false,
+        }
+    }
+}
+
+impl<R> StrictReadToCtrlZ<R> {
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<R> {
+        &self.inner
+    }
+
+    /// Unwraps this `StrictReadToCtrlZ`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+impl<R> StrictReadToCtrlZ<R>
+where
+    R: Read,
+{
+    /// Reads bytes into `buf`, returning a typed error if a `0x1A` byte is followed by data.
+    pub fn read_strict(&mut self, buf: &mut [u8]) -> result::Result<usize, ReadError> {
+        if let Some(error) = self.pending.take() {
+            self.failed = true;
+            return Err(error);
+        }
+        if self.failed {
+            return Ok(0);
+        }
+
+        let n = try!(self.inner.read(buf));
+        let start = self.inner.bytes_delivered() - n as u64;
+        let terminators = self.inner.terminators;
+        let quote = self.inner.quote;
+        for i in 0..n {
+            let byte = buf[i];
+            if quote == Some(byte) {
+                self.quoted = !self.quoted;
+            } else if !self.quoted && terminators.contains(byte) {
+                // The terminating byte was read as a regular byte, so the byte following it was
+                // either delivered along with it or has been read ahead.
+                let next = if i + 1 < n {
+                    Some(buf[i + 1])
+                } else {
+                    self.inner.buffer.get(self.inner.pos).cloned()
+                };
+                let error = match next {
+                    Some(byte) if is_padding(&terminators, byte) => ReadError::DataAfterCtrlZ,
+                    _ => ReadError::UnexpectedCtrlZ {
+                        offset: start + i as u64,
+                    },
+                };
+                if i == 0 {
+                    self.failed = true;
+                    return Err(error);
+                }
+                self.pending = Some(error);
+                return Ok(i);
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<R> Read for StrictReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_strict(buf).map_err(io::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadError;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn padding_after_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\r\n\0\0" as &[u8]).strict();
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert!(reader.get_ref().is_terminated());
+    }
+
+    #[test]
+    fn unexpected_ctrl_z() {
+        let mut buf = [0; 16];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1abar" as &[u8]).strict();
+
+        assert_ok_eq!(reader.read_strict(&mut buf), 3);
+        match reader.read_strict(&mut buf) {
+            Err(ReadError::UnexpectedCtrlZ { offset }) => assert_eq!(offset, 3),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_ok_eq!(reader.read_strict(&mut buf), 0);
+    }
+
+    #[test]
+    fn unexpected_ctrl_z_at_start() {
+        let mut reader = ReadToCtrlZ::new(b"\x1abar" as &[u8]).strict();
+
+        match reader.read_strict(&mut [0; 16]) {
+            Err(ReadError::UnexpectedCtrlZ { offset }) => assert_eq!(offset, 0),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn data_after_ctrl_z() {
+        let mut buf = [0; 16];
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\0\0bar" as &[u8]).strict();
+
+        assert_ok_eq!(reader.read_strict(&mut buf), 3);
+        match reader.read_strict(&mut buf) {
+            Err(ReadError::DataAfterCtrlZ) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn data_after_ctrl_z_split_reads() {
+        let mut count = 0;
+        let mut buf = [0; 16];
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|buf: &mut [u8]| {
+            // Reads "a" followed by `0x1A`, padding, and more data one byte at a time.
+            let input = b"a\x1a\0b";
+            if count == input.len() {
+                return Ok(0);
+            }
+            buf[0] = input[count];
+            count += 1;
+            Ok(1)
+        }))
+        .strict();
+
+        assert_ok_eq!(reader.read_strict(&mut buf), 1);
+        match reader.read_strict(&mut buf) {
+            Err(ReadError::DataAfterCtrlZ) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn quoted_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"\"a\x1ab\"\x1a" as &[u8])
+            .quote_aware(b'"')
+            .strict();
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 5);
+        assert_eq!(output, "\"a\x1ab\"");
+    }
+
+    #[test]
+    fn read_maps_error() {
+        let mut output = String::new();
+        let error = assert_err!(ReadToCtrlZ::new(b"foo\x1abar" as &[u8])
+            .strict()
+            .read_to_string(&mut output));
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "unexpected 0x1A byte at byte 3");
+        assert_eq!(
+            assert_some!(error.get_ref()).description(),
+            "unexpected 0x1A byte"
+        );
+    }
+
+    #[test]
+    fn io_error() {
+        let mut reader = ReadToCtrlZ::new(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }))
+        .strict();
+
+        match reader.read_strict(&mut [0; 4]) {
+            Err(ReadError::Io(error)) => assert_eq!(error.kind(), ErrorKind::Other),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::Other
+        );
+    }
+}
@@ -0,0 +1,671 @@
+// The `strip` feature requires a newer Rust version, so the MSRV does not apply here.
+#![allow(clippy::incompatible_msrv)]
+
+use std::fs;
+use std::fs::File;
+use std::fs::FileTimes;
+use std::fs::Metadata;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use ReadToCtrlZ;
+
+/// The number of temporary files created by this process, used to give each a unique name.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Options for [`strip_in_place()`].
+///
+/// By default, no backup is made, padding is kept, and permissions and modification times are
+/// preserved.
+///
+/// This type is only available with the `strip` feature enabled.
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the fields `backup`,
+/// `remove_padding`, and `preserve_metadata`.
+///
+/// [`strip_in_place()`]: crate::strip_in_place()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StripOptions {
+    /// Whether or not to keep a copy of the original file.
+    backup: bool,
+    /// Whether or not to remove trailing `\0` bytes preceding the `0x1A` byte.
+    remove_padding: bool,
+    /// Whether or not to copy permissions and timestamps to the rewritten file.
+    preserve_metadata: bool,
+}
+
+impl StripOptions {
+    /// Creates a new `StripOptions` with the default options.
+    pub fn new() -> Self {
+        StripOptions {
+            backup: false,
+            remove_padding: false,
+            preserve_metadata: true,
+        }
+    }
+
+    /// Sets whether the original file is kept alongside the rewritten file.
+    ///
+    /// The backup is placed next to the original, with `.bak` appended to its file name. An
+    /// existing file at that path is overwritten.
+    pub fn backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+
+    /// Sets whether trailing `\0` bytes preceding the `0x1A` byte are removed as well.
+    pub fn remove_padding(mut self, remove_padding: bool) -> Self {
+        self.remove_padding = remove_padding;
+        self
+    }
+
+    /// Sets whether the original file's permissions and access and modification times are
+    /// applied to the rewritten file.
+    ///
+    /// Timestamps the platform does not report are left as they are.
+    pub fn preserve_metadata(mut self, preserve_metadata: bool) -> Self {
+        self.preserve_metadata = preserve_metadata;
+        self
+    }
+}
+
+impl Default for StripOptions {
+    fn default() -> Self {
+        StripOptions::new()
+    }
+}
+
+/// The result of a successful call to [`strip_in_place()`].
+///
+/// This type is only available with the `strip` feature enabled.
+///
+/// With the `serde` feature enabled, this is serialized as a struct with the fields
+/// `original_len`, `new_len`, `marker_found`, and `backup_path`.
+///
+/// [`strip_in_place()`]: crate::strip_in_place()
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StripOutcome {
+    /// The length of the file before stripping.
+    original_len: u64,
+    /// The length of the file after stripping.
+    new_len: u64,
+    /// Whether or not a `0x1A` byte was found.
+    marker_found: bool,
+    /// The path of the backup, if one was made.
+    #[cfg_attr(feature = "serde", serde(rename = "backup_path"))]
+    backup: Option<PathBuf>,
+}
+
+impl StripOutcome {
+    /// Returns the length of the file before stripping.
+    pub fn original_len(&self) -> u64 {
+        self.original_len
+    }
+
+    /// Returns the length of the file after stripping.
+    pub fn new_len(&self) -> u64 {
+        self.new_len
+    }
+
+    /// Returns the number of bytes removed from the end of the file.
+    pub fn bytes_removed(&self) -> u64 {
+        self.original_len - self.new_len
+    }
+
+    /// Returns whether a `0x1A` byte was found in the file.
+    ///
+    /// A file without one may still be modified if trailing padding is removed.
+    pub fn marker_found(&self) -> bool {
+        self.marker_found
+    }
+
+    /// Returns whether the file was rewritten.
+    ///
+    /// A file with nothing to strip is left untouched, and no backup is made.
+    pub fn is_modified(&self) -> bool {
+        self.new_len != self.original_len
+    }
+
+    /// Returns the path of the backup of the original file, if one was made.
+    pub fn backup_path(&self) -> Option<&Path> {
+        self.backup.as_ref().map(|path| path.as_path())
+    }
+}
+
+/// Atomically rewrites a file to contain only the bytes preceding its first `0x1A` byte.
+///
+/// The stripped contents are written to a temporary file in the same directory, which is synced
+/// to disk and then renamed over the original. If any step fails, the temporary file is removed
+/// and the original is left untouched, so the path always holds either the original or the
+/// completed new file.
+///
+/// The temporary file is named after the original, followed by the process ID, a counter, and
+/// `.ctrl-z-tmp`, such as `foo.txt.1234.0.ctrl-z-tmp`. Existing files are never overwritten, so if
+/// a process is killed while stripping, the file it leaves behind does not block later calls and
+/// can safely be deleted.
+///
+/// This function is only available with the `strip` feature enabled, which requires Rust 1.75 or
+/// later.
+///
+/// # Example
+/// ```no_run
+/// use ctrl_z::strip_in_place;
+/// use ctrl_z::StripOptions;
+///
+/// let outcome = strip_in_place("foo.txt", StripOptions::new().backup(true)).unwrap();
+/// if let Some(backup) = outcome.backup_path() {
+///     println!("original kept at {}", backup.display());
+/// }
+/// ```
+pub fn strip_in_place<P>(path: P, options: StripOptions) -> Result<StripOutcome>
+where
+    P: AsRef<Path>,
+{
+    strip_in_place_with(path.as_ref(), options, |file, content| {
+        file.write_all(content)
+    })
+}
+
+/// Performs [`strip_in_place()`], writing the temporary file's contents using `write`.
+fn strip_in_place_with<F>(path: &Path, options: StripOptions, write: F) -> Result<StripOutcome>
+where
+    F: FnOnce(&mut File, &[u8]) -> Result<()>,
+{
+    let metadata = try!(fs::metadata(path));
+    let mut content = Vec::new();
+    let mut reader = ReadToCtrlZ::new(try!(File::open(path)));
+    try!(reader.read_to_end(&mut content));
+    if options.remove_padding {
+        while content.last() == Some(&b'\0') {
+            content.pop();
+        }
+    }
+
+    let mut outcome = StripOutcome {
+        original_len: metadata.len(),
+        new_len: content.len() as u64,
+        marker_found: reader.is_terminated(),
+        backup: None,
+    };
+    if !outcome.is_modified() {
+        return Ok(outcome);
+    }
+
+    if options.backup {
+        let backup = sibling(path, ".bak");
+        try!(fs::copy(path, &backup));
+        outcome.backup = Some(backup);
+    }
+
+    let (temp, mut file) = try!(create_temp(path));
+    let result = write(&mut file, &content)
+        .and_then(|()| {
+            if options.preserve_metadata {
+                preserve_metadata(&file, &metadata)
+            } else {
+                Ok(())
+            }
+        })
+        .and_then(|()| file.sync_all())
+        .and_then(|()| {
+            drop(file);
+            fs::rename(&temp, path)
+        });
+    if let Err(error) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(error);
+    }
+    sync_parent(path);
+
+    Ok(outcome)
+}
+
+/// The result of processing a single file in [`strip_files()`].
+///
+/// This type is only available with the `strip` feature enabled.
+///
+/// [`strip_files()`]: crate::strip_files()
+#[derive(Debug)]
+pub struct BatchEntry {
+    /// The path of the file.
+    path: PathBuf,
+    /// The result of stripping the file.
+    result: Result<StripOutcome>,
+}
+
+impl BatchEntry {
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the outcome of stripping the file, or `None` if an error occurred.
+    pub fn outcome(&self) -> Option<&StripOutcome> {
+        self.result.as_ref().ok()
+    }
+
+    /// Returns the error that occurred while stripping the file, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.result.as_ref().err()
+    }
+
+    /// Returns the number of bytes removed from the file, which is `0` if an error occurred.
+    pub fn bytes_removed(&self) -> u64 {
+        self.outcome().map_or(0, StripOutcome::bytes_removed)
+    }
+
+    /// Returns whether a `0x1A` byte was found in the file.
+    ///
+    /// This is `false` if an error occurred.
+    pub fn marker_found(&self) -> bool {
+        self.outcome().map_or(false, StripOutcome::marker_found)
+    }
+}
+
+/// A report of the files processed by [`strip_files()`].
+///
+/// This type is only available with the `strip` feature enabled.
+///
+/// [`strip_files()`]: crate::strip_files()
+#[derive(Debug)]
+pub struct BatchReport {
+    /// The result of each file, in the order processed.
+    entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    /// Returns the result of each file, in the order processed.
+    pub fn entries(&self) -> &[BatchEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of files processed, including those that failed.
+    pub fn files_processed(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the number of files that were rewritten.
+    pub fn files_modified(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.outcome().map_or(false, StripOutcome::is_modified))
+            .count()
+    }
+
+    /// Returns the number of files for which an error occurred.
+    pub fn files_failed(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.error().is_some())
+            .count()
+    }
+
+    /// Returns the total number of bytes removed across all files.
+    pub fn bytes_removed(&self) -> u64 {
+        self.entries.iter().map(BatchEntry::bytes_removed).sum()
+    }
+}
+
+/// Strips each file in `paths` in place, continuing past files that fail.
+///
+/// Each file is processed as by [`strip_in_place()`], using the same `options`. An error for one
+/// file is recorded in the returned report, and processing continues with the next file.
+///
+/// This function is only available with the `strip` feature enabled.
+///
+/// # Example
+/// ```no_run
+/// use ctrl_z::strip_files;
+/// use ctrl_z::StripOptions;
+/// use std::path::PathBuf;
+///
+/// let paths = vec![PathBuf::from("foo.txt"), PathBuf::from("bar.txt")];
+/// let report = strip_files(paths, StripOptions::new());
+///
+/// for entry in report.entries() {
+///     if let Some(error) = entry.error() {
+///         println!("{}: {}", entry.path().display(), error);
+///     }
+/// }
+/// println!("removed {} bytes", report.bytes_removed());
+/// ```
+///
+/// [`strip_in_place()`]: crate::strip_in_place()
+pub fn strip_files<I>(paths: I, options: StripOptions) -> BatchReport
+where
+    I: IntoIterator<Item = PathBuf>,
+{
+    strip_files_with_progress(paths, options, |_| {})
+}
+
+/// Strips each file in `paths` in place, calling `callback` after each file is processed.
+///
+/// This behaves like [`strip_files()`], with `callback` receiving each entry as it is added to the
+/// report.
+///
+/// This function is only available with the `strip` feature enabled.
+///
+/// [`strip_files()`]: crate::strip_files()
+pub fn strip_files_with_progress<I, F>(
+    paths: I,
+    options: StripOptions,
+    mut callback: F,
+) -> BatchReport
+where
+    I: IntoIterator<Item = PathBuf>,
+    F: FnMut(&BatchEntry),
+{
+    let mut entries = Vec::new();
+    for path in paths {
+        let result = strip_in_place(&path, options);
+        let entry = BatchEntry {
+            path: path,
+            result: result,
+        };
+        callback(&entry);
+        entries.push(entry);
+    }
+    BatchReport { entries: entries }
+}
+
+/// Returns the path of a file next to `path`, with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Creates a new temporary file next to `path`, returning its path and the open file.
+///
+/// Names already in use, such as by files left behind by a killed process, are skipped.
+fn create_temp(path: &Path) -> Result<(PathBuf, File)> {
+    loop {
+        let suffix = format!(
+            ".{}.{}.ctrl-z-tmp",
+            process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        );
+        let temp = sibling(path, &suffix);
+        match OpenOptions::new().write(true).create_new(true).open(&temp) {
+            Ok(file) => return Ok((temp, file)),
+            Err(ref error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Applies the permissions and timestamps in `metadata` to `file`.
+fn preserve_metadata(file: &File, metadata: &Metadata) -> Result<()> {
+    try!(file.set_permissions(metadata.permissions()));
+    let mut times = FileTimes::new();
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+    file.set_times(times)
+}
+
+/// Syncs the directory containing `path`, so the rename is durable.
+///
+/// This is only possible on Unix. Failure is ignored, as the rename has already completed.
+#[cfg(unix)]
+fn sync_parent(path: &Path) {
+    let parent = match path.parent() {
+        Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+        Some(parent) => parent,
+        None => return,
+    };
+    if let Ok(directory) = File::open(parent) {
+        let _ = directory.sync_all();
+    }
+}
+
+/// Syncs the directory containing `path`, so the rename is durable.
+///
+/// This is only possible on Unix.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_files;
+    use super::strip_files_with_progress;
+    use super::strip_in_place;
+    use super::strip_in_place_with;
+    use super::StripOptions;
+    use super::TEMP_FILES;
+    use std::env;
+    use std::fs;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Write;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::process;
+    use std::sync::atomic::Ordering;
+
+    /// Creates a file in the temporary directory with the given contents, returning its path.
+    fn create(name: &str, contents: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(name);
+        assert_ok!(fs::write(&path, contents));
+        path
+    }
+
+    /// Returns whether any temporary file created while stripping `path` remains.
+    fn temp_file_remains(path: &Path) -> bool {
+        let prefix = format!("{}.", assert_some!(assert_some!(path.file_name()).to_str()));
+        assert_ok!(fs::read_dir(assert_some!(path.parent()))).any(|entry| {
+            let name = assert_ok!(entry).file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(".ctrl-z-tmp")
+        })
+    }
+
+    #[test]
+    fn strip() {
+        let path = create("ctrl_z_strip_strip", b"foo\x1abar");
+
+        let outcome = assert_ok!(strip_in_place(&path, StripOptions::new()));
+
+        assert_eq!(outcome.original_len(), 7);
+        assert_eq!(outcome.new_len(), 3);
+        assert!(outcome.is_modified());
+        assert_none!(outcome.backup_path());
+        assert_ok_eq!(fs::read(&path), b"foo");
+        assert!(!temp_file_remains(&path));
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn unchanged() {
+        let path = create("ctrl_z_strip_unchanged", b"foo");
+
+        let outcome = assert_ok!(strip_in_place(&path, StripOptions::new().backup(true)));
+
+        assert!(!outcome.is_modified());
+        assert_none!(outcome.backup_path());
+        assert!(!path.with_file_name("ctrl_z_strip_unchanged.bak").exists());
+        assert_ok_eq!(fs::read(&path), b"foo");
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn backup() {
+        let path = create("ctrl_z_strip_backup", b"foo\x1abar");
+
+        let outcome = assert_ok!(strip_in_place(&path, StripOptions::new().backup(true)));
+        let backup = assert_some!(outcome.backup_path()).to_path_buf();
+
+        assert_eq!(backup, path.with_file_name("ctrl_z_strip_backup.bak"));
+        assert_ok_eq!(fs::read(&backup), b"foo\x1abar");
+        assert_ok_eq!(fs::read(&path), b"foo");
+        assert_ok!(fs::remove_file(&path));
+        assert_ok!(fs::remove_file(&backup));
+    }
+
+    #[test]
+    fn remove_padding() {
+        let path = create("ctrl_z_strip_remove_padding", b"foo\r\n\0\0\x1a\x1a");
+
+        let outcome = assert_ok!(strip_in_place(
+            &path,
+            StripOptions::new().remove_padding(true)
+        ));
+
+        assert_eq!(outcome.new_len(), 5);
+        assert_ok_eq!(fs::read(&path), b"foo\r\n");
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn preserve_metadata() {
+        let path = create("ctrl_z_strip_preserve_metadata", b"foo\x1abar");
+        let mut permissions = assert_ok!(fs::metadata(&path)).permissions();
+        permissions.set_readonly(true);
+        assert_ok!(fs::set_permissions(&path, permissions));
+        let modified = assert_ok!(assert_ok!(fs::metadata(&path)).modified());
+
+        assert_ok!(strip_in_place(&path, StripOptions::new()));
+
+        let metadata = assert_ok!(fs::metadata(&path));
+        assert!(metadata.permissions().readonly());
+        assert_ok_eq!(metadata.modified(), modified);
+        assert_ok_eq!(fs::read(&path), b"foo");
+        let mut permissions = metadata.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        assert_ok!(fs::set_permissions(&path, permissions));
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn failed_write_leaves_original() {
+        let path = create("ctrl_z_strip_failed_write", b"foo\x1abar");
+
+        let error = assert_err!(strip_in_place_with(
+            &path,
+            StripOptions::new(),
+            |file, content| {
+                // Writes part of the contents before failing.
+                try!(file.write_all(&content[..1]));
+                Err(Error::new(ErrorKind::Other, "foo"))
+            }
+        ));
+
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_ok_eq!(fs::read(&path), b"foo\x1abar");
+        assert!(!temp_file_remains(&path));
+        assert_ok!(fs::remove_file(&path));
+    }
+
+    #[test]
+    fn existing_temp_file_skipped() {
+        let path = create("ctrl_z_strip_existing_temp", b"foo\x1abar");
+        // A file left behind under the next name to be used, as if by a killed process.
+        let temp = create(
+            &format!(
+                "ctrl_z_strip_existing_temp.{}.{}.ctrl-z-tmp",
+                process::id(),
+                TEMP_FILES.load(Ordering::Relaxed)
+            ),
+            b"baz",
+        );
+
+        assert_ok!(strip_in_place(&path, StripOptions::new()));
+
+        assert_ok_eq!(fs::read(&path), b"foo");
+        assert_ok_eq!(fs::read(&temp), b"baz");
+        assert_ok!(fs::remove_file(&path));
+        assert_ok!(fs::remove_file(&temp));
+    }
+
+    #[test]
+    fn strip_files_report() {
+        let terminated = create("ctrl_z_strip_files_terminated", b"foo\x1abar");
+        let clean = create("ctrl_z_strip_files_clean", b"foo");
+        let missing = env::temp_dir().join("ctrl_z_strip_files_missing");
+        let _ = fs::remove_file(&missing);
+
+        let report = strip_files(
+            vec![terminated.clone(), clean.clone(), missing.clone()],
+            StripOptions::new(),
+        );
+
+        assert_eq!(report.files_processed(), 3);
+        assert_eq!(report.files_modified(), 1);
+        assert_eq!(report.files_failed(), 1);
+        assert_eq!(report.bytes_removed(), 4);
+        let entries = report.entries();
+        assert_eq!(entries[0].path(), terminated.as_path());
+        assert!(entries[0].marker_found());
+        assert_eq!(entries[0].bytes_removed(), 4);
+        assert_none!(entries[0].error());
+        assert!(!entries[1].marker_found());
+        assert_eq!(entries[1].bytes_removed(), 0);
+        assert!(!assert_some!(entries[1].outcome()).is_modified());
+        assert_eq!(entries[2].path(), missing.as_path());
+        assert_eq!(assert_some!(entries[2].error()).kind(), ErrorKind::NotFound);
+        assert!(!entries[2].marker_found());
+        assert_ok_eq!(fs::read(&terminated), b"foo");
+        assert_ok!(fs::remove_file(&terminated));
+        assert_ok!(fs::remove_file(&clean));
+    }
+
+    #[test]
+    fn strip_files_progress() {
+        let first = create("ctrl_z_strip_files_progress_first", b"a\x1a");
+        let second = create("ctrl_z_strip_files_progress_second", b"bc\x1a");
+        let mut seen = Vec::new();
+
+        let report = strip_files_with_progress(
+            vec![first.clone(), second.clone()],
+            StripOptions::new(),
+            |entry| seen.push((entry.path().to_path_buf(), entry.bytes_removed())),
+        );
+
+        assert_eq!(seen, [(first.clone(), 1), (second.clone(), 1)]);
+        assert_eq!(report.files_modified(), 2);
+        assert_ok!(fs::remove_file(&first));
+        assert_ok!(fs::remove_file(&second));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let options = StripOptions::new().backup(true);
+        let json = assert_ok!(serde_json::to_string(&options));
+        assert_eq!(
+            json,
+            r#"{"backup":true,"remove_padding":false,"preserve_metadata":true}"#
+        );
+        assert_ok_eq!(serde_json::from_str::<StripOptions>(&json), options);
+
+        let outcome = super::StripOutcome {
+            original_len: 7,
+            new_len: 3,
+            marker_found: true,
+            backup: Some(PathBuf::from("foo.bak")),
+        };
+        let json = assert_ok!(serde_json::to_string(&outcome));
+        assert_eq!(
+            json,
+            r#"{"original_len":7,"new_len":3,"marker_found":true,"backup_path":"foo.bak"}"#
+        );
+        assert_ok_eq!(serde_json::from_str::<super::StripOutcome>(&json), outcome);
+    }
+}
@@ -0,0 +1,151 @@
+/// A set of bytes that terminate reading.
+///
+/// By default, a [`ReadToCtrlZ`] only terminates on `0x1A`. A `TerminatorSet` can be provided
+/// through [`ReadToCtrlZ::terminate_on()`] to terminate on any of a number of bytes instead.
+///
+/// # Example
+/// Here is an example of a set terminating on any C0 control character, while still allowing
+/// tabs and line endings to be read.
+///
+/// ```
+/// use ctrl_z::TerminatorSet;
+///
+/// let terminators = TerminatorSet::c0_controls();
+///
+/// assert!(terminators.contains(b'\x07'));
+/// assert!(!terminators.contains(b'\n'));
+/// ```
+///
+/// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+/// [`ReadToCtrlZ::terminate_on()`]: crate::ReadToCtrlZ::terminate_on()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TerminatorSet {
+    /// One bit for each possible byte value.
+    bits: [u64; 4],
+}
+
+impl TerminatorSet {
+    /// Creates a new `TerminatorSet` containing no bytes.
+    pub fn new() -> Self {
+        TerminatorSet { bits: [0; 4] }
+    }
+
+    /// Creates a new `TerminatorSet` containing only `0x1A`.
+    ///
+    /// This is the set used by a [`ReadToCtrlZ`] unless configured otherwise.
+    ///
+    /// [`ReadToCtrlZ`]: crate::ReadToCtrlZ
+    pub fn ctrl_z() -> Self {
+        let mut terminators = TerminatorSet::new();
+        terminators.insert(b'\x1a');
+        terminators
+    }
+
+    /// Creates a new `TerminatorSet` containing every C0 control character (`0x00` through
+    /// `0x1F`) except for `\t`, `\r`, and `\n`.
+    ///
+    /// This is useful for legacy captures (such as line-printer output) which end at the first
+    /// control character that is not part of regular text.
+    pub fn c0_controls() -> Self {
+        TerminatorSet::c0_controls_except(b"\t\r\n")
+    }
+
+    /// Creates a new `TerminatorSet` containing every C0 control character (`0x00` through
+    /// `0x1F`) except for the bytes in `allowed`.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::TerminatorSet;
+    ///
+    /// let terminators = TerminatorSet::c0_controls_except(b"\n");
+    ///
+    /// assert!(terminators.contains(b'\t'));
+    /// assert!(!terminators.contains(b'\n'));
+    /// ```
+    pub fn c0_controls_except(allowed: &[u8]) -> Self {
+        let mut terminators = TerminatorSet::new();
+        for byte in 0x00..0x20 {
+            terminators.insert(byte);
+        }
+        for &byte in allowed {
+            terminators.remove(byte);
+        }
+        terminators
+    }
+
+    /// Adds `byte` to the set.
+    pub fn insert(&mut self, byte: u8) {
+        self.bits[(byte >> 6) as usize] |= 1 << (byte & 0x3f);
+    }
+
+    /// Removes `byte` from the set.
+    pub fn remove(&mut self, byte: u8) {
+        self.bits[(byte >> 6) as usize] &= !(1 << (byte & 0x3f));
+    }
+
+    /// Returns whether `byte` is contained in the set.
+    pub fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte >> 6) as usize] & (1 << (byte & 0x3f)) != 0
+    }
+}
+
+impl Default for TerminatorSet {
+    fn default() -> Self {
+        TerminatorSet::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TerminatorSet;
+
+    #[test]
+    fn new_is_empty() {
+        let terminators = TerminatorSet::new();
+
+        for byte in 0..256 {
+            assert!(!terminators.contains(byte as u8));
+        }
+    }
+
+    #[test]
+    fn ctrl_z_contains_only_ctrl_z() {
+        let terminators = TerminatorSet::ctrl_z();
+
+        for byte in 0..256 {
+            assert_eq!(terminators.contains(byte as u8), byte == 0x1a);
+        }
+    }
+
+    #[test]
+    fn c0_controls() {
+        let terminators = TerminatorSet::c0_controls();
+
+        for byte in 0..256 {
+            assert_eq!(
+                terminators.contains(byte as u8),
+                byte < 0x20 && byte != 0x09 && byte != 0x0a && byte != 0x0d
+            );
+        }
+    }
+
+    #[test]
+    fn c0_controls_except() {
+        let terminators = TerminatorSet::c0_controls_except(b"\x07\x7f");
+
+        assert!(!terminators.contains(b'\x07'));
+        assert!(terminators.contains(b'\t'));
+        assert!(!terminators.contains(b'\x7f'));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut terminators = TerminatorSet::new();
+
+        terminators.insert(b'\xff');
+        assert!(terminators.contains(b'\xff'));
+
+        terminators.remove(b'\xff');
+        assert!(!terminators.contains(b'\xff'));
+    }
+}
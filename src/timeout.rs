@@ -0,0 +1,218 @@
+// `Duration` requires a newer Rust version, as does the `libc` crate itself.
+#![allow(clippy::incompatible_msrv)]
+
+use libc;
+use std::cmp;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+use ReadToCtrlZ;
+
+/// A reader that waits at most a fixed timeout for its inner reader to become readable.
+struct Timeout<R> {
+    /// The internal reader being read.
+    inner: R,
+    /// The timeout passed to `poll()`, in milliseconds.
+    timeout: libc::c_int,
+}
+
+impl<R> Read for Timeout<R>
+where
+    R: Read + AsRawFd,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut fd = libc::pollfd {
+            fd: self.inner.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        loop {
+            // SAFETY: `fd` is a single valid `pollfd`.
+            match unsafe { libc::poll(&mut fd, 1, self.timeout) } {
+                0 => return Err(Error::new(ErrorKind::TimedOut, "read timed out")),
+                result if result < 0 => {
+                    let error = Error::last_os_error();
+                    if error.kind() != ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                }
+                _ => return self.inner.read(buf),
+            }
+        }
+    }
+}
+
+/// Converts `timeout` to the milliseconds passed to `poll()`.
+///
+/// Any sub-millisecond remainder is rounded up, so a nonzero timeout never becomes an immediate
+/// one. Timeouts longer than `c_int::MAX` milliseconds are capped.
+fn poll_millis(timeout: Duration) -> libc::c_int {
+    let millis = timeout
+        .as_secs()
+        .saturating_mul(1000)
+        .saturating_add((timeout.subsec_nanos() as u64 + 999_999) / 1_000_000);
+    cmp::min(millis, libc::c_int::MAX as u64) as libc::c_int
+}
+
+/// A reader to read until a `0x1A` byte, failing any read that waits longer than a timeout.
+///
+/// Before each read from the inner reader, its file descriptor is polled for at most the timeout.
+/// If no data becomes available in time, the read returns an error of kind
+/// [`ErrorKind::TimedOut`]. Bytes already buffered internally are returned without waiting.
+///
+/// The inner reader must be unbuffered, such as a [`File`](std::fs::File) or a socket. Polling
+/// only observes the file descriptor, so bytes held in a buffer of the inner reader itself, such
+/// as the one behind [`io::stdin()`](std::io::stdin()), are not seen, and a read may time out
+/// even though data is available.
+///
+/// This type is only available on Unix platforms with the `timeout` feature enabled. It is created
+/// using [`ReadToCtrlZ::with_timeout()`].
+///
+/// # Example
+/// ```no_run
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use std::time::Duration;
+///
+/// let fifo = File::open("input.fifo").unwrap();
+/// let mut reader = ReadToCtrlZ::with_timeout(fifo, Duration::from_secs(5));
+/// let mut output = String::new();
+///
+/// reader.read_to_string(&mut output).unwrap();
+/// ```
+///
+/// [`ReadToCtrlZ::with_timeout()`]: crate::ReadToCtrlZ::with_timeout()
+pub struct TimeoutReadToCtrlZ<R> {
+    /// The underlying reader, polling its inner reader before each read.
+    inner: ReadToCtrlZ<Timeout<R>>,
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: Read + AsRawFd,
+{
+    /// Creates a new `TimeoutReadToCtrlZ`, wrapping the provided reader.
+    ///
+    /// The timeout is rounded up to a whole number of milliseconds. Timeouts longer than
+    /// [`i32::MAX`] milliseconds are treated as [`i32::MAX`] milliseconds.
+    ///
+    /// The inner reader must not buffer bytes itself, or they may be missed by the timeout. See
+    /// [`TimeoutReadToCtrlZ`] for details.
+    ///
+    /// This method is only available on Unix platforms with the `timeout` feature enabled.
+    pub fn with_timeout(inner: R, timeout: Duration) -> TimeoutReadToCtrlZ<R> {
+        TimeoutReadToCtrlZ {
+            inner: ReadToCtrlZ::new(Timeout {
+                inner: inner,
+                timeout: poll_millis(timeout),
+            }),
+        }
+    }
+}
+
+impl<R> TimeoutReadToCtrlZ<R> {
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner.inner.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader may cause bytes to be missed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner.inner.inner
+    }
+
+    /// Unwraps this `TimeoutReadToCtrlZ`, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner.inner.inner
+    }
+}
+
+impl<R> Read for TimeoutReadToCtrlZ<R>
+where
+    R: Read + AsRawFd,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::poll_millis;
+    use libc;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn read() {
+        let (mut sender, receiver) = assert_ok!(UnixStream::pair());
+        assert_ok!(sender.write_all(b"foo\x1abar"));
+        let mut reader = ReadToCtrlZ::with_timeout(receiver, Duration::from_millis(100));
+        let mut output = String::new();
+
+        // The sender is still connected, but reading stops at the `0x1A` byte.
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn read_timed_out() {
+        let (_sender, receiver) = assert_ok!(UnixStream::pair());
+        let mut reader = ReadToCtrlZ::with_timeout(receiver, Duration::from_millis(10));
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::TimedOut
+        );
+    }
+
+    #[test]
+    fn read_after_timed_out() {
+        let (mut sender, receiver) = assert_ok!(UnixStream::pair());
+        let mut reader = ReadToCtrlZ::with_timeout(receiver, Duration::from_millis(10));
+        let mut output = [0; 4];
+
+        assert_err!(reader.read(&mut output));
+        assert_ok!(sender.write_all(b"foo"));
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(&output[..3], b"foo");
+    }
+
+    #[test]
+    fn poll_millis_rounds_up() {
+        assert_eq!(poll_millis(Duration::from_micros(500)), 1);
+        assert_eq!(poll_millis(Duration::from_nanos(1)), 1);
+        assert_eq!(poll_millis(Duration::from_micros(1500)), 2);
+        assert_eq!(poll_millis(Duration::from_millis(2)), 2);
+        assert_eq!(poll_millis(Duration::from_secs(0)), 0);
+    }
+
+    #[test]
+    fn poll_millis_capped() {
+        assert_eq!(
+            poll_millis(Duration::from_secs(u64::max_value())),
+            libc::c_int::MAX
+        );
+    }
+
+    #[test]
+    fn read_sub_millisecond_timeout() {
+        let (mut sender, receiver) = assert_ok!(UnixStream::pair());
+        assert_ok!(sender.write_all(b"foo\x1a"));
+        let mut reader = ReadToCtrlZ::with_timeout(receiver, Duration::from_micros(500));
+        let mut output = String::new();
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+}
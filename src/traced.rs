@@ -0,0 +1,325 @@
+use std::any;
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// A reader to read until a `0x1A` byte, emitting [`tracing`] events as it reads.
+///
+/// A `trace`-level event is emitted when the first byte is read, when the `0x1A` byte is
+/// encountered, when the inner reader reaches its end without a `0x1A` byte, and when an I/O
+/// error occurs. Each event includes the `bytes_read` so far, the `offset` of the event within
+/// the inner reader, and the `inner_type` of the inner reader.
+///
+/// This type is only available with the `tracing` feature enabled. It is created using
+/// [`ReadToCtrlZ::with_tracing()`].
+///
+/// # Example
+/// ```
+/// use ctrl_z::ReadToCtrlZ;
+/// use std::io::Read;
+///
+/// let mut reader = ReadToCtrlZ::with_tracing(&b"foo\x1abar"[..]);
+/// let mut output = String::new();
+///
+/// assert!(reader.read_to_string(&mut output).is_ok());
+/// assert_eq!(output, "foo");
+/// ```
+///
+/// [`tracing`]: https://docs.rs/tracing
+/// [`ReadToCtrlZ::with_tracing()`]: crate::ReadToCtrlZ::with_tracing()
+pub struct TracedReadToCtrlZ<R> {
+    /// The internal reader being traced.
+    inner: ReadToCtrlZ<R>,
+    /// Whether or not the first byte has been read.
+    started: bool,
+    /// Whether or not the end of reading, by either the `0x1A` byte or the end of the inner
+    /// reader, has been traced.
+    finished: bool,
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Creates a new `TracedReadToCtrlZ`, wrapping the provided reader.
+    ///
+    /// This method is only available with the `tracing` feature enabled.
+    pub fn with_tracing(inner: R) -> TracedReadToCtrlZ<R> {
+        TracedReadToCtrlZ {
+            inner: ReadToCtrlZ::new(inner),
+            started: false,
+            finished: false,
+        }
+    }
+}
+
+impl<R> TracedReadToCtrlZ<R> {
+    /// Returns a reference to the underlying `ReadToCtrlZ`.
+    pub fn get_ref(&self) -> &ReadToCtrlZ<R> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying `ReadToCtrlZ`.
+    pub fn get_mut(&mut self) -> &mut ReadToCtrlZ<R> {
+        &mut self.inner
+    }
+
+    /// Unwraps this `TracedReadToCtrlZ`, returning the underlying `ReadToCtrlZ`.
+    pub fn into_inner(self) -> ReadToCtrlZ<R> {
+        self.inner
+    }
+}
+
+// `type_name()` requires a newer Rust version, as does the `tracing` crate itself.
+#[allow(clippy::incompatible_msrv)]
+impl<R> Read for TracedReadToCtrlZ<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let inner_type = any::type_name::<R>();
+        let n = match self.inner.read(buf) {
+            Ok(n) => n,
+            Err(error) => {
+                trace!(
+                    bytes_read = self.inner.bytes_delivered(),
+                    offset = self.inner.inner_bytes_read(),
+                    inner_type = inner_type,
+                    error = %error,
+                    "I/O error"
+                );
+                return Err(error);
+            }
+        };
+
+        if n > 0 && !self.started {
+            self.started = true;
+            trace!(
+                bytes_read = self.inner.bytes_delivered(),
+                offset = 0,
+                inner_type = inner_type,
+                "first byte read"
+            );
+        }
+        if !self.finished {
+            if self.inner.terminator().is_some() {
+                self.finished = true;
+                trace!(
+                    bytes_read = self.inner.bytes_delivered(),
+                    offset = self.inner.bytes_delivered(),
+                    inner_type = inner_type,
+                    "0x1A encountered"
+                );
+            } else if n == 0 && !buf.is_empty() {
+                self.finished = true;
+                trace!(
+                    bytes_read = self.inner.bytes_delivered(),
+                    offset = self.inner.inner_bytes_read(),
+                    inner_type = inner_type,
+                    "natural EOF"
+                );
+            }
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use tracing::field::Field;
+    use tracing::field::Visit;
+    use tracing::span;
+    use tracing::Event;
+    use tracing::Metadata;
+    use tracing::Subscriber;
+    use ClosureReader;
+    use ReadToCtrlZ;
+
+    /// The fields of a captured event, each formatted as a string, in the order they were
+    /// recorded.
+    type Fields = Vec<(String, String)>;
+
+    /// A subscriber capturing the fields of every event.
+    struct CapturingSubscriber {
+        events: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    /// Formats the fields of an event.
+    struct FieldVisitor<'a> {
+        fields: &'a mut Fields,
+    }
+
+    impl<'a> Visit for FieldVisitor<'a> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields
+                .push((field.name().to_owned(), value.to_owned()));
+        }
+
+        // `dyn` requires a newer Rust version, as does the `tracing` crate itself.
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.fields
+                .push((field.name().to_owned(), format!("{:?}", value)));
+        }
+    }
+
+    impl Subscriber for CapturingSubscriber {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut fields = Vec::new();
+            event.record(&mut FieldVisitor {
+                fields: &mut fields,
+            });
+            assert_ok!(self.events.lock()).push(fields);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    /// Runs `f` with a subscriber installed on the current thread, returning the fields of each
+    /// event emitted.
+    fn capture<F>(f: F) -> Vec<Fields>
+    where
+        F: FnOnce(),
+    {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        ::tracing::subscriber::with_default(
+            CapturingSubscriber {
+                events: events.clone(),
+            },
+            f,
+        );
+        let events = assert_ok!(events.lock());
+        events.clone()
+    }
+
+    /// Builds the expected fields of an event.
+    fn fields(pairs: &[(&str, &str)]) -> Fields {
+        pairs
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn read_to_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_tracing(b"foo\x1abar" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_some_eq!(reader.get_ref().terminator(), b'\x1a');
+    }
+
+    #[test]
+    fn read_to_eof() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::with_tracing(b"foo" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+        assert_none!(reader.get_ref().terminator());
+    }
+
+    #[test]
+    fn events_ctrl_z() {
+        let events = capture(|| {
+            let mut output = String::new();
+            let mut reader = ReadToCtrlZ::with_tracing(b"foo\x1abar" as &[u8]);
+            assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        });
+        let inner_type = "&[u8]";
+
+        assert_eq!(
+            events,
+            [
+                fields(&[
+                    ("message", "first byte read"),
+                    ("bytes_read", "3"),
+                    ("offset", "0"),
+                    ("inner_type", inner_type),
+                ]),
+                fields(&[
+                    ("message", "0x1A encountered"),
+                    ("bytes_read", "3"),
+                    ("offset", "3"),
+                    ("inner_type", inner_type),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_eof() {
+        let events = capture(|| {
+            let mut output = String::new();
+            let mut reader = ReadToCtrlZ::with_tracing(b"foo" as &[u8]);
+            assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        });
+        let inner_type = "&[u8]";
+
+        assert_eq!(
+            events,
+            [
+                fields(&[
+                    ("message", "first byte read"),
+                    ("bytes_read", "3"),
+                    ("offset", "0"),
+                    ("inner_type", inner_type),
+                ]),
+                fields(&[
+                    ("message", "natural EOF"),
+                    ("bytes_read", "3"),
+                    ("offset", "3"),
+                    ("inner_type", inner_type),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_error() {
+        let events = capture(|| {
+            let mut reader = ReadToCtrlZ::with_tracing(ClosureReader(|_: &mut [u8]| {
+                Err(Error::new(ErrorKind::Other, "foo"))
+            }));
+            assert_err!(reader.read(&mut [0; 4]));
+        });
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event[0], ("message".to_owned(), "I/O error".to_owned()));
+        assert_eq!(event[1], ("bytes_read".to_owned(), "0".to_owned()));
+        assert_eq!(event[2], ("offset".to_owned(), "0".to_owned()));
+        assert_eq!(event[3].0, "inner_type");
+        assert!(event[3].1.contains("ClosureReader"));
+        assert_eq!(event[4], ("error".to_owned(), "foo".to_owned()));
+    }
+
+    #[test]
+    fn read_error() {
+        let mut reader = ReadToCtrlZ::with_tracing(ClosureReader(|_: &mut [u8]| {
+            Err(Error::new(ErrorKind::Other, "foo"))
+        }));
+
+        assert_eq!(
+            assert_err!(reader.read(&mut [0; 4])).kind(),
+            ErrorKind::Other
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use find_ctrl_z;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Result;
+use ReadToCtrlZ;
+
+/// A reader returned by [`ReadToCtrlZ::transparent()`], either passing through the inner reader
+/// unchanged or reading it until a `0x1A` byte.
+///
+/// [`ReadToCtrlZ::transparent()`]: crate::ReadToCtrlZ::transparent()
+pub enum Transparent<R> {
+    /// No `0x1A` byte was found in the inner reader's initial buffer, so it is read directly.
+    Inner(R),
+    /// A `0x1A` byte was found in the inner reader's initial buffer, so it is wrapped.
+    Wrapped(ReadToCtrlZ<R>),
+}
+
+impl<R> ReadToCtrlZ<R>
+where
+    R: BufRead,
+{
+    /// Creates a reader that only wraps the provided reader if its initial buffer contains a
+    /// `0x1A` byte.
+    ///
+    /// The inner reader's buffer is filled and scanned once. If it contains no `0x1A` byte, the
+    /// inner reader is returned directly as [`Transparent::Inner`], avoiding the cost of scanning
+    /// every subsequent read. Otherwise, it is wrapped in a `ReadToCtrlZ` as
+    /// [`Transparent::Wrapped`].
+    ///
+    /// Only the initial buffer is scanned, so a `0x1A` byte beyond it is passed through as a
+    /// regular byte. This is intended for inputs whose `0x1A` byte, if any, is known to be within
+    /// the first buffer, such as small files read through a [`BufReader`] at least as large as the
+    /// file. If the inner reader is empty, it is returned directly.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::Transparent;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::transparent(&b"foo\x1abar"[..]).unwrap();
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.is_wrapped());
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo");
+    /// ```
+    ///
+    /// [`Transparent::Inner`]: crate::Transparent::Inner
+    /// [`Transparent::Wrapped`]: crate::Transparent::Wrapped
+    /// [`BufReader`]: std::io::BufReader
+    pub fn transparent(mut inner: R) -> Result<Transparent<R>> {
+        let found = find_ctrl_z(try!(inner.fill_buf())).is_some();
+        if found {
+            Ok(Transparent::Wrapped(ReadToCtrlZ::new(inner)))
+        } else {
+            Ok(Transparent::Inner(inner))
+        }
+    }
+}
+
+impl<R> Transparent<R> {
+    /// Returns whether the inner reader was wrapped in a `ReadToCtrlZ`.
+    pub fn is_wrapped(&self) -> bool {
+        match *self {
+            Transparent::Inner(_) => false,
+            Transparent::Wrapped(_) => true,
+        }
+    }
+}
+
+impl<R> Read for Transparent<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match *self {
+            Transparent::Inner(ref mut inner) => inner.read(buf),
+            Transparent::Wrapped(ref mut inner) => inner.read(buf),
+        }
+    }
+}
+
+impl<R> BufRead for Transparent<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        match *self {
+            Transparent::Inner(ref mut inner) => inner.fill_buf(),
+            Transparent::Wrapped(ref mut inner) => inner.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match *self {
+            Transparent::Inner(ref mut inner) => inner.consume(amount),
+            Transparent::Wrapped(ref mut inner) => inner.consume(amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transparent;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Read;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn without_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = assert_ok!(ReadToCtrlZ::transparent(b"foobar" as &[u8]));
+
+        assert!(!reader.is_wrapped());
+        assert_ok_eq!(reader.read_to_string(&mut output), 6);
+        assert_eq!(output, "foobar");
+    }
+
+    #[test]
+    fn with_ctrl_z() {
+        let mut output = String::new();
+        let mut reader = assert_ok!(ReadToCtrlZ::transparent(b"foo\x1abar" as &[u8]));
+
+        assert!(reader.is_wrapped());
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn buf_read() {
+        let mut reader = assert_ok!(ReadToCtrlZ::transparent(b"foo\x1abar" as &[u8]));
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn empty() {
+        let reader = assert_ok!(ReadToCtrlZ::transparent(b"" as &[u8]));
+
+        match reader {
+            Transparent::Inner(inner) => assert_eq!(inner, b""),
+            Transparent::Wrapped(_) => panic!("empty reader was wrapped"),
+        }
+    }
+
+    #[test]
+    fn ctrl_z_beyond_initial_buffer() {
+        let mut output = String::new();
+        let inner = BufReader::with_capacity(4, b"foobar\x1abaz" as &[u8]);
+        let mut reader = assert_ok!(ReadToCtrlZ::transparent(inner));
+
+        // Only the initial buffer is scanned, so the `0x1A` byte is passed through.
+        assert!(!reader.is_wrapped());
+        assert_ok_eq!(reader.read_to_string(&mut output), 10);
+        assert_eq!(output, "foobar\x1abaz");
+    }
+}
@@ -0,0 +1,465 @@
+use std::error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io::Error;
+use std::io::ErrorKind;
+use ReadToCtrlZ;
+use TerminatorSet;
+
+/// The bytes allowed in the logical content when [validating text].
+///
+/// [validating text]: crate::ReadToCtrlZ::validate_text()
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TextProfile {
+    /// Printable ASCII (`0x20` through `0x7E`), plus `\t`, `\r`, and `\n`.
+    Ascii,
+    /// Printable ASCII, `\t`, `\r`, and `\n`, plus every byte from `0x80` through `0xFF`, as used
+    /// by 8-bit code pages such as CP437.
+    Extended,
+    /// Only the bytes contained in the provided list.
+    Custom(Vec<u8>),
+}
+
+impl TextProfile {
+    /// Returns the set of bytes allowed by this profile.
+    fn allowed(&self) -> TerminatorSet {
+        let mut allowed = TerminatorSet::new();
+        match *self {
+            TextProfile::Ascii | TextProfile::Extended => {
+                for byte in 0x20..0x7f {
+                    allowed.insert(byte);
+                }
+                for &byte in b"\t\r\n" {
+                    allowed.insert(byte);
+                }
+                if *self == TextProfile::Extended {
+                    for byte in 0x80..0x100 {
+                        allowed.insert(byte as u8);
+                    }
+                }
+            }
+            TextProfile::Custom(ref bytes) => {
+                for &byte in bytes {
+                    allowed.insert(byte);
+                }
+            }
+        }
+        allowed
+    }
+}
+
+/// A disallowed byte found while [validating text].
+///
+/// When [failing on violations](crate::ReadToCtrlZ::fail_on_violation()), this is returned wrapped
+/// in an [`Error`] of kind [`ErrorKind::InvalidInput`].
+///
+/// [validating text]: crate::ReadToCtrlZ::validate_text()
+/// [`Error`]: std::io::Error
+/// [`ErrorKind::InvalidInput`]: std::io::ErrorKind::InvalidInput
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Violation {
+    /// The offset of the byte within the delivered bytes.
+    offset: u64,
+    /// The disallowed byte.
+    byte: u8,
+}
+
+impl Violation {
+    /// Returns the offset of the disallowed byte within the delivered bytes.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the disallowed byte.
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "disallowed byte 0x{:02X} at offset {}",
+            self.byte, self.offset
+        )
+    }
+}
+
+impl error::Error for Violation {
+    fn description(&self) -> &str {
+        "disallowed byte"
+    }
+}
+
+/// The state of text validation.
+pub struct Validator {
+    /// The bytes allowed in the logical content.
+    allowed: TerminatorSet,
+    /// The disallowed bytes found so far.
+    violations: Vec<Violation>,
+    /// Whether the first violation is returned as an error.
+    fail_fast: bool,
+    /// Whether or not reading has been stopped by a violation.
+    stopped: bool,
+    /// The offset up to which bytes have been checked, so bytes returned by repeated calls to
+    /// `fill_buf()` are only recorded once.
+    checked: u64,
+}
+
+impl Validator {
+    /// Returns the set of allowed bytes.
+    pub fn allowed(&self) -> TerminatorSet {
+        self.allowed
+    }
+
+    /// Returns whether `byte` is allowed.
+    pub fn allows(&self, byte: u8) -> bool {
+        self.allowed.contains(byte)
+    }
+
+    /// Returns whether the first violation is returned as an error.
+    pub fn fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    /// Returns whether reading has been stopped by a violation.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Records `byte` at `offset` as a violation, unless it has already been checked.
+    pub fn record(&mut self, offset: u64, byte: u8) {
+        if offset >= self.checked {
+            self.violations.push(Violation {
+                offset: offset,
+                byte: byte,
+            });
+            self.checked = offset + 1;
+        }
+    }
+
+    /// Marks every byte before `offset` as checked.
+    pub fn check_to(&mut self, offset: u64) {
+        if offset > self.checked {
+            self.checked = offset;
+        }
+    }
+
+    /// Records `byte` at `offset` as a violation and stops reading, returning the error to be
+    /// returned.
+    pub fn fail(&mut self, offset: u64, byte: u8) -> Error {
+        self.record(offset, byte);
+        self.stopped = true;
+        Error::new(
+            ErrorKind::InvalidInput,
+            Violation {
+                offset: offset,
+                byte: byte,
+            },
+        )
+    }
+
+    /// Clears all recorded violations, leaving the configuration intact.
+    pub fn reset(&mut self) {
+        self.violations.clear();
+        self.stopped = false;
+        self.checked = 0;
+    }
+}
+
+impl<R> ReadToCtrlZ<R> {
+    /// Validates that the delivered bytes only contain bytes allowed by `profile`.
+    ///
+    /// Reading proceeds as usual, but every delivered byte not allowed by `profile` is recorded as
+    /// a [`Violation`], which can be retrieved using [`violations()`](ReadToCtrlZ::violations())
+    /// once reading has finished. Bytes are checked in the same pass that searches for the `0x1A`
+    /// byte, so the input is only scanned once. The `0x1A` byte and anything following it are
+    /// never checked.
+    ///
+    /// To stop reading at the first violation instead, use
+    /// [`fail_on_violation()`](ReadToCtrlZ::fail_on_violation()).
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::TextProfile;
+    /// use std::io::Read;
+    ///
+    /// let mut reader =
+    ///     ReadToCtrlZ::new(&b"foo\x07bar\r\n\x1a\x00"[..]).validate_text(TextProfile::Ascii);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_ok());
+    /// assert_eq!(output, "foo\x07bar\r\n");
+    /// assert_eq!(reader.violations().len(), 1);
+    /// assert_eq!(reader.violations()[0].offset(), 3);
+    /// assert_eq!(reader.violations()[0].byte(), b'\x07');
+    /// ```
+    ///
+    /// [`Violation`]: crate::Violation
+    pub fn validate_text(mut self, profile: TextProfile) -> Self {
+        let fail_fast = self.validator.as_ref().map_or(false, Validator::fail_fast);
+        self.validator = Some(Validator {
+            allowed: profile.allowed(),
+            violations: Vec::new(),
+            fail_fast: fail_fast,
+            stopped: false,
+            checked: 0,
+        });
+        self
+    }
+
+    /// Returns an error at the first disallowed byte when [validating text], rather than only
+    /// recording it.
+    ///
+    /// The bytes preceding the disallowed byte are delivered, after which the next read returns
+    /// an error of kind [`ErrorKind::InvalidInput`] wrapping the [`Violation`]. The error is only
+    /// returned once; subsequent reads behave as if the end of the input was reached. This has no
+    /// effect unless text is being validated.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::ReadToCtrlZ;
+    /// use ctrl_z::TextProfile;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = ReadToCtrlZ::new(&b"foo\x07bar\x1a"[..])
+    ///     .validate_text(TextProfile::Ascii)
+    ///     .fail_on_violation(true);
+    /// let mut output = String::new();
+    ///
+    /// assert!(reader.read_to_string(&mut output).is_err());
+    /// assert_eq!(output, "foo");
+    /// ```
+    ///
+    /// [validating text]: ReadToCtrlZ::validate_text()
+    /// [`ErrorKind::InvalidInput`]: std::io::ErrorKind::InvalidInput
+    /// [`Violation`]: crate::Violation
+    pub fn fail_on_violation(mut self, fail: bool) -> Self {
+        if let Some(ref mut validator) = self.validator {
+            validator.fail_fast = fail;
+        }
+        self
+    }
+
+    /// Returns the disallowed bytes found so far when [validating text], in the order they were
+    /// delivered.
+    ///
+    /// This is empty if text is not being validated.
+    ///
+    /// [validating text]: ReadToCtrlZ::validate_text()
+    pub fn violations(&self) -> &[Violation] {
+        match self.validator {
+            Some(ref validator) => &validator.violations,
+            None => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextProfile;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use ReadToCtrlZ;
+    use UntilOutcome;
+
+    #[test]
+    fn ascii_report_only() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"fo\x00o\tbar\xe9\r\n\x1a\x07" as &[u8])
+            .validate_text(TextProfile::Ascii);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 11);
+        assert_eq!(output, b"fo\x00o\tbar\xe9\r\n");
+        let violations = reader
+            .violations()
+            .iter()
+            .map(|violation| (violation.offset(), violation.byte()))
+            .collect::<Vec<_>>();
+        assert_eq!(violations, [(2, b'\x00'), (8, b'\xe9')]);
+    }
+
+    #[test]
+    fn extended_allows_high_bytes() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"caf\xe9\x7f\x1a" as &[u8]).validate_text(TextProfile::Extended);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 5);
+        assert_eq!(reader.violations().len(), 1);
+        assert_eq!(reader.violations()[0].offset(), 4);
+        assert_eq!(reader.violations()[0].byte(), b'\x7f');
+    }
+
+    #[test]
+    fn custom_allowlist() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"0110201\x1a" as &[u8])
+            .validate_text(TextProfile::Custom(b"01".to_vec()));
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 7);
+        assert_eq!(reader.violations().len(), 1);
+        assert_eq!(reader.violations()[0].offset(), 4);
+    }
+
+    #[test]
+    fn no_violations() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"foo\r\n\x1a\x00" as &[u8]).validate_text(TextProfile::Ascii);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 5);
+        assert!(reader.violations().is_empty());
+    }
+
+    #[test]
+    fn not_validating() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x00" as &[u8]);
+
+        assert_ok_eq!(reader.read_to_end(&mut output), 4);
+        assert!(reader.violations().is_empty());
+    }
+
+    #[test]
+    fn fail_fast() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x07bar\x1a" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+        let mut output = [0; 16];
+
+        // The bytes preceding the violation are delivered first.
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_eq!(&output[..3], b"foo");
+        let error = assert_err!(reader.read(&mut output));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "disallowed byte 0x07 at offset 3");
+        assert_ok_eq!(reader.read(&mut output), 0);
+        assert_eq!(reader.violations().len(), 1);
+    }
+
+    #[test]
+    fn fail_fast_keeps_buffer() {
+        let mut reader =
+            ReadToCtrlZ::with_buffer(b"foo\x07bar\x1a" as &[u8], Vec::with_capacity(1 << 20))
+                .validate_text(TextProfile::Ascii)
+                .fail_on_violation(true);
+        let mut output = [0; 16];
+
+        assert_ok_eq!(reader.read(&mut output), 3);
+        assert_err!(reader.read(&mut output));
+        assert_eq!(reader.into_buffer().capacity(), 1 << 20);
+    }
+
+    #[test]
+    fn fail_fast_first_byte() {
+        let mut reader = ReadToCtrlZ::new(b"\x00foo" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+
+        assert_err!(reader.read(&mut [0; 16]));
+        assert_eq!(reader.violations()[0].offset(), 0);
+    }
+
+    #[test]
+    fn fail_fast_without_violation() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"foo\x1a\x00" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+
+        assert_ok_eq!(reader.read_to_string(&mut output), 3);
+        assert_eq!(output, "foo");
+    }
+
+    #[test]
+    fn buf_read_report_only() {
+        let inner = BufReader::with_capacity(3, b"fo\x00o\x01ba\x1a\x02" as &[u8]);
+        let mut reader = ReadToCtrlZ::new(inner).validate_text(TextProfile::Ascii);
+
+        // Filling the buffer repeatedly does not record violations more than once.
+        assert_ok_eq!(reader.fill_buf(), b"fo\x00");
+        assert_ok_eq!(reader.fill_buf(), b"fo\x00");
+        reader.consume(1);
+        let mut output = Vec::new();
+        assert_ok_eq!(reader.read_until(b'\n', &mut output), 6);
+        assert_eq!(output, b"o\x00o\x01ba");
+        let offsets = reader
+            .violations()
+            .iter()
+            .map(|violation| violation.offset())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, [2, 4]);
+    }
+
+    #[test]
+    fn buf_read_fail_fast() {
+        let mut reader = ReadToCtrlZ::new(b"foo\x07bar\x1a" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+
+        assert_ok_eq!(reader.fill_buf(), b"foo");
+        reader.consume(3);
+        let error = assert_err!(reader.fill_buf());
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_ok_eq!(reader.fill_buf(), b"");
+    }
+
+    #[test]
+    fn read_until_or_marker_fail_fast() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(b"a\x07b\n" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+
+        let error = assert_err!(reader.read_until_or_marker(b'\n', &mut output));
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(output, b"a");
+        assert_eq!(reader.violations().len(), 1);
+        assert_eq!(reader.violations()[0].offset(), 1);
+    }
+
+    #[test]
+    fn read_until_or_marker_report_only() {
+        let mut output = Vec::new();
+        let mut reader =
+            ReadToCtrlZ::new(b"a\x07b\nc\x1a\x00" as &[u8]).validate_text(TextProfile::Ascii);
+
+        assert_ok_eq!(
+            reader.read_until_or_marker(b'\n', &mut output),
+            UntilOutcome::Delimiter
+        );
+        assert_eq!(output, b"a\x07b\n");
+        assert_eq!(reader.violations().len(), 1);
+        assert_eq!(reader.violations()[0].offset(), 1);
+    }
+
+    #[test]
+    fn read_line_limited_fail_fast() {
+        let mut output = String::new();
+        let mut reader = ReadToCtrlZ::new(b"a\x07b\n" as &[u8])
+            .validate_text(TextProfile::Ascii)
+            .fail_on_violation(true);
+
+        assert_err!(reader.read_line_limited(&mut output, 16));
+        assert_eq!(reader.violations().len(), 1);
+    }
+
+    #[test]
+    fn rewind_clears_violations() {
+        let mut output = Vec::new();
+        let mut reader = ReadToCtrlZ::new(::std::io::Cursor::new(b"\x00".to_vec()))
+            .validate_text(TextProfile::Ascii);
+        assert_ok!(reader.read_to_end(&mut output));
+        assert_eq!(reader.violations().len(), 1);
+
+        assert_ok!(reader.rewind());
+
+        assert!(reader.violations().is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+use std::error;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::io::Write;
+use std::result;
+
+/// A writer to write data terminated by a `0x1A` byte.
+///
+/// Bytes are written to the inner writer unchanged until the writer is sealed using
+/// [`seal()`](WriteToCtrlZ::seal()), which writes the `0x1A` byte. Writing to a sealed writer
+/// fails, unless a new segment is started using [`new_segment()`](WriteToCtrlZ::new_segment()).
+/// This allows writing multiple `0x1A`-delimited segments through a single writer.
+///
+/// # Example
+/// ```
+/// use ctrl_z::WriteToCtrlZ;
+/// use std::io::Write;
+///
+/// let mut writer = WriteToCtrlZ::new(Vec::new());
+///
+/// assert!(writer.write_all(b"foo").is_ok());
+/// assert!(writer.seal().is_ok());
+/// assert!(writer.new_segment().is_ok());
+/// assert!(writer.write_all(b"bar").is_ok());
+/// assert!(writer.seal().is_ok());
+///
+/// assert_eq!(writer.into_inner().unwrap(), b"foo\x1abar\x1a");
+/// ```
+pub struct WriteToCtrlZ<W> {
+    /// The internal writer being written to.
+    inner: W,
+    /// Whether or not the `0x1A` byte has been written for the current segment.
+    sealed: bool,
+}
+
+impl<W> WriteToCtrlZ<W> {
+    /// Creates a new `WriteToCtrlZ`, wrapping the provided writer.
+    pub fn new(inner: W) -> Self {
+        WriteToCtrlZ {
+            inner: inner,
+            sealed: false,
+        }
+    }
+
+    /// Returns whether the current segment has been sealed.
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    /// Starts a new segment after the current segment has been sealed.
+    ///
+    /// Nothing is written to the inner writer. Subsequent writes are written following the
+    /// previous `0x1A` byte.
+    ///
+    /// # Errors
+    /// Returns an error of kind [`InvalidInput`](ErrorKind::InvalidInput) if the current segment
+    /// has not been sealed, as the segments would otherwise not be delimited.
+    pub fn new_segment(&mut self) -> Result<()> {
+        if !self.sealed {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "current segment is not sealed",
+            ));
+        }
+        self.sealed = false;
+        Ok(())
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
+
+impl<W> WriteToCtrlZ<W>
+where
+    W: Write,
+{
+    /// Seals the current segment by writing the `0x1A` byte.
+    ///
+    /// Sealing an already sealed segment writes nothing.
+    pub fn seal(&mut self) -> Result<()> {
+        if !self.sealed {
+            try!(self.inner.write_all(b"\x1a"));
+            self.sealed = true;
+        }
+        Ok(())
+    }
+
+    /// Seals the current segment and flushes the inner writer, returning the inner writer.
+    ///
+    /// # Errors
+    /// If writing the `0x1A` byte or flushing fails, an [`IntoInnerError`] is returned, from which
+    /// the `WriteToCtrlZ` can be recovered to retry. Whether the `0x1A` byte was already written
+    /// is reported by [`IntoInnerError::is_sealed()`]; if it was, finishing again only retries the
+    /// flush.
+    ///
+    /// # Example
+    /// ```
+    /// use ctrl_z::WriteToCtrlZ;
+    /// use std::io::Write;
+    ///
+    /// let mut writer = WriteToCtrlZ::new(Vec::new());
+    ///
+    /// assert!(writer.write_all(b"foo").is_ok());
+    /// assert_eq!(writer.finish().unwrap(), b"foo\x1a");
+    /// ```
+    ///
+    /// [`IntoInnerError`]: crate::IntoInnerError
+    /// [`IntoInnerError::is_sealed()`]: crate::IntoInnerError::is_sealed()
+    pub fn finish(mut self) -> result::Result<W, IntoInnerError<W>> {
+        if let Err(error) = self.seal() {
+            return Err(IntoInnerError {
+                writer: self,
+                error: error,
+            });
+        }
+        self.into_inner()
+    }
+
+    /// Flushes the inner writer, returning it.
+    ///
+    /// The current segment is not sealed automatically; use [`finish()`](WriteToCtrlZ::finish())
+    /// to seal it as well.
+    ///
+    /// # Errors
+    /// If flushing fails, an [`IntoInnerError`] is returned, from which the `WriteToCtrlZ` can be
+    /// recovered.
+    ///
+    /// [`IntoInnerError`]: crate::IntoInnerError
+    pub fn into_inner(mut self) -> result::Result<W, IntoInnerError<W>> {
+        match self.inner.flush() {
+            Ok(()) => Ok(self.inner),
+            Err(error) => Err(IntoInnerError {
+                writer: self,
+                error: error,
+            }),
+        }
+    }
+}
+
+/// An error returned by [`WriteToCtrlZ::finish()`] and [`WriteToCtrlZ::into_inner()`].
+///
+/// This mirrors [`std::io::IntoInnerError`]: it combines the error that occurred with the writer,
+/// so that the writer can be recovered and the operation retried.
+///
+/// [`WriteToCtrlZ::finish()`]: crate::WriteToCtrlZ::finish()
+/// [`WriteToCtrlZ::into_inner()`]: crate::WriteToCtrlZ::into_inner()
+pub struct IntoInnerError<W> {
+    /// The writer that failed.
+    writer: WriteToCtrlZ<W>,
+    /// The error that occurred.
+    error: Error,
+}
+
+impl<W> IntoInnerError<W> {
+    /// Returns the error that occurred.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Returns whether the `0x1A` byte had been written before the error occurred.
+    pub fn is_sealed(&self) -> bool {
+        self.writer.is_sealed()
+    }
+
+    /// Returns the writer that failed, so the operation can be retried.
+    pub fn into_inner(self) -> WriteToCtrlZ<W> {
+        self.writer
+    }
+
+    /// Returns the error that occurred, discarding the writer.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl<W> Debug for IntoInnerError<W> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        // The writer is omitted, so that it is not required to implement `Debug`.
+        write!(
+            formatter,
+            "IntoInnerError {{ error: {:?}, sealed: {:?} }}",
+            self.error,
+            self.is_sealed()
+        )
+    }
+}
+
+impl<W> Display for IntoInnerError<W> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.error)
+    }
+}
+
+impl<W> error::Error for IntoInnerError<W> {
+    fn description(&self) -> &str {
+        self.error.description()
+    }
+}
+
+impl<W> From<IntoInnerError<W>> for io::Error {
+    fn from(error: IntoInnerError<W>) -> Self {
+        error.error
+    }
+}
+
+impl<W> Write for WriteToCtrlZ<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.sealed {
+            return Err(Error::new(ErrorKind::Other, "writer is sealed"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WriteToCtrlZ;
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Result;
+    use std::io::Write;
+    use ReadToCtrlZ;
+
+    #[test]
+    fn seal() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+
+        assert_ok!(writer.write_all(b"foo"));
+        assert_ok!(writer.seal());
+        assert!(writer.is_sealed());
+        assert_eq!(writer.get_ref(), b"foo\x1a");
+    }
+
+    #[test]
+    fn seal_twice() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+
+        assert_ok!(writer.seal());
+        assert_ok!(writer.seal());
+        assert_eq!(writer.get_ref(), b"\x1a");
+    }
+
+    #[test]
+    fn write_after_seal() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+        assert_ok!(writer.seal());
+
+        assert_eq!(assert_err!(writer.write(b"foo")).kind(), ErrorKind::Other);
+        assert_eq!(writer.get_ref(), b"\x1a");
+    }
+
+    #[test]
+    fn new_segment() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+
+        assert_ok!(writer.write_all(b"foo"));
+        assert_ok!(writer.seal());
+        assert_ok!(writer.new_segment());
+        assert!(!writer.is_sealed());
+        assert_ok!(writer.write_all(b"bar"));
+        assert_ok!(writer.seal());
+        assert_eq!(writer.get_ref(), b"foo\x1abar\x1a");
+    }
+
+    #[test]
+    fn new_segment_unsealed() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+        assert_ok!(writer.write_all(b"foo"));
+
+        assert_eq!(
+            assert_err!(writer.new_segment()).kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(writer.get_ref(), b"foo");
+    }
+
+    #[test]
+    fn read_segments() {
+        let mut writer = WriteToCtrlZ::new(Vec::new());
+        assert_ok!(writer.write_all(b"foo"));
+        assert_ok!(writer.seal());
+        assert_ok!(writer.new_segment());
+        assert_ok!(writer.write_all(b"bar"));
+        assert_ok!(writer.seal());
+        let data = assert_ok!(writer.into_inner());
+        let mut output = String::new();
+
+        assert_ok_eq!(ReadToCtrlZ::new(&data[4..]).read_to_string(&mut output), 3);
+        assert_eq!(output, "bar");
+    }
+
+    /// A writer that fails once when writing the `0x1A` byte or when flushing.
+    #[derive(Debug)]
+    struct Failing {
+        written: Vec<u8>,
+        fail_marker: bool,
+        fail_flush: bool,
+        flushed: bool,
+    }
+
+    impl Failing {
+        fn new(fail_marker: bool, fail_flush: bool) -> Self {
+            Failing {
+                written: Vec::new(),
+                fail_marker: fail_marker,
+                fail_flush: fail_flush,
+                flushed: false,
+            }
+        }
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            if self.fail_marker && buf.contains(&b'\x1a') {
+                self.fail_marker = false;
+                return Err(Error::new(ErrorKind::Other, "marker"));
+            }
+            self.written.extend(buf.iter().cloned());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            if self.fail_flush {
+                self.fail_flush = false;
+                return Err(Error::new(ErrorKind::Other, "flush"));
+            }
+            self.flushed = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn finish() {
+        let mut writer = WriteToCtrlZ::new(Failing::new(false, false));
+        assert_ok!(writer.write_all(b"foo"));
+
+        let inner = assert_ok!(writer.finish());
+        assert_eq!(inner.written, b"foo\x1a");
+        assert!(inner.flushed);
+    }
+
+    #[test]
+    fn finish_marker_fails() {
+        let mut writer = WriteToCtrlZ::new(Failing::new(true, false));
+        assert_ok!(writer.write_all(b"foo"));
+
+        let error = assert_err!(writer.finish());
+        assert_eq!(error.error().kind(), ErrorKind::Other);
+        assert!(!error.is_sealed());
+        assert!(!error.into_inner().get_ref().flushed);
+    }
+
+    #[test]
+    fn finish_marker_fails_retry() {
+        let writer = WriteToCtrlZ::new(Failing::new(true, false));
+
+        let writer = assert_err!(writer.finish()).into_inner();
+        let inner = assert_ok!(writer.finish());
+        assert_eq!(inner.written, b"\x1a");
+        assert!(inner.flushed);
+    }
+
+    #[test]
+    fn finish_flush_fails() {
+        let mut writer = WriteToCtrlZ::new(Failing::new(false, true));
+        assert_ok!(writer.write_all(b"foo"));
+
+        let error = assert_err!(writer.finish());
+        assert_eq!(error.to_string(), "flush");
+        assert!(error.is_sealed());
+
+        // Retrying does not write the `0x1A` byte again.
+        let inner = assert_ok!(error.into_inner().finish());
+        assert_eq!(inner.written, b"foo\x1a");
+        assert!(inner.flushed);
+    }
+
+    #[test]
+    fn into_inner_flush_fails() {
+        let mut writer = WriteToCtrlZ::new(Failing::new(false, true));
+        assert_ok!(writer.write_all(b"foo"));
+
+        let error = assert_err!(writer.into_inner());
+        assert!(!error.is_sealed());
+        assert_eq!(error.into_error().kind(), ErrorKind::Other);
+    }
+}
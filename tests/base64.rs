@@ -0,0 +1,39 @@
+//! Reads CP/M files that were base64-encoded for transmission, such as email attachments.
+
+extern crate base64;
+extern crate ctrl_z;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ctrl_z::ReadToCtrlZ;
+use std::io::BufRead;
+use std::io::Cursor;
+use std::io::Read;
+
+/// `b"hello\x1aworld"`, base64-encoded.
+const ENCODED: &'static str = "aGVsbG8ad29ybGQ=";
+
+#[test]
+fn decoded_read() {
+    let decoded = STANDARD.decode(ENCODED).unwrap();
+    assert_eq!(decoded, b"hello\x1aworld");
+    let mut output = Vec::new();
+
+    assert_eq!(
+        ReadToCtrlZ::new(Cursor::new(decoded))
+            .read_to_end(&mut output)
+            .unwrap(),
+        5
+    );
+    assert_eq!(output, b"hello");
+}
+
+#[test]
+fn decoded_buf_read() {
+    let mut reader = ReadToCtrlZ::new(Cursor::new(STANDARD.decode(ENCODED).unwrap()));
+
+    assert_eq!(reader.fill_buf().unwrap(), b"hello");
+    reader.consume(5);
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+    assert_eq!(reader.terminator(), Some(b'\x1a'));
+}
@@ -0,0 +1,44 @@
+//! Reads legacy files that were zstd-compressed for archival, decompressing them while reading.
+
+extern crate ctrl_z;
+extern crate zstd;
+
+use ctrl_z::ReadToCtrlZ;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use zstd::stream::read::Decoder;
+
+/// The decompressed contents of the archived file.
+const CONTENTS: &'static [u8] = b"content\x1amore";
+
+/// Returns `CONTENTS`, zstd-compressed at the default level.
+fn compressed() -> Vec<u8> {
+    zstd::encode_all(CONTENTS, 0).unwrap()
+}
+
+#[test]
+fn decoder_read() {
+    let compressed = compressed();
+    let mut output = Vec::new();
+
+    assert_eq!(
+        ReadToCtrlZ::new(Decoder::new(&compressed[..]).unwrap())
+            .read_to_end(&mut output)
+            .unwrap(),
+        7
+    );
+    assert_eq!(output, b"content");
+}
+
+#[test]
+fn decoder_buf_read() {
+    let compressed = compressed();
+    let decoder = Decoder::new(&compressed[..]).unwrap();
+    let mut reader = ReadToCtrlZ::new(BufReader::new(decoder));
+
+    assert_eq!(reader.fill_buf().unwrap(), b"content");
+    reader.consume(7);
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+    assert_eq!(reader.terminator(), Some(b'\x1a'));
+}